@@ -0,0 +1,127 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::PathBuf,
+};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{Error, Result, Token};
+
+/// An on-disk cache of GET responses, keyed by endpoint, token and URL and revalidated using
+/// ETags.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    dir: PathBuf,
+    /// Hash of the endpoint and token, so that different users never share entries.
+    scope: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct CacheEntry {
+    pub etag: String,
+    pub body: serde_json::Value,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf, endpoint: &Url, token: &Token) -> Result<Self> {
+        fs::create_dir_all(&dir).map_err(|source| Error::Unknown {
+            message: format!("Could not create cache directory `{}`", dir.display()),
+            source: Box::new(source),
+        })?;
+        let scope = Sha256::new()
+            .chain_update(endpoint.as_str())
+            .chain_update([0])
+            .chain_update(&token.0)
+            .finalize()
+            .to_vec();
+        Ok(Self { dir, scope })
+    }
+
+    /// Get the cached entry for the given URL, if any. Unreadable entries are treated as missing.
+    pub fn get(&self, url: &Url) -> Option<CacheEntry> {
+        let path = self.entry_path(url);
+        let file = File::open(&path).ok()?;
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(entry) => Some(entry),
+            Err(error) => {
+                debug!(
+                    "Ignoring invalid cache entry `{}`: {}",
+                    path.display(),
+                    error
+                );
+                None
+            }
+        }
+    }
+
+    /// Store the response body for the given URL. Failing to write to the cache is not fatal.
+    ///
+    /// The entry is written to a uniquely named temporary file which is then renamed into place,
+    /// so that concurrent readers and writers never see a partially written entry.
+    pub fn put(&self, url: &Url, etag: String, body: &[u8]) {
+        let body = match serde_json::from_slice(body) {
+            Ok(body) => body,
+            Err(_) => return,
+        };
+        let path = self.entry_path(url);
+        let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        let result = File::create(&temp_path)
+            .map_err(serde_json::Error::io)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                serde_json::to_writer(&mut writer, &CacheEntry { etag, body })?;
+                writer.flush().map_err(serde_json::Error::io)
+            })
+            .and_then(|()| fs::rename(&temp_path, &path).map_err(serde_json::Error::io));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&temp_path);
+            warn!(
+                "Could not write cache entry `{}`: {}",
+                path.display(),
+                error
+            );
+        }
+    }
+
+    fn entry_path(&self, url: &Url) -> PathBuf {
+        let key = Sha256::new()
+            .chain_update(&self.scope)
+            .chain_update(url.as_str())
+            .finalize();
+        self.dir.join(format!("{key:x}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("reinfer-cache-test-{}", std::process::id()));
+        let endpoint = Url::parse("https://reinfer.dev").unwrap();
+        let cache = ResponseCache::new(dir.clone(), &endpoint, &Token("token".to_owned())).unwrap();
+        let url = Url::parse("https://reinfer.dev/api/v1/sources").unwrap();
+
+        assert!(cache.get(&url).is_none());
+        cache.put(
+            &url,
+            "\"abc\"".to_owned(),
+            br#"{"status": "ok", "sources": []}"#,
+        );
+        let entry = cache.get(&url).unwrap();
+        assert_eq!(entry.etag, "\"abc\"");
+        assert_eq!(entry.body["sources"], serde_json::json!([]));
+
+        // Another user of the same endpoint doesn't see the entry
+        let other_cache =
+            ResponseCache::new(dir.clone(), &endpoint, &Token("other".to_owned())).unwrap();
+        assert!(other_cache.get(&url).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
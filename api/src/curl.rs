@@ -0,0 +1,75 @@
+use reqwest::{blocking::Request, header};
+
+/// Whether to print an equivalent `curl` command for each request made by the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrintCurl {
+    /// Don't print curl commands.
+    #[default]
+    No,
+    /// Print a curl command to stderr before sending each request.
+    Yes,
+    /// Print a curl command to stderr instead of sending each request.
+    Only,
+}
+
+/// Build a `curl` command equivalent to the given request. The API token is replaced with a
+/// `$REINFER_TOKEN` shell variable.
+pub(crate) fn to_curl_command(request: &Request) -> String {
+    let mut command = format!(
+        "curl -X {} {}",
+        request.method(),
+        shell_quote(request.url().as_str())
+    );
+
+    for (name, value) in request.headers() {
+        let header = if name == header::AUTHORIZATION {
+            format!(r#""{name}: Bearer $REINFER_TOKEN""#)
+        } else {
+            shell_quote(&format!(
+                "{name}: {}",
+                String::from_utf8_lossy(value.as_bytes())
+            ))
+        };
+        command.push_str(&format!(" \\\n  -H {header}"));
+    }
+
+    if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+        command.push_str(&format!(
+            " \\\n  --data-raw {}",
+            shell_quote(&String::from_utf8_lossy(body))
+        ));
+    }
+    command
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'\''"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{blocking::Client, Method};
+    use serde_json::json;
+
+    #[test]
+    fn test_to_curl_command() {
+        let request = Client::new()
+            .request(Method::PUT, "https://reinfer.dev/api/v1/sources/a/b")
+            .query(&[("after", "x")])
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .json(&json!({"title": "it's"}))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            to_curl_command(&request),
+            concat!(
+                "curl -X PUT 'https://reinfer.dev/api/v1/sources/a/b?after=x' \\\n",
+                "  -H \"authorization: Bearer $REINFER_TOKEN\" \\\n",
+                "  -H 'content-type: application/json' \\\n",
+                r#"  --data-raw '{"title":"it'\''s"}'"#
+            )
+        );
+    }
+}
@@ -6,10 +6,28 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("API request failed with {}: {}", status_code, message)]
+    #[error(
+        "API request failed with {}: {}{}",
+        status_code,
+        message,
+        request_id
+            .as_ref()
+            .map(|request_id| format!(
+                " (request id: {request_id}, include it when contacting support)"
+            ))
+            .unwrap_or_default()
+    )]
     Api {
         status_code: StatusCode,
         message: String,
+        request_id: Option<String>,
+    },
+
+    #[error("Request failed after {} retries", retries)]
+    RetriesExhausted {
+        retries: u8,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
     },
 
     #[error("Invalid endpoint: '{}'", endpoint)]
@@ -57,6 +75,15 @@ pub enum Error {
     #[error("Expected <owner>/<name>, got: {}", name)]
     BadBucketName { name: String },
 
+    #[error(
+        "Expected a transform tag of the form <name>.<version>.<model>, got: {}",
+        tag
+    )]
+    BadTransformTag { tag: String },
+
+    #[error("Expected a valid user agent header value, got: {}", user_agent)]
+    BadUserAgent { user_agent: String },
+
     #[error("Expected a valid bucket type, got: {}", bucket_type)]
     BadBucketType { bucket_type: String },
 
@@ -64,7 +91,7 @@ pub enum Error {
     BadTenantQuotaKind { tenant_quota_kind: String },
 
     #[error("Could not parse JSON response.")]
-    BadJsonResponse(#[source] reqwest::Error),
+    BadJsonResponse(#[source] serde_json::Error),
 
     #[error(
         "Status code {} inconsistent with response payload: {}",
@@ -85,9 +112,29 @@ pub enum Error {
         source: reqwest::Error,
     },
 
+    #[error(
+        "{} request to `{}` was not sent, only its curl command was printed",
+        method,
+        url
+    )]
+    RequestNotSent { method: String, url: String },
+
+    #[error("Response body is larger than the maximum of {} bytes.", max_bytes)]
+    ResponseTooLarge { max_bytes: u64 },
+
     #[error("An unknown error has occurred: {}", message)]
     Unknown {
         message: String,
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
 }
+
+impl Error {
+    /// Whether this is an API error for a resource which doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::Api { status_code, .. } if *status_code == StatusCode::NOT_FOUND
+        )
+    }
+}
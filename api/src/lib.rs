@@ -1,14 +1,23 @@
 #![deny(clippy::all)]
+mod cache;
+mod curl;
 mod error;
+mod proxy;
+mod redact;
 pub mod resources;
 pub mod retry;
 
 use chrono::{DateTime, Utc};
-use http::{header::ACCEPT, Method};
-use log::debug;
+use http::{
+    header::{ACCEPT, ETAG, IF_NONE_MATCH},
+    Method,
+};
+use log::{debug, log_enabled, trace, warn, Level};
 use once_cell::sync::Lazy;
 use reqwest::{
-    blocking::{multipart::Form, Client as HttpClient, Response as HttpResponse},
+    blocking::{
+        multipart::Form, Client as HttpClient, Request, RequestBuilder, Response as HttpResponse,
+    },
     header::{self, HeaderMap, HeaderValue},
     IntoUrl, Proxy, Result as ReqwestResult,
 };
@@ -45,7 +54,8 @@ use serde_json::json;
 use std::{
     cell::Cell,
     fmt::{Debug, Display},
-    io::Read,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -96,9 +106,16 @@ use crate::resources::{
     EmptySuccess, Response,
 };
 
-use crate::retry::{Retrier, RetryConfig};
+use crate::{
+    cache::ResponseCache,
+    curl::to_curl_command,
+    proxy::ProxyExclusions,
+    redact::body_for_trace,
+    retry::{Retrier, RetryConfig, RetryOutcome},
+};
 
 pub use crate::{
+    curl::PrintCurl,
     error::{Error, Result},
     resources::{
         bucket::{
@@ -157,30 +174,40 @@ pub trait SplittableRequest {
         Self: Sized;
 
     fn count(&self) -> usize;
+
+    /// Ids of the items in the request, to report which items failed.
+    fn ids(&self) -> Vec<String>;
 }
 
+/// Configuration for requests which are split into one request per item when a batch fails.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SplitConfig {
+    /// Amount of time to wait between the requests for each item. The wait doubles after each
+    /// failed request, up to `MAX_SPLIT_BACKOFF_FACTOR` times this, and resets on success.
+    pub base_wait: Duration,
+    /// Largest batch to split into requests for each item. Larger batches which fail are counted
+    /// as failed as a whole.
+    pub max_split_size: Option<usize>,
+}
+
+const MAX_SPLIT_BACKOFF_FACTOR: u32 = 32;
+
 pub struct SplitableRequestResponse<ResponseT>
 where
     for<'de> ResponseT: Deserialize<'de> + ReducibleResponse,
 {
     pub response: ResponseT,
     pub num_failed: usize,
+    /// Ids of the items which failed.
+    pub failed_ids: Vec<String>,
 }
 
+/// A response which can be combined with the responses to the other parts of a split request.
+/// There are no default implementations, so that responses with counts don't drop them.
 pub trait ReducibleResponse {
-    fn merge(self, _b: Self) -> Self
-    where
-        Self: std::default::Default,
-    {
-        Default::default()
-    }
+    fn merge(self, b: Self) -> Self;
 
-    fn empty() -> Self
-    where
-        Self: std::default::Default,
-    {
-        Default::default()
-    }
+    fn empty() -> Self;
 }
 
 pub struct Config {
@@ -188,9 +215,33 @@ pub struct Config {
     pub token: Token,
     pub accept_invalid_certificates: bool,
     pub proxy: Option<Url>,
+    /// Hosts, domain suffixes or CIDR blocks which should bypass the proxy, in addition to those
+    /// listed in the `NO_PROXY` environment variable.
+    pub proxy_exclusions: Vec<String>,
     /// Retry settings to use, if any. This will apply to all requests except for POST requests
     /// which are not idempotent (as they cannot be naively retried).
     pub retry_config: Option<RetryConfig>,
+    /// Whether to print an equivalent curl command for each request, and whether to still send it.
+    pub print_curl: PrintCurl,
+    /// Directory in which to cache GET responses. Cached responses are revalidated using ETags.
+    pub cache_dir: Option<PathBuf>,
+    /// How to split batch requests which fail because of invalid items.
+    pub split_config: SplitConfig,
+    /// User agent to send with each request. Defaults to `reinfer-client/<version>`.
+    pub user_agent: Option<String>,
+    /// Maximum number of idle connections to keep open to the API host for reuse. Defaults to no
+    /// limit, so every connection of a burst of concurrent requests is kept alive.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept open for reuse. Defaults to 90 seconds.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Only use HTTP/1.1, for proxies which mishandle HTTP/2.
+    pub http1_only: bool,
+    /// Timeout for requests which transfer attachments or audio, which can take much longer than
+    /// other requests. Defaults to the timeout for all requests.
+    pub attachment_timeout: Option<Duration>,
+    /// Largest response body to read, to guard against misbehaving endpoints. Defaults to
+    /// `DEFAULT_MAX_RESPONSE_BYTES`.
+    pub max_response_bytes: u64,
 }
 
 impl Default for Config {
@@ -200,7 +251,17 @@ impl Default for Config {
             token: Token("".to_owned()),
             accept_invalid_certificates: false,
             proxy: None,
+            proxy_exclusions: Vec::new(),
             retry_config: None,
+            print_curl: PrintCurl::No,
+            cache_dir: None,
+            split_config: SplitConfig::default(),
+            user_agent: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            http1_only: false,
+            attachment_timeout: None,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
         }
     }
 }
@@ -211,6 +272,11 @@ pub struct Client {
     http_client: HttpClient,
     headers: HeaderMap,
     retrier: Option<Retrier>,
+    print_curl: PrintCurl,
+    cache: Option<ResponseCache>,
+    split_config: SplitConfig,
+    attachment_timeout: Option<Duration>,
+    max_response_bytes: u64,
 }
 
 #[derive(Serialize)]
@@ -235,6 +301,8 @@ pub struct GetCommentsIterPageQuery<'a> {
     pub after: Option<&'a Continuation>,
     pub limit: usize,
     pub include_markup: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_thread_properties: bool,
 }
 
 #[derive(Serialize)]
@@ -259,6 +327,10 @@ impl Client {
     pub fn new(config: Config) -> Result<Client> {
         let http_client = build_http_client(&config)?;
         let headers = build_headers(&config)?;
+        let cache = config
+            .cache_dir
+            .map(|dir| ResponseCache::new(dir, &config.endpoint, &config.token))
+            .transpose()?;
         let endpoints = Endpoints::new(config.endpoint)?;
         let retrier = config.retry_config.map(Retrier::new);
         Ok(Client {
@@ -266,6 +338,11 @@ impl Client {
             http_client,
             headers,
             retrier,
+            print_curl: config.print_curl,
+            cache,
+            split_config: config.split_config,
+            attachment_timeout: config.attachment_timeout,
+            max_response_bytes: config.max_response_bytes,
         })
     }
 
@@ -364,6 +441,13 @@ impl Client {
             .quotas)
     }
 
+    /// Get quotas for the given tenant. Requires permission to manage quotas of other tenants.
+    pub fn get_tenant_quotas(&self, tenant_id: &TenantId) -> Result<Vec<Quota>> {
+        Ok(self
+            .get::<_, GetQuotasResponse>(self.endpoints.tenant_quotas(tenant_id)?)?
+            .quotas)
+    }
+
     /// Delete a user.
     pub fn delete_user(&self, user: impl Into<UserIdentifier>) -> Result<()> {
         let UserIdentifier::Id(user_id) = user.into();
@@ -393,6 +477,7 @@ impl Client {
         continuation: Option<&ContinuationKind>,
         to_timestamp: Option<DateTime<Utc>>,
         limit: usize,
+        include_thread_properties: bool,
     ) -> Result<CommentsIterPage> {
         // Comments are returned from the API in increasing order of their
         // `timestamp` field.
@@ -413,6 +498,7 @@ impl Client {
             after,
             limit,
             include_markup: true,
+            include_thread_properties,
         };
         self.get_query(self.endpoints.comments(source_name)?, Some(&query_params))
     }
@@ -754,27 +840,20 @@ impl Client {
         comment_id: &CommentId,
         audio_path: impl AsRef<Path>,
     ) -> Result<()> {
-        let form = Form::new()
-            .file("file", audio_path)
-            .map_err(|source| Error::Unknown {
-                message: "PUT comment audio operation failed".to_owned(),
-                source: source.into(),
-            })?;
-        let http_response = self
-            .http_client
-            .put(self.endpoints.comment_audio(source_id, comment_id)?)
-            .headers(self.headers.clone())
-            .multipart(form)
-            .send()
-            .map_err(|source| Error::ReqwestError {
-                message: "PUT comment audio operation failed".to_owned(),
-                source,
-            })?;
-        let status = http_response.status();
-        http_response
-            .json::<Response<EmptySuccess>>()
-            .map_err(Error::BadJsonResponse)?
-            .into_result(status)?;
+        let audio_path = audio_path.as_ref();
+        let form = || Form::new().file("file", audio_path);
+        form().map_err(|source| Error::Unknown {
+            message: "PUT comment audio operation failed".to_owned(),
+            source: source.into(),
+        })?;
+        let url = self.endpoints.comment_audio(source_id, comment_id)?;
+        let http_response = self.send_request(&Method::PUT, &url, &Retry::No, || {
+            self.with_attachment_timeout(self.http_client.put(url.clone()))
+                .headers(self.headers.clone())
+                .multipart(form().expect("Audio file was readable before the request"))
+                .build()
+        })?;
+        self.json_response::<EmptySuccess>(http_response)?;
         Ok(())
     }
 
@@ -795,58 +874,98 @@ impl Client {
             });
         }
 
-        let do_request = || {
-            let form = Form::new()
-                .file("file", attachment)
-                .map_err(|source| Error::Unknown {
-                    message: "PUT comment attachment operation failed".to_owned(),
-                    source: source.into(),
-                })
-                .unwrap();
-            let request = self
-                .http_client
-                .request(Method::PUT, url.clone())
-                .multipart(form)
-                .headers(self.headers.clone());
-
-            request.send()
-        };
-
-        let result = self.with_retries(do_request);
-
-        let http_response = result.map_err(|source| Error::ReqwestError {
-            source,
-            message: "Operation failed.".to_string(),
+        let form = || Form::new().file("file", attachment);
+        form().map_err(|source| Error::Unknown {
+            message: "PUT comment attachment operation failed".to_owned(),
+            source: source.into(),
+        })?;
+        let http_response = self.send_request(&Method::PUT, &url, &Retry::Yes, || {
+            self.with_attachment_timeout(self.http_client.put(url.clone()))
+                .multipart(form().expect("Attachment was readable before the request"))
+                .headers(self.headers.clone())
+                .build()
         })?;
 
-        let status = http_response.status();
-
-        http_response
-            .json::<Response<UploadAttachmentResponse>>()
-            .map_err(Error::BadJsonResponse)?
-            .into_result(status)
+        self.json_response(http_response)
     }
 
     pub fn get_attachment(&self, reference: &AttachmentReference) -> Result<Vec<u8>> {
-        let mut response = self.raw_request(
+        let response = self.get_attachment_response(reference)?;
+
+        self.read_body(response)
+    }
+
+    fn get_attachment_response(&self, reference: &AttachmentReference) -> Result<HttpResponse> {
+        self.raw_request(
             &Method::GET,
             &self.endpoints.attachment_reference(reference)?,
             &None::<()>,
             &None::<()>,
             &Retry::Yes,
             None,
-        )?;
-
-        let mut buffer = Vec::new();
+            self.attachment_timeout,
+        )
+    }
 
-        response
-            .read_to_end(&mut buffer)
-            .map_err(|source| Error::Unknown {
-                message: "Failed to read buffer".to_string(),
+    /// Like `get_attachment`, but streams the attachment into `writer` instead of buffering it in
+    /// memory. Returns the number of bytes written.
+    pub fn get_attachment_to_writer(
+        &self,
+        reference: &AttachmentReference,
+        writer: &mut impl Write,
+    ) -> Result<u64> {
+        let response = self.get_attachment_response(reference)?;
+
+        let max_bytes = self.max_response_bytes;
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_bytes)
+        {
+            return Err(Error::ResponseTooLarge { max_bytes });
+        }
+        let written = io::copy(&mut response.take(max_bytes.saturating_add(1)), writer).map_err(
+            |source| Error::Unknown {
+                message: "Failed to write attachment".to_owned(),
                 source: Box::new(source),
-            })?;
+            },
+        )?;
+        if written > max_bytes {
+            return Err(Error::ResponseTooLarge { max_bytes });
+        }
+        Ok(written)
+    }
 
-        Ok(buffer)
+    /// Stream an attachment to a file at `path`. The attachment is first written next to it with
+    /// a `.part` suffix, so that `path` only ever exists with the complete attachment.
+    pub fn get_attachment_to_path(
+        &self,
+        reference: &AttachmentReference,
+        path: &Path,
+    ) -> Result<u64> {
+        let mut partial_path = path.as_os_str().to_owned();
+        partial_path.push(".part");
+        let partial_path = PathBuf::from(partial_path);
+
+        let to_error = |source: io::Error| Error::Unknown {
+            message: format!("Failed to write attachment to `{}`", path.display()),
+            source: Box::new(source),
+        };
+        let result = File::create(&partial_path)
+            .map_err(to_error)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                let written = self.get_attachment_to_writer(reference, &mut writer)?;
+                writer.flush().map_err(to_error)?;
+                Ok(written)
+            })
+            .and_then(|written| {
+                fs::rename(&partial_path, path).map_err(to_error)?;
+                Ok(written)
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(&partial_path);
+        }
+        result
     }
 
     pub fn get_integrations(&self) -> Result<Vec<Integration>> {
@@ -1076,7 +1195,11 @@ impl Client {
                 &Some(serde_json::to_value(params).expect("query params serialization error")),
                 &None::<()>,
                 &Retry::Yes,
-                Some(HeaderValue::from_str("text/csv").expect("Could not parse csv header")),
+                Some(HeaderMap::from_iter([(
+                    ACCEPT,
+                    HeaderValue::from_str("text/csv").expect("Could not parse csv header"),
+                )])),
+                None,
             )?
             .text()
             .expect("Could not get csv text");
@@ -1312,7 +1435,69 @@ impl Client {
         LocationT: IntoUrl + Display + Clone,
         for<'de> SuccessT: Deserialize<'de>,
     {
-        self.request(&Method::GET, &url, &None::<()>, &None::<()>, &Retry::Yes)
+        match &self.cache {
+            Some(cache) => self.get_cached(cache, url),
+            None => self.request(&Method::GET, &url, &None::<()>, &None::<()>, &Retry::Yes),
+        }
+    }
+
+    /// GET a resource, revalidating any cached response with `If-None-Match`. A `304 Not
+    /// Modified` returns the cached body.
+    fn get_cached<LocationT, SuccessT>(
+        &self,
+        cache: &ResponseCache,
+        url: LocationT,
+    ) -> Result<SuccessT>
+    where
+        LocationT: IntoUrl + Display + Clone,
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        debug!("Attempting GET `{}` (cached)", url);
+        let url = url.into_url().map_err(|source| Error::ReqwestError {
+            source,
+            message: "Invalid URL.".to_owned(),
+        })?;
+
+        let cached = cache.get(&url);
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = cached
+            .as_ref()
+            .and_then(|entry| HeaderValue::from_str(&entry.etag).ok())
+        {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+
+        let http_response = self.raw_request(
+            &Method::GET,
+            &url,
+            &None::<()>,
+            &None::<()>,
+            &Retry::Yes,
+            Some(headers),
+            None,
+        )?;
+        let status = http_response.status();
+
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                debug!("Using cached response for `{}`", url);
+                return serde_json::from_value::<Response<SuccessT>>(entry.body)
+                    .map_err(Error::BadJsonResponse)?
+                    .into_result(reqwest::StatusCode::OK);
+            }
+        }
+
+        let request_id = request_id(&http_response);
+        let etag = http_response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_owned);
+        let body = self.read_body(http_response)?;
+        if let (true, Some(etag)) = (status.is_success(), etag) {
+            cache.put(&url, etag, &body);
+        }
+        deserialize_response(status, request_id, &body)
     }
 
     fn get_query<LocationT, QueryT, SuccessT>(
@@ -1343,33 +1528,24 @@ impl Client {
         debug!("Attempting DELETE `{}`", url);
 
         let attempts = Cell::new(0);
-        let http_response = self
-            .with_retries(|| {
-                attempts.set(attempts.get() + 1);
-
-                let mut request = self
-                    .http_client
-                    .delete(url.clone())
-                    .headers(self.headers.clone());
-                if let Some(query) = query {
-                    request = request.query(query);
-                }
-                request.send()
-            })
-            .map_err(|source| Error::ReqwestError {
-                source,
-                message: "DELETE operation failed.".to_owned(),
-            })?;
-        let status = http_response.status();
-        http_response
-            .json::<Response<EmptySuccess>>()
-            .map_err(Error::BadJsonResponse)?
-            .into_result(status)
+        let http_response = self.send_request(&Method::DELETE, &url, &Retry::Yes, || {
+            attempts.set(attempts.get() + 1);
+
+            let mut request = self
+                .http_client
+                .delete(url.clone())
+                .headers(self.headers.clone());
+            if let Some(query) = query {
+                request = request.query(query);
+            }
+            request.build()
+        })?;
+        self.json_response::<EmptySuccess>(http_response)
             .map_or_else(
                 // Ignore 404 not found if the request had to be re-tried - assume the target
                 // object was deleted on a previous incomplete request.
                 |error| {
-                    if attempts.get() > 1 && status == reqwest::StatusCode::NOT_FOUND {
+                    if attempts.get() > 1 && error.is_not_found() {
                         Ok(())
                     } else {
                         Err(error)
@@ -1406,6 +1582,7 @@ impl Client {
         self.request(&Method::PUT, &url, &Some(request), &None::<()>, &Retry::Yes)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn raw_request<LocationT, RequestT, QueryT>(
         &self,
         method: &Method,
@@ -1413,7 +1590,8 @@ impl Client {
         body: &Option<RequestT>,
         query: &Option<QueryT>,
         retry: &Retry,
-        accept_header: Option<HeaderValue>,
+        extra_headers: Option<HeaderMap>,
+        timeout: Option<Duration>,
     ) -> Result<reqwest::blocking::Response>
     where
         LocationT: IntoUrl + Display + Clone,
@@ -1422,11 +1600,20 @@ impl Client {
     {
         let mut headers = self.headers.clone();
 
-        if let Some(accept_header) = accept_header {
-            headers.insert(ACCEPT, accept_header);
+        if let Some(extra_headers) = extra_headers {
+            headers.extend(extra_headers);
         }
 
-        let do_request = || {
+        if log_enabled!(Level::Trace) {
+            if let Some(body) = body {
+                match serde_json::to_vec(body) {
+                    Ok(body) => trace!("{method} `{url}` request body: {}", body_for_trace(&body)),
+                    Err(error) => trace!("{method} `{url}` request body is not JSON: {error}"),
+                }
+            }
+        }
+
+        let build_request = || {
             let request = self
                 .http_client
                 .request(method.clone(), url.clone())
@@ -1440,19 +1627,66 @@ impl Client {
                 Some(body) => request.json(body),
                 None => request,
             };
-            request.send()
+            let request = match timeout {
+                Some(timeout) => request.timeout(timeout),
+                None => request,
+            };
+            request.build()
         };
 
-        let result = match retry {
+        let http_response = self.send_request(method, url, retry, build_request)?;
+        trace!("{method} `{url}` responded with {}", http_response.status());
+
+        Ok(http_response)
+    }
+
+    /// Send the request made by `build_request`, which is called again for each retry. With
+    /// `PrintCurl::Only` the request is printed and never sent.
+    fn send_request(
+        &self,
+        method: &Method,
+        url: &impl Display,
+        retry: &Retry,
+        build_request: impl Fn() -> ReqwestResult<Request>,
+    ) -> Result<HttpResponse> {
+        if self.print_curl == PrintCurl::Only {
+            let request = build_request().map_err(|source| Error::ReqwestError {
+                source,
+                message: format!("Could not build {method} request."),
+            })?;
+            eprintln!("{}", to_curl_command(&request));
+            return Err(Error::RequestNotSent {
+                method: method.to_string(),
+                url: url.to_string(),
+            });
+        }
+
+        let do_request = || {
+            let request = build_request()?;
+            if self.print_curl == PrintCurl::Yes {
+                eprintln!("{}", to_curl_command(&request));
+            }
+            self.http_client.execute(request)
+        };
+
+        let RetryOutcome {
+            result,
+            retries_exhausted,
+        } = match retry {
             Retry::Yes => self.with_retries(do_request),
-            Retry::No => do_request(),
+            Retry::No => RetryOutcome {
+                result: do_request(),
+                retries_exhausted: false,
+            },
         };
         let http_response = result.map_err(|source| Error::ReqwestError {
             source,
             message: format!("{method} operation failed."),
-        })?;
-
-        Ok(http_response)
+        });
+        if retries_exhausted {
+            return Err(self.retries_exhausted_error(http_response));
+        }
+        http_response
     }
 
     fn splitable_request<LocationT, RequestT, SuccessT, QueryT>(
@@ -1486,16 +1720,42 @@ impl Client {
             Ok(response) => Ok(SplitableRequestResponse {
                 response,
                 num_failed: 0,
+                failed_ids: Vec::new(),
             }),
             Err(error) if should_split(&error) => {
-                let mut num_failed = 0;
+                let count = body.count();
+                if self
+                    .split_config
+                    .max_split_size
+                    .is_some_and(|max_split_size| count > max_split_size)
+                {
+                    warn!("Not splitting failed batch of {count} items, as it is over the maximum split size: {error}");
+                    return Ok(SplitableRequestResponse {
+                        num_failed: count,
+                        response: SuccessT::empty(),
+                        failed_ids: body.ids(),
+                    });
+                }
+
+                let mut failed_ids = Vec::new();
+                let mut wait = self.split_config.base_wait;
                 let response = body
                     .split()
-                    .filter_map(|request| {
+                    .enumerate()
+                    .filter_map(|(index, request)| {
+                        if index > 0 && !wait.is_zero() {
+                            std::thread::sleep(wait);
+                        }
+                        let ids = request.ids();
                         match self.request(&method, &url, &Some(request), &query, &retry) {
-                            Ok(response) => Some(response),
+                            Ok(response) => {
+                                wait = self.split_config.base_wait;
+                                Some(response)
+                            }
                             Err(_) => {
-                                num_failed += 1;
+                                failed_ids.extend(ids);
+                                wait = (wait * 2)
+                                    .min(self.split_config.base_wait * MAX_SPLIT_BACKOFF_FACTOR);
                                 None
                             }
                         }
@@ -1505,8 +1765,9 @@ impl Client {
                     });
 
                 Ok(SplitableRequestResponse {
-                    num_failed,
+                    num_failed: failed_ids.len(),
                     response,
+                    failed_ids,
                 })
             }
             Err(error) => Err(error),
@@ -1528,27 +1789,124 @@ impl Client {
         for<'de> SuccessT: Deserialize<'de>,
     {
         debug!("Attempting {} `{}`", method, url);
-        let http_response = self.raw_request(method, url, body, query, retry, None)?;
+        let http_response = self.raw_request(method, url, body, query, retry, None, None)?;
 
         let status = http_response.status();
+        let request_id = request_id(&http_response);
+        let body = self.read_body(http_response)?;
+        trace!("{method} `{url}` response body: {}", body_for_trace(&body));
+        deserialize_response(status, request_id, &body)
+    }
 
+    /// Read and deserialize a JSON response, see `read_body`.
+    fn json_response<SuccessT>(&self, http_response: HttpResponse) -> Result<SuccessT>
+    where
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        let status = http_response.status();
+        let request_id = request_id(&http_response);
+        let body = self.read_body(http_response)?;
+        deserialize_response(status, request_id, &body)
+    }
+
+    /// Read a whole response body, failing if it's larger than `max_response_bytes` rather than
+    /// buffering an arbitrarily large response.
+    fn read_body(&self, http_response: HttpResponse) -> Result<Vec<u8>> {
+        let max_bytes = self.max_response_bytes;
+        if http_response
+            .content_length()
+            .is_some_and(|length| length > max_bytes)
+        {
+            return Err(Error::ResponseTooLarge { max_bytes });
+        }
+        let mut body = Vec::new();
         http_response
-            .json::<Response<SuccessT>>()
-            .map_err(Error::BadJsonResponse)?
-            .into_result(status)
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(&mut body)
+            .map_err(|source| Error::Unknown {
+                message: "Failed to read response body".to_owned(),
+                source: Box::new(source),
+            })?;
+        if body.len() as u64 > max_bytes {
+            return Err(Error::ResponseTooLarge { max_bytes });
+        }
+        Ok(body)
     }
 
-    fn with_retries(
-        &self,
-        send_request: impl Fn() -> ReqwestResult<HttpResponse>,
-    ) -> ReqwestResult<HttpResponse> {
+    fn with_attachment_timeout(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.attachment_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Send a request with retries, if configured.
+    fn with_retries(&self, send_request: impl Fn() -> ReqwestResult<HttpResponse>) -> RetryOutcome {
         match &self.retrier {
             Some(retrier) => retrier.with_retries(send_request),
-            None => send_request(),
+            None => RetryOutcome {
+                result: send_request(),
+                retries_exhausted: false,
+            },
+        }
+    }
+
+    /// The error for a request whose final retry failed with `response`, so that callers can tell
+    /// transient failures apart from permanent ones.
+    fn retries_exhausted_error(&self, response: Result<HttpResponse>) -> Error {
+        let source = match response {
+            Ok(response) => {
+                let status = response.status();
+                let request_id = request_id(&response);
+                response
+                    .json::<Response<serde_json::Value>>()
+                    .ok()
+                    .and_then(|body| {
+                        body.into_result_with_request_id(status, request_id.clone())
+                            .err()
+                    })
+                    .unwrap_or(Error::Api {
+                        status_code: status,
+                        message: String::new(),
+                        request_id,
+                    })
+            }
+            Err(error) => error,
+        };
+        Error::RetriesExhausted {
+            retries: self
+                .retrier
+                .as_ref()
+                .map_or(0, |retrier| retrier.max_retry_count()),
+            source: Box::new(source),
         }
     }
 }
 
+/// Header in which the server returns the id of each request.
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+fn request_id(response: &HttpResponse) -> Option<String> {
+    response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|request_id| request_id.to_str().ok())
+        .map(str::to_owned)
+}
+
+fn deserialize_response<SuccessT>(
+    status: reqwest::StatusCode,
+    request_id: Option<String>,
+    body: &[u8],
+) -> Result<SuccessT>
+where
+    for<'de> SuccessT: Deserialize<'de>,
+{
+    serde_json::from_slice::<Response<SuccessT>>(body)
+        .map_err(Error::BadJsonResponse)?
+        .into_result_with_request_id(status, request_id)
+}
+
 #[derive(Copy, Clone)]
 enum Retry {
     Yes,
@@ -1651,9 +2009,10 @@ pub struct CommentsIter<'a> {
     done: bool,
     page_size: usize,
     to_timestamp: Option<DateTime<Utc>>,
+    include_thread_properties: bool,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct CommentsIterTimerange {
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
@@ -1678,8 +2037,16 @@ impl<'a> CommentsIter<'a> {
             continuation: from_timestamp.map(ContinuationKind::Timestamp),
             done: false,
             page_size: page_size.unwrap_or(Self::DEFAULT_PAGE_SIZE),
+            include_thread_properties: false,
         }
     }
+
+    /// Request the thread properties of each comment, which are returned in
+    /// `Comment::thread_properties`.
+    pub fn include_thread_properties(mut self, include_thread_properties: bool) -> Self {
+        self.include_thread_properties = include_thread_properties;
+        self
+    }
 }
 
 impl Iterator for CommentsIter<'_> {
@@ -1694,6 +2061,7 @@ impl Iterator for CommentsIter<'_> {
             self.continuation.as_ref(),
             self.to_timestamp,
             self.page_size,
+            self.include_thread_properties,
         );
         Some(response.map(|page| {
             self.continuation = page.continuation.map(ContinuationKind::Continuation);
@@ -2086,6 +2454,15 @@ impl Endpoints {
         construct_endpoint(&self.base, &["api", "_private", "quotas"])
     }
 
+    // The tenant scoped version of `quotas`, i.e. the parent of the `quota` route used by
+    // `create_quota` to set a single quota of a tenant.
+    fn tenant_quotas(&self, tenant_id: &TenantId) -> Result<Url> {
+        construct_endpoint(
+            &self.base,
+            &["api", "_private", "quotas", &tenant_id.to_string()],
+        )
+    }
+
     fn quota(&self, tenant_id: &TenantId, tenant_quota_kind: TenantQuotaKind) -> Result<Url> {
         construct_endpoint(
             &self.base,
@@ -2271,14 +2648,35 @@ impl Endpoints {
 
 const DEFAULT_HTTP_TIMEOUT_SECONDS: u64 = 240;
 
+/// Default for `Config::max_response_bytes`, large enough for any legitimate attachment.
+pub const DEFAULT_MAX_RESPONSE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+const DEFAULT_USER_AGENT: &str = concat!("reinfer-client/", env!("CARGO_PKG_VERSION"));
+
 fn build_http_client(config: &Config) -> Result<HttpClient> {
     let mut builder = HttpClient::builder()
         .gzip(true)
         .danger_accept_invalid_certs(config.accept_invalid_certificates)
         .timeout(Some(Duration::from_secs(DEFAULT_HTTP_TIMEOUT_SECONDS)));
 
+    if let Some(pool_max_idle_per_host) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(pool_max_idle_per_host);
+    }
+    if let Some(pool_idle_timeout) = config.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(pool_idle_timeout);
+    }
+    if config.http1_only {
+        builder = builder.http1_only();
+    }
+
     if let Some(proxy) = config.proxy.clone() {
-        builder = builder.proxy(Proxy::all(proxy).map_err(Error::BuildHttpClient)?);
+        let exclusions = ProxyExclusions::from_env_and(&config.proxy_exclusions);
+        let proxy = if exclusions.is_empty() {
+            Proxy::all(proxy).map_err(Error::BuildHttpClient)?
+        } else {
+            Proxy::custom(move |url| (!exclusions.matches(url)).then(|| proxy.clone()))
+        };
+        builder = builder.proxy(proxy);
     }
     builder.build().map_err(Error::BuildHttpClient)
 }
@@ -2293,6 +2691,14 @@ fn build_headers(config: &Config) -> Result<HeaderMap> {
             }
         })?,
     );
+    let user_agent = config
+        .user_agent
+        .clone()
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_owned());
+    headers.insert(
+        header::USER_AGENT,
+        HeaderValue::from_str(&user_agent).map_err(|_| Error::BadUserAgent { user_agent })?,
+    );
     Ok(headers)
 }
 
@@ -2343,4 +2749,268 @@ mod tests {
             [("id", "Stream"), ("id", "River"), ("id", "Waterfall"),]
         );
     }
+
+    #[test]
+    fn test_split_on_failure_max_split_size() {
+        let new_comment = |id: &str| NewComment {
+            id: CommentId(id.to_owned()),
+            thread_id: None,
+            timestamp: Utc::now(),
+            messages: Vec::new(),
+            user_properties: PropertyMap::new(),
+            attachments: Vec::new(),
+        };
+        let comments = vec![new_comment("1"), new_comment("2")];
+        let client = |max_split_size| {
+            Client::new(Config {
+                endpoint: Url::parse(&mockito::server_url()).unwrap(),
+                split_config: SplitConfig {
+                    base_wait: Duration::ZERO,
+                    max_split_size,
+                },
+                ..Default::default()
+            })
+            .unwrap()
+        };
+        let source_name = SourceFullName("project/split-test".to_owned());
+
+        let failure = mockito::mock(
+            "PUT",
+            mockito::Matcher::Regex("split-test/comments$".to_owned()),
+        )
+        .match_query(mockito::Matcher::Any)
+        .with_status(422)
+        .with_body(r#"{"status": "error", "message": "Invalid comment"}"#)
+        .expect(3)
+        .create();
+        let result = client(None)
+            .put_comments_split_on_failure(&source_name, comments.clone(), false)
+            .unwrap();
+        assert_eq!(result.num_failed, 2);
+        assert_eq!(result.failed_ids, vec!["1", "2"]);
+        failure.assert();
+
+        let failure = mockito::mock(
+            "PUT",
+            mockito::Matcher::Regex("split-test/comments$".to_owned()),
+        )
+        .match_query(mockito::Matcher::Any)
+        .with_status(422)
+        .with_body(r#"{"status": "error", "message": "Invalid comment"}"#)
+        .expect(1)
+        .create();
+        let result = client(Some(1))
+            .put_comments_split_on_failure(&source_name, comments, false)
+            .unwrap();
+        assert_eq!(result.num_failed, 2);
+        assert_eq!(result.failed_ids, vec!["1", "2"]);
+        failure.assert();
+    }
+
+    #[test]
+    fn test_comments_iter_include_thread_properties() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+        let source_name = SourceFullName("project/thread-test".to_owned());
+
+        let page = mockito::mock(
+            "GET",
+            mockito::Matcher::Regex("thread-test/comments$".to_owned()),
+        )
+        .match_query(mockito::Matcher::UrlEncoded(
+            "include_thread_properties".to_owned(),
+            "true".to_owned(),
+        ))
+        .with_body(
+            r#"{
+                "status": "ok",
+                "comments": [{
+                    "id": "1",
+                    "uid": "abc.1",
+                    "timestamp": "2024-01-01T00:00:00Z",
+                    "messages": [],
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "thread_properties": {"num_messages": 2, "thread_position": 0}
+                }],
+                "continuation": null
+            }"#,
+        )
+        .create();
+        let comments: Vec<Comment> = client
+            .get_comments_iter(&source_name, None, CommentsIterTimerange::default())
+            .include_thread_properties(true)
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(comments.len(), 1);
+        assert!(comments[0].thread_properties.is_some());
+        page.assert();
+    }
+
+    #[test]
+    fn test_print_curl_only_does_not_send_delete() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            print_curl: PrintCurl::Only,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let delete = mockito::mock("DELETE", mockito::Matcher::Regex("abc123def$".to_owned()))
+            .expect(0)
+            .create();
+        let error = client
+            .delete_source(SourceId("abc123def".to_owned()))
+            .unwrap_err();
+        assert!(matches!(error, Error::RequestNotSent { .. }));
+        delete.assert();
+    }
+
+    #[test]
+    fn test_api_error_includes_request_id() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let failure = mockito::mock(
+            "GET",
+            mockito::Matcher::Regex("request-id-test/comments$".to_owned()),
+        )
+        .match_query(mockito::Matcher::Any)
+        .with_status(500)
+        .with_header("x-request-id", "abc123")
+        .with_body(r#"{"status": "error", "message": "Internal error"}"#)
+        .create();
+        let error = client
+            .get_comments_iter_page(
+                &SourceFullName("project/request-id-test".to_owned()),
+                None,
+                None,
+                1,
+                false,
+            )
+            .unwrap_err();
+        match &error {
+            Error::Api { request_id, .. } => assert_eq!(request_id.as_deref(), Some("abc123")),
+            _ => panic!("Expected an API error, got {error:?}"),
+        }
+        assert!(error.to_string().contains("request id: abc123"));
+        failure.assert();
+    }
+
+    #[test]
+    fn test_retries_exhausted_error() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            retry_config: Some(RetryConfig {
+                strategy: crate::retry::RetryStrategy::Always,
+                max_retry_count: 1,
+                base_wait: std::time::Duration::from_secs(0),
+                backoff_factor: 0.0,
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let failure = mockito::mock(
+            "GET",
+            mockito::Matcher::Regex("retries-test/comments$".to_owned()),
+        )
+        .match_query(mockito::Matcher::Any)
+        .with_status(503)
+        .with_body(r#"{"status": "error", "message": "Unavailable"}"#)
+        .expect(2)
+        .create();
+        let error = client
+            .get_comments_iter_page(
+                &SourceFullName("project/retries-test".to_owned()),
+                None,
+                None,
+                1,
+                false,
+            )
+            .unwrap_err();
+        match &error {
+            Error::RetriesExhausted { retries, .. } => assert_eq!(*retries, 1),
+            _ => panic!("Expected retries to be exhausted, got {error:?}"),
+        }
+        let source = std::error::Error::source(&error).and_then(|source| source.downcast_ref());
+        assert!(matches!(source, Some(Error::Api { status_code, .. })
+            if *status_code == reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        failure.assert();
+    }
+
+    #[test]
+    fn test_max_response_bytes() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            max_response_bytes: 8,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let small = mockito::mock("GET", "/api/v1/attachments/small")
+            .with_body("12345678")
+            .create();
+        let large = mockito::mock("GET", "/api/v1/attachments/large")
+            .with_body("123456789")
+            .create();
+        assert_eq!(
+            client
+                .get_attachment(&AttachmentReference("small".to_owned()))
+                .unwrap(),
+            b"12345678"
+        );
+        let error = client
+            .get_attachment(&AttachmentReference("large".to_owned()))
+            .unwrap_err();
+        assert!(matches!(error, Error::ResponseTooLarge { max_bytes: 8 }));
+        small.assert();
+        large.assert();
+    }
+
+    #[test]
+    fn test_get_attachment_to_path() {
+        let client = Client::new(Config {
+            endpoint: Url::parse(&mockito::server_url()).unwrap(),
+            max_response_bytes: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!(
+            "reinfer-attachment-test-{}",
+            Utc::now().timestamp_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let _small = mockito::mock("GET", "/api/v1/attachments/to-path-small")
+            .with_body("12345678")
+            .create();
+        let _large = mockito::mock("GET", "/api/v1/attachments/to-path-large")
+            .with_body("123456789")
+            .create();
+
+        let path = dir.join("small.txt");
+        let written = client
+            .get_attachment_to_path(&AttachmentReference("to-path-small".to_owned()), &path)
+            .unwrap();
+        assert_eq!(written, 8);
+        assert_eq!(std::fs::read(&path).unwrap(), b"12345678");
+
+        let path = dir.join("large.txt");
+        assert!(client
+            .get_attachment_to_path(&AttachmentReference("to-path-large".to_owned()), &path)
+            .is_err());
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,188 @@
+use std::{env, net::IpAddr};
+use url::{Host, Url};
+
+/// A set of rules describing which hosts should bypass the configured proxy.
+///
+/// Each rule follows the conventions of the `NO_PROXY` environment variable:
+///   - `*` matches every host
+///   - `example.com`, `.example.com` and `*.example.com` match `example.com` and
+///     all of its subdomains
+///   - `10.0.0.1` matches exactly that IP address
+///   - `10.0.0.0/8` matches every IP address in the CIDR block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ProxyExclusions {
+    rules: Vec<ExclusionRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExclusionRule {
+    Wildcard,
+    Domain(String),
+    Ip(IpAddr),
+    Cidr { network: IpAddr, prefix_len: u8 },
+}
+
+impl ProxyExclusions {
+    /// Build the exclusions from the `NO_PROXY` / `no_proxy` environment variables, plus any
+    /// additional explicit exclusions.
+    pub fn from_env_and(exclusions: &[String]) -> Self {
+        let from_env = env::var("NO_PROXY")
+            .or_else(|_| env::var("no_proxy"))
+            .unwrap_or_default();
+
+        Self::parse(
+            from_env
+                .split(',')
+                .chain(exclusions.iter().map(String::as_str)),
+        )
+    }
+
+    pub fn parse<'a>(entries: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            rules: entries
+                .into_iter()
+                .filter_map(ExclusionRule::parse)
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn matches(&self, url: &Url) -> bool {
+        let host = match url.host() {
+            Some(host) => host,
+            None => return false,
+        };
+        self.rules.iter().any(|rule| rule.matches(&host))
+    }
+}
+
+impl ExclusionRule {
+    fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return None;
+        }
+        if entry == "*" {
+            return Some(Self::Wildcard);
+        }
+
+        if let Some((network, prefix_len)) = entry.split_once('/') {
+            let network = network.parse::<IpAddr>().ok()?;
+            let prefix_len = prefix_len.parse::<u8>().ok()?;
+            let max_prefix_len = match network {
+                IpAddr::V4(_) => 32,
+                IpAddr::V6(_) => 128,
+            };
+            return (prefix_len <= max_prefix_len).then_some(Self::Cidr {
+                network,
+                prefix_len,
+            });
+        }
+
+        let unbracketed = entry.trim_start_matches('[').trim_end_matches(']');
+        if let Ok(ip) = unbracketed.parse::<IpAddr>() {
+            return Some(Self::Ip(ip));
+        }
+
+        // Entries may carry a port (e.g. `localhost:8080`), which we ignore.
+        let domain = entry.rsplit_once(':').map_or(entry, |(domain, _)| domain);
+        let domain = domain
+            .trim_start_matches('*')
+            .trim_start_matches('.')
+            .to_lowercase();
+        (!domain.is_empty()).then_some(Self::Domain(domain))
+    }
+
+    fn matches(&self, host: &Host<&str>) -> bool {
+        match (self, host) {
+            (Self::Wildcard, _) => true,
+            (Self::Domain(domain), Host::Domain(host)) => {
+                let host = host.to_lowercase();
+                host == *domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+            (Self::Ip(ip), Host::Ipv4(host)) => *ip == IpAddr::V4(*host),
+            (Self::Ip(ip), Host::Ipv6(host)) => *ip == IpAddr::V6(*host),
+            (
+                Self::Cidr {
+                    network,
+                    prefix_len,
+                },
+                Host::Ipv4(host),
+            ) => cidr_contains(network, *prefix_len, &IpAddr::V4(*host)),
+            (
+                Self::Cidr {
+                    network,
+                    prefix_len,
+                },
+                Host::Ipv6(host),
+            ) => cidr_contains(network, *prefix_len, &IpAddr::V6(*host)),
+            _ => false,
+        }
+    }
+}
+
+fn cidr_contains(network: &IpAddr, prefix_len: u8, ip: &IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u32::from(*network) & mask == u32::from(*ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u128::from(*network) & mask == u128::from(*ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excludes(rules: &str, url: &str) -> bool {
+        ProxyExclusions::parse(rules.split(',')).matches(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_domain_exclusions() {
+        assert!(excludes("localhost", "http://localhost:8000/api"));
+        assert!(excludes("example.com", "https://example.com"));
+        assert!(excludes("example.com", "https://api.EXAMPLE.com"));
+        assert!(excludes(".example.com", "https://api.example.com"));
+        assert!(excludes("*.example.com", "https://example.com"));
+        assert!(excludes("example.com:443", "https://example.com"));
+        assert!(!excludes("example.com", "https://notexample.com"));
+        assert!(!excludes("api.example.com", "https://example.com"));
+    }
+
+    #[test]
+    fn test_ip_and_cidr_exclusions() {
+        assert!(excludes("127.0.0.1", "http://127.0.0.1:8000"));
+        assert!(!excludes("127.0.0.1", "http://127.0.0.2"));
+        assert!(excludes("10.0.0.0/8", "http://10.1.2.3"));
+        assert!(!excludes("10.0.0.0/8", "http://11.1.2.3"));
+        assert!(excludes("0.0.0.0/0", "http://192.168.0.1"));
+        assert!(excludes("::1", "http://[::1]:8000"));
+        assert!(excludes("fd00::/8", "http://[fd12::1]"));
+        assert!(!excludes("fd00::/8", "http://[fe80::1]"));
+        assert!(!excludes("10.0.0.0/8", "http://[::1]"));
+    }
+
+    #[test]
+    fn test_wildcard_and_empty_exclusions() {
+        assert!(excludes("*", "https://reinfer.dev"));
+        assert!(!excludes("", "https://reinfer.dev"));
+        assert!(ProxyExclusions::parse(" , ,".split(',')).is_empty());
+        assert!(ProxyExclusions::parse(["10.0.0.0/33"]).is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::env;
+
+/// Setting this environment variable to `1` disables truncation of bodies logged at trace level.
+const LOG_BODIES_ENV_VARIABLE_NAME: &str = "REINFER_CLI_LOG_BODIES";
+
+const MAX_TRACE_BODY_LENGTH: usize = 2048;
+
+const REDACTED: &str = "<redacted>";
+
+/// Object keys whose values are credentials or obvious personal data, and should never be logged.
+const REDACTED_KEYS: &[&str] = &[
+    "api_token",
+    "authorization",
+    "bcc",
+    "cc",
+    "client_secret",
+    "email",
+    "from",
+    "password",
+    "phone",
+    "recipients",
+    "secret",
+    "sender",
+    "to",
+    "token",
+];
+
+static LOG_FULL_BODIES: Lazy<bool> =
+    Lazy::new(|| env::var(LOG_BODIES_ENV_VARIABLE_NAME).is_ok_and(|value| value == "1"));
+
+/// Format a request or response body for trace logging, redacting credentials and personal data
+/// and truncating long bodies.
+pub(crate) fn body_for_trace(body: &[u8]) -> String {
+    let body = match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes of non-JSON data>", body.len()),
+    };
+    truncate(body, *LOG_FULL_BODIES)
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.to_lowercase().as_str()) {
+                    *value = Value::String(REDACTED.to_owned());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn truncate(mut body: String, full: bool) -> String {
+    if full || body.len() <= MAX_TRACE_BODY_LENGTH {
+        return body;
+    }
+    let mut end = MAX_TRACE_BODY_LENGTH;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    let total_length = body.len();
+    body.truncate(end);
+    body.push_str(&format!("... ({total_length} bytes total)"));
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact() {
+        let mut value = json!({
+            "comments": [{
+                "id": "1",
+                "messages": [{"from": "a@b.com", "to": ["c@d.com"], "body": {"text": "hi"}}],
+            }],
+            "Token": "secret",
+        });
+        redact(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "comments": [{
+                    "id": "1",
+                    "messages": [{"from": REDACTED, "to": REDACTED, "body": {"text": "hi"}}],
+                }],
+                "Token": REDACTED,
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        let body = "é".repeat(MAX_TRACE_BODY_LENGTH);
+        let truncated = truncate(body.clone(), false);
+        assert!(truncated.len() < body.len());
+        assert!(truncated.ends_with(&format!("... ({} bytes total)", body.len())));
+        assert_eq!(truncate(body.clone(), true), body);
+        assert_eq!(truncate("{}".to_owned(), false), "{}");
+    }
+
+    #[test]
+    fn test_body_for_trace_non_json() {
+        assert_eq!(body_for_trace(b"\x00\x01"), "<2 bytes of non-JSON data>");
+    }
+}
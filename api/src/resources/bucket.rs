@@ -148,14 +148,18 @@ pub(crate) struct GetKeyedSyncStatesResponse {
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct KeyedSyncStateId(pub String);
 
+impl FromStr for KeyedSyncStateId {
+    type Err = Error;
+    fn from_str(string: &str) -> Result<Self> {
+        Ok(Self(string.into()))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct GetKeyedSyncStateIdsRequest {
     pub mailbox_name: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
-pub(crate) struct DeleteKeyedSyncStateResponse {}
-
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub(crate) struct GetKeyedSyncStateIdsResponse {
     pub keyed_sync_state_ids: Vec<KeyedSyncStateId>,
@@ -171,12 +175,18 @@ pub(crate) struct GetResponse {
     pub bucket: Bucket,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub enum BucketType {
+    #[default]
     #[serde(rename = "emails")]
     Emails,
 }
 
+impl BucketType {
+    /// Names of all the bucket types, as accepted by `FromStr`.
+    pub const NAMES: &'static [&'static str] = &["emails"];
+}
+
 impl FromStr for BucketType {
     type Err = Error;
 
@@ -190,12 +200,6 @@ impl FromStr for BucketType {
     }
 }
 
-impl Default for BucketType {
-    fn default() -> Self {
-        Self::Emails
-    }
-}
-
 impl Display for BucketType {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
         match *self {
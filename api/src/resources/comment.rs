@@ -70,6 +70,11 @@ type UserPropertyName = String;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPropertiesFilter(pub HashMap<UserPropertyName, PropertyFilter>);
 
+type ThreadPropertyName = String;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadPropertiesFilter(pub HashMap<ThreadPropertyName, PropertyFilter>);
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct PropertyFilter {
     #[serde(skip_serializing_if = "<[_]>::is_empty", default)]
@@ -110,6 +115,9 @@ pub struct CommentFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_properties: Option<UserPropertiesFilter>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_properties: Option<ThreadPropertiesFilter>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub sources: Vec<SourceId>,
@@ -127,10 +135,23 @@ pub struct MessagesFilter {
     pub to: Option<PropertyFilter>,
 }
 
-#[derive(Debug, Clone, Serialize)]
-#[serde(rename_all(serialize = "lowercase"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommentPredictionsThreshold {
     Auto,
+    /// The same confidence threshold, between 0 and 1, for all labels.
+    Fixed(NotNan<f64>),
+}
+
+impl Serialize for CommentPredictionsThreshold {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::Fixed(threshold) => serializer.serialize_f64(threshold.into_inner()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -211,12 +232,24 @@ impl SplittableRequest for PutCommentsRequest {
     fn count(&self) -> usize {
         self.comments.len()
     }
+
+    fn ids(&self) -> Vec<String> {
+        self.comments.iter().map(|item| item.id.0.clone()).collect()
+    }
 }
 
 #[derive(Default, Debug, Copy, Clone, Deserialize)]
 pub struct PutCommentsResponse;
 
-impl ReducibleResponse for PutCommentsResponse {}
+impl ReducibleResponse for PutCommentsResponse {
+    fn empty() -> Self {
+        Self
+    }
+
+    fn merge(self, _b: Self) -> Self {
+        Self
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct SyncCommentsRequest {
@@ -236,9 +269,13 @@ impl SplittableRequest for SyncCommentsRequest {
     fn count(&self) -> usize {
         self.comments.len()
     }
+
+    fn ids(&self) -> Vec<String> {
+        self.comments.iter().map(|item| item.id.0.clone()).collect()
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
 pub struct SyncCommentsResponse {
     pub new: usize,
     pub updated: usize,
@@ -253,6 +290,7 @@ impl ReducibleResponse for SyncCommentsResponse {
             unchanged: 0,
         }
     }
+
     fn merge(self, b: Self) -> Self {
         Self {
             new: self.new + b.new,
@@ -283,6 +321,10 @@ pub struct Comment {
 
     #[serde(default)]
     pub has_annotations: bool,
+
+    /// Only returned when requested, see `CommentsIter::include_thread_properties`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub thread_properties: Option<ThreadProperties>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -648,7 +690,7 @@ where
     T: HasAnnotations,
 {
     fn has_annotations(&self) -> bool {
-        self.as_ref().map_or(false, HasAnnotations::has_annotations)
+        self.as_ref().is_some_and(HasAnnotations::has_annotations)
     }
 }
 
@@ -932,6 +974,59 @@ mod tests {
     use serde_json::{self, json, Value as JsonValue};
     use std::collections::HashMap;
 
+    #[test]
+    fn test_serialize_predictions_threshold() {
+        assert_eq!(
+            serde_json::to_value(CommentPredictionsThreshold::Auto).unwrap(),
+            json!("auto")
+        );
+        assert_eq!(
+            serde_json::to_value(CommentPredictionsThreshold::Fixed(
+                NotNan::new(0.25).unwrap()
+            ))
+            .unwrap(),
+            json!(0.25)
+        );
+    }
+
+    #[test]
+    fn sync_comments_response_merge() {
+        let responses = vec![
+            SyncCommentsResponse {
+                new: 1,
+                updated: 0,
+                unchanged: 0,
+            },
+            SyncCommentsResponse {
+                new: 0,
+                updated: 1,
+                unchanged: 0,
+            },
+            SyncCommentsResponse {
+                new: 1,
+                updated: 0,
+                unchanged: 0,
+            },
+            SyncCommentsResponse {
+                new: 0,
+                updated: 0,
+                unchanged: 1,
+            },
+        ];
+
+        let merged = responses
+            .into_iter()
+            .fold(SyncCommentsResponse::empty(), ReducibleResponse::merge);
+        assert_eq!(
+            merged,
+            SyncCommentsResponse {
+                new: 2,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+    }
+
     #[test]
     fn property_map_empty_serialize() {
         assert_eq!(serde_json::to_string(&PropertyMap::new()).expect(""), "{}");
@@ -101,7 +101,7 @@ pub enum TimeResolution {
     Month,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Attribute {
     Labels,
@@ -109,7 +109,7 @@ pub enum Attribute {
     AttachmentPropertyNumAttachments,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum AttributeFilterEnum {
     StringAnyOf {
@@ -121,7 +121,7 @@ pub enum AttributeFilterEnum {
     },
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttributeFilter {
     pub attribute: Attribute,
     pub filter: AttributeFilterEnum,
@@ -155,18 +155,17 @@ pub struct StatisticsRequestParams {
     pub time_resolution: Option<TimeResolution>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum OrderEnum {
-    ByLabel { label: String },
+    ByLabel {
+        label: String,
+    },
+    #[default]
     Recent,
-    Sample { seed: usize },
-}
-
-impl Default for OrderEnum {
-    fn default() -> Self {
-        Self::Recent
-    }
+    Sample {
+        seed: usize,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -177,19 +176,21 @@ pub struct SummaryRequestParams {
     pub filter: CommentFilter,
 }
 
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct QueryRequestParams {
-    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    #[serde(skip_serializing_if = "<[_]>::is_empty", default)]
     pub attribute_filters: Vec<AttributeFilter>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub continuation: Option<Continuation>,
 
+    #[serde(default)]
     pub filter: CommentFilter,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
 
+    #[serde(default)]
     pub order: OrderEnum,
 }
 
@@ -218,6 +219,24 @@ pub struct UserPropertySummaryList {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Summary {
     pub user_properties: UserPropertySummaryList,
+    /// Thread properties present in the dataset, in the same shape as `user_properties`. Not
+    /// returned for datasets without threads.
+    #[serde(default)]
+    pub thread_properties: Option<UserPropertySummaryList>,
+}
+
+impl UserPropertySummaryList {
+    /// The full names of all string and number properties.
+    pub fn full_names(&self) -> impl Iterator<Item = &str> {
+        self.string
+            .iter()
+            .map(|property| property.full_name.as_str())
+            .chain(
+                self.number
+                    .iter()
+                    .map(|property| property.full_name.as_str()),
+            )
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -413,6 +432,16 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_deserialize_query_params() {
+        let json = r#"{"attribute_filters":[{"attribute":"labels","filter":{"kind":"string_any_of","any_of":["Access Management"]}}],"filter":{"reviewed":"only_unreviewed"},"limit":20,"order":{"kind":"sample","seed":42}}"#;
+        let params: QueryRequestParams = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&params).unwrap(), json);
+
+        let params: QueryRequestParams = serde_json::from_str("{}").unwrap();
+        assert!(matches!(params.order, OrderEnum::Recent));
+    }
+
     #[test]
     pub fn test_serialize_query_params_by_label() {
         let params = QueryRequestParams {
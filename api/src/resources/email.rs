@@ -92,12 +92,24 @@ impl SplittableRequest for PutEmailsRequest {
     fn count(&self) -> usize {
         self.emails.len()
     }
+
+    fn ids(&self) -> Vec<String> {
+        self.emails.iter().map(|item| item.id.0.clone()).collect()
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct PutEmailsResponse {}
 
-impl ReducibleResponse for PutEmailsResponse {}
+impl ReducibleResponse for PutEmailsResponse {
+    fn empty() -> Self {
+        Self {}
+    }
+
+    fn merge(self, _b: Self) -> Self {
+        Self {}
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct Continuation(pub String);
@@ -42,16 +42,27 @@ pub(crate) struct ApiError {
 pub(crate) struct EmptySuccess {}
 
 impl ApiError {
-    fn into_error_kind(self, status_code: StatusCode) -> Error {
+    fn into_error_kind(self, status_code: StatusCode, request_id: Option<String>) -> Error {
         Error::Api {
             status_code,
             message: self.message.unwrap_or_default(),
+            request_id,
         }
     }
 }
 
 impl<'de, SuccessT: Deserialize<'de>> Response<SuccessT> {
     pub fn into_result(self, status_code: StatusCode) -> Result<SuccessT> {
+        self.into_result_with_request_id(status_code, None)
+    }
+
+    /// Like `into_result`, with the id the server gave the request included in errors to help
+    /// when contacting support.
+    pub fn into_result_with_request_id(
+        self,
+        status_code: StatusCode,
+        request_id: Option<String>,
+    ) -> Result<SuccessT> {
         match self {
             Response::Success(success) => {
                 if status_code.is_success() {
@@ -70,7 +81,7 @@ impl<'de, SuccessT: Deserialize<'de>> Response<SuccessT> {
                         message: error.message.unwrap_or_default(),
                     })
                 } else {
-                    Err(error.into_error_kind(status_code))
+                    Err(error.into_error_kind(status_code, request_id))
                 }
             }
         }
@@ -90,7 +90,7 @@ impl Display for TenantQuotaKind {
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct CreateQuota {
     pub hard_limit: u64,
 
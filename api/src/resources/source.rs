@@ -1,4 +1,6 @@
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
@@ -16,11 +18,20 @@ use crate::{
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct TransformTag(pub String);
 
+static TRANSFORM_TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]+\.[0-9]+\.[A-Za-z0-9_-]+$").unwrap());
+
 impl FromStr for TransformTag {
     type Err = Error;
 
     fn from_str(string: &str) -> Result<Self> {
-        Ok(Self(string.to_owned()))
+        if TRANSFORM_TAG_REGEX.is_match(string) {
+            Ok(Self(string.to_owned()))
+        } else {
+            Err(Error::BadTransformTag {
+                tag: string.to_owned(),
+            })
+        }
     }
 }
 
@@ -264,6 +275,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_source_only_serializes_provided_fields() {
+        let update = UpdateSource {
+            title: Some("New title"),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"title":"New title"}"#
+        );
+    }
+
+    #[test]
+    fn transform_tag_validation() {
+        assert!(TransformTag::from_str("generic.0.CONVKER5").is_ok());
+        assert!(TransformTag::from_str("generic.0").is_err());
+        assert!(TransformTag::from_str("generic.x.CONVKER5").is_err());
+        assert!(TransformTag::from_str("generic.0.CONVKER5 ").is_err());
+    }
+
     #[test]
     fn unknown_source_kind_roundtrips() {
         let kind = SourceKind::from_str("unknown").unwrap();
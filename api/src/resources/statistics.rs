@@ -12,8 +12,8 @@ pub(crate) struct GetResponse {
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct LabelCount {
-    positive: f32,
-    negative: f32,
+    pub positive: f32,
+    pub negative: f32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -139,99 +139,160 @@ pub(crate) struct GetResponse {
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub(crate) struct WelcomeEmailResponse {}
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
-#[serde(untagged)]
+#[derive(Debug, Clone, DeserializeFromStr, SerializeDisplay, PartialEq, Eq, Hash)]
 pub enum ProjectPermission {
-    // TODO(jcalero)[RE-978] There is a bug with the implementation of this enum that causes
-    // deserialization of non-Unknown properties to fail. See
-    // [RE-978](https://reinfer.atlassian.net/browse/RE-978) for more info.
-    #[serde(rename = "sources-add-comments")]
     CommentsAdmin,
-
-    #[serde(rename = "datasets-admin")]
     DatasetsAdmin,
-
-    #[serde(rename = "voc")]
     DatasetsWrite,
-
-    #[serde(rename = "datasets-review")]
     DatasetsReview,
-
-    #[serde(rename = "voc-readonly")]
     DatasetsRead,
-
-    #[serde(rename = "datasets-export")]
     DatasetsExport,
-
-    #[serde(rename = "sources-admin")]
     SourcesAdmin,
-
-    #[serde(rename = "sources-translate")]
     SourcesTranslate,
-
-    #[serde(rename = "sources-read")]
     SourcesRead,
-
-    #[serde(rename = "sources-read-sensitive")]
     SourcesReadSensitive,
-
-    #[serde(rename = "streams-admin")]
     StreamsAdmin,
-
-    #[serde(rename = "streams-consume")]
     StreamsConsume,
-
-    #[serde(rename = "streams-read")]
     StreamsRead,
-
-    #[serde(rename = "streams-write")]
     StreamsWrite,
-
-    #[serde(rename = "users-read")]
     UsersRead,
-
-    #[serde(rename = "users-write")]
     UsersWrite,
-
-    #[serde(rename = "buckets-read")]
     BucketsRead,
-
-    #[serde(rename = "buckets-write")]
     BucketsWrite,
-
-    #[serde(rename = "buckets-append")]
     BucketsAppend,
-
-    #[serde(rename = "files-write")]
     FilesWrite,
-
-    #[serde(rename = "appliance-config-read")]
     ApplianceConfigRead,
-
-    #[serde(rename = "appliance-config-write")]
     ApplianceConfigWrite,
-
-    #[serde(rename = "integrations-read")]
     IntegrationsRead,
-
-    #[serde(rename = "integrations-write")]
     IntegrationsWrite,
-
     Unknown(Box<str>),
 }
 
+const PROJECT_COMMENTS_ADMIN_AS_STR: &str = "sources-add-comments";
+const PROJECT_DATASETS_ADMIN_AS_STR: &str = "datasets-admin";
+const PROJECT_DATASETS_WRITE_AS_STR: &str = "voc";
+const PROJECT_DATASETS_REVIEW_AS_STR: &str = "datasets-review";
+const PROJECT_DATASETS_READ_AS_STR: &str = "voc-readonly";
+const PROJECT_DATASETS_EXPORT_AS_STR: &str = "datasets-export";
+const PROJECT_SOURCES_ADMIN_AS_STR: &str = "sources-admin";
+const PROJECT_SOURCES_TRANSLATE_AS_STR: &str = "sources-translate";
+const PROJECT_SOURCES_READ_AS_STR: &str = "sources-read";
+const PROJECT_SOURCES_READ_SENSITIVE_AS_STR: &str = "sources-read-sensitive";
+const PROJECT_STREAMS_ADMIN_AS_STR: &str = "streams-admin";
+const PROJECT_STREAMS_CONSUME_AS_STR: &str = "streams-consume";
+const PROJECT_STREAMS_READ_AS_STR: &str = "streams-read";
+const PROJECT_STREAMS_WRITE_AS_STR: &str = "streams-write";
+const PROJECT_USERS_READ_AS_STR: &str = "users-read";
+const PROJECT_USERS_WRITE_AS_STR: &str = "users-write";
+const PROJECT_BUCKETS_READ_AS_STR: &str = "buckets-read";
+const PROJECT_BUCKETS_WRITE_AS_STR: &str = "buckets-write";
+const PROJECT_BUCKETS_APPEND_AS_STR: &str = "buckets-append";
+const PROJECT_FILES_WRITE_AS_STR: &str = "files-write";
+const PROJECT_APPLIANCE_CONFIG_READ_AS_STR: &str = "appliance-config-read";
+const PROJECT_APPLIANCE_CONFIG_WRITE_AS_STR: &str = "appliance-config-write";
+const PROJECT_INTEGRATIONS_READ_AS_STR: &str = "integrations-read";
+const PROJECT_INTEGRATIONS_WRITE_AS_STR: &str = "integrations-write";
+
+impl ProjectPermission {
+    /// Names of all the project permissions known to this client.
+    pub const NAMES: &'static [&'static str] = &[
+        PROJECT_COMMENTS_ADMIN_AS_STR,
+        PROJECT_DATASETS_ADMIN_AS_STR,
+        PROJECT_DATASETS_WRITE_AS_STR,
+        PROJECT_DATASETS_REVIEW_AS_STR,
+        PROJECT_DATASETS_READ_AS_STR,
+        PROJECT_DATASETS_EXPORT_AS_STR,
+        PROJECT_SOURCES_ADMIN_AS_STR,
+        PROJECT_SOURCES_TRANSLATE_AS_STR,
+        PROJECT_SOURCES_READ_AS_STR,
+        PROJECT_SOURCES_READ_SENSITIVE_AS_STR,
+        PROJECT_STREAMS_ADMIN_AS_STR,
+        PROJECT_STREAMS_CONSUME_AS_STR,
+        PROJECT_STREAMS_READ_AS_STR,
+        PROJECT_STREAMS_WRITE_AS_STR,
+        PROJECT_USERS_READ_AS_STR,
+        PROJECT_USERS_WRITE_AS_STR,
+        PROJECT_BUCKETS_READ_AS_STR,
+        PROJECT_BUCKETS_WRITE_AS_STR,
+        PROJECT_BUCKETS_APPEND_AS_STR,
+        PROJECT_FILES_WRITE_AS_STR,
+        PROJECT_APPLIANCE_CONFIG_READ_AS_STR,
+        PROJECT_APPLIANCE_CONFIG_WRITE_AS_STR,
+        PROJECT_INTEGRATIONS_READ_AS_STR,
+        PROJECT_INTEGRATIONS_WRITE_AS_STR,
+    ];
+}
+
 impl FromStr for ProjectPermission {
     type Err = Error;
 
     fn from_str(string: &str) -> Result<Self> {
-        serde_json::de::from_str(&format!("\"{string}\"")).map_err(|_| {
-            Error::BadProjectPermission {
-                permission: string.into(),
-            }
+        Ok(match string {
+            PROJECT_COMMENTS_ADMIN_AS_STR => ProjectPermission::CommentsAdmin,
+            PROJECT_DATASETS_ADMIN_AS_STR => ProjectPermission::DatasetsAdmin,
+            PROJECT_DATASETS_WRITE_AS_STR => ProjectPermission::DatasetsWrite,
+            PROJECT_DATASETS_REVIEW_AS_STR => ProjectPermission::DatasetsReview,
+            PROJECT_DATASETS_READ_AS_STR => ProjectPermission::DatasetsRead,
+            PROJECT_DATASETS_EXPORT_AS_STR => ProjectPermission::DatasetsExport,
+            PROJECT_SOURCES_ADMIN_AS_STR => ProjectPermission::SourcesAdmin,
+            PROJECT_SOURCES_TRANSLATE_AS_STR => ProjectPermission::SourcesTranslate,
+            PROJECT_SOURCES_READ_AS_STR => ProjectPermission::SourcesRead,
+            PROJECT_SOURCES_READ_SENSITIVE_AS_STR => ProjectPermission::SourcesReadSensitive,
+            PROJECT_STREAMS_ADMIN_AS_STR => ProjectPermission::StreamsAdmin,
+            PROJECT_STREAMS_CONSUME_AS_STR => ProjectPermission::StreamsConsume,
+            PROJECT_STREAMS_READ_AS_STR => ProjectPermission::StreamsRead,
+            PROJECT_STREAMS_WRITE_AS_STR => ProjectPermission::StreamsWrite,
+            PROJECT_USERS_READ_AS_STR => ProjectPermission::UsersRead,
+            PROJECT_USERS_WRITE_AS_STR => ProjectPermission::UsersWrite,
+            PROJECT_BUCKETS_READ_AS_STR => ProjectPermission::BucketsRead,
+            PROJECT_BUCKETS_WRITE_AS_STR => ProjectPermission::BucketsWrite,
+            PROJECT_BUCKETS_APPEND_AS_STR => ProjectPermission::BucketsAppend,
+            PROJECT_FILES_WRITE_AS_STR => ProjectPermission::FilesWrite,
+            PROJECT_APPLIANCE_CONFIG_READ_AS_STR => ProjectPermission::ApplianceConfigRead,
+            PROJECT_APPLIANCE_CONFIG_WRITE_AS_STR => ProjectPermission::ApplianceConfigWrite,
+            PROJECT_INTEGRATIONS_READ_AS_STR => ProjectPermission::IntegrationsRead,
+            PROJECT_INTEGRATIONS_WRITE_AS_STR => ProjectPermission::IntegrationsWrite,
+            value => ProjectPermission::Unknown(value.into()),
         })
     }
 }
 
+impl Display for ProjectPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ProjectPermission::CommentsAdmin => PROJECT_COMMENTS_ADMIN_AS_STR,
+                ProjectPermission::DatasetsAdmin => PROJECT_DATASETS_ADMIN_AS_STR,
+                ProjectPermission::DatasetsWrite => PROJECT_DATASETS_WRITE_AS_STR,
+                ProjectPermission::DatasetsReview => PROJECT_DATASETS_REVIEW_AS_STR,
+                ProjectPermission::DatasetsRead => PROJECT_DATASETS_READ_AS_STR,
+                ProjectPermission::DatasetsExport => PROJECT_DATASETS_EXPORT_AS_STR,
+                ProjectPermission::SourcesAdmin => PROJECT_SOURCES_ADMIN_AS_STR,
+                ProjectPermission::SourcesTranslate => PROJECT_SOURCES_TRANSLATE_AS_STR,
+                ProjectPermission::SourcesRead => PROJECT_SOURCES_READ_AS_STR,
+                ProjectPermission::SourcesReadSensitive => PROJECT_SOURCES_READ_SENSITIVE_AS_STR,
+                ProjectPermission::StreamsAdmin => PROJECT_STREAMS_ADMIN_AS_STR,
+                ProjectPermission::StreamsConsume => PROJECT_STREAMS_CONSUME_AS_STR,
+                ProjectPermission::StreamsRead => PROJECT_STREAMS_READ_AS_STR,
+                ProjectPermission::StreamsWrite => PROJECT_STREAMS_WRITE_AS_STR,
+                ProjectPermission::UsersRead => PROJECT_USERS_READ_AS_STR,
+                ProjectPermission::UsersWrite => PROJECT_USERS_WRITE_AS_STR,
+                ProjectPermission::BucketsRead => PROJECT_BUCKETS_READ_AS_STR,
+                ProjectPermission::BucketsWrite => PROJECT_BUCKETS_WRITE_AS_STR,
+                ProjectPermission::BucketsAppend => PROJECT_BUCKETS_APPEND_AS_STR,
+                ProjectPermission::FilesWrite => PROJECT_FILES_WRITE_AS_STR,
+                ProjectPermission::ApplianceConfigRead => PROJECT_APPLIANCE_CONFIG_READ_AS_STR,
+                ProjectPermission::ApplianceConfigWrite => PROJECT_APPLIANCE_CONFIG_WRITE_AS_STR,
+                ProjectPermission::IntegrationsRead => PROJECT_INTEGRATIONS_READ_AS_STR,
+                ProjectPermission::IntegrationsWrite => PROJECT_INTEGRATIONS_WRITE_AS_STR,
+                ProjectPermission::Unknown(value) => value.as_ref(),
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, DeserializeFromStr, SerializeDisplay, PartialEq, Eq, Hash)]
 pub enum GlobalPermission {
     Root,
@@ -368,16 +429,44 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn specific_project_permission_roundtrips() {
-        // TODO(jcalero)[RE-978] This test was written to showcase bug RE-978. It demonstrates that
-        // deserialization of an `ProjectPermission` does not parse correctly.
-        let permission = ProjectPermission::DatasetsRead;
+    fn test_project_permissions_json_round_trip() {
+        let project_permissions = vec![
+            ProjectPermission::CommentsAdmin,
+            ProjectPermission::DatasetsAdmin,
+            ProjectPermission::DatasetsWrite,
+            ProjectPermission::DatasetsReview,
+            ProjectPermission::DatasetsRead,
+            ProjectPermission::DatasetsExport,
+            ProjectPermission::SourcesAdmin,
+            ProjectPermission::SourcesTranslate,
+            ProjectPermission::SourcesRead,
+            ProjectPermission::SourcesReadSensitive,
+            ProjectPermission::StreamsAdmin,
+            ProjectPermission::StreamsConsume,
+            ProjectPermission::StreamsRead,
+            ProjectPermission::StreamsWrite,
+            ProjectPermission::UsersRead,
+            ProjectPermission::UsersWrite,
+            ProjectPermission::BucketsRead,
+            ProjectPermission::BucketsWrite,
+            ProjectPermission::BucketsAppend,
+            ProjectPermission::FilesWrite,
+            ProjectPermission::ApplianceConfigRead,
+            ProjectPermission::ApplianceConfigWrite,
+            ProjectPermission::IntegrationsRead,
+            ProjectPermission::IntegrationsWrite,
+        ];
+        let project_permissions_as_json = serde_json::to_value(&project_permissions).unwrap();
 
         assert_eq!(
-            &serde_json::ser::to_string(&permission).unwrap(),
-            "\"voc-readonly\""
-        )
+            project_permissions_as_json,
+            serde_json::json!(ProjectPermission::NAMES)
+        );
+
+        let project_permissions_from_json: Vec<ProjectPermission> =
+            serde_json::from_value(project_permissions_as_json).unwrap();
+
+        assert_eq!(project_permissions, project_permissions_from_json);
     }
 
     #[test]
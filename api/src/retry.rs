@@ -29,6 +29,15 @@ pub struct RetryConfig {
     pub backoff_factor: f64,
 }
 
+/// The result of a request sent with retries.
+#[derive(Debug)]
+pub(crate) struct RetryOutcome {
+    pub result: Result<Response>,
+    /// Whether the request was retried the maximum number of times and the final attempt still
+    /// failed in a way which would have been retried.
+    pub retries_exhausted: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct Retrier {
     config: RetryConfig,
@@ -47,11 +56,26 @@ impl Retrier {
         status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
     }
 
-    pub fn with_retries(&self, send_request: impl Fn() -> Result<Response>) -> Result<Response> {
+    fn is_retryable(result: &Result<Response>) -> bool {
+        match result {
+            Ok(response) => Self::should_retry(response.status()),
+            Err(error) => error.is_timeout() || error.is_connect() || error.is_request(),
+        }
+    }
+
+    pub fn max_retry_count(&self) -> u8 {
+        self.config.max_retry_count
+    }
+
+    /// Sends the request, retrying according to the config.
+    pub fn with_retries(&self, send_request: impl Fn() -> Result<Response>) -> RetryOutcome {
         if self.is_first_request.swap(false, SeqCst)
             && self.config.strategy == RetryStrategy::Automatic
         {
-            return send_request();
+            return RetryOutcome {
+                result: send_request(),
+                retries_exhausted: false,
+            };
         }
 
         for i_retry in 0..self.config.max_retry_count {
@@ -72,12 +96,21 @@ impl Retrier {
                     warn_and_sleep!(error)
                 }
                 // If anything else, just return it immediately
-                result => return result,
+                result => {
+                    return RetryOutcome {
+                        result,
+                        retries_exhausted: false,
+                    }
+                }
             }
         }
 
         // On last retry don't handle the error, just propagate all errors.
-        send_request()
+        let result = send_request();
+        RetryOutcome {
+            retries_exhausted: self.config.max_retry_count > 0 && Self::is_retryable(&result),
+            result,
+        }
     }
 }
 
@@ -103,6 +136,7 @@ mod tests {
         assert!(
             handler
                 .with_retries(|| get(format!("http://{}", server_address())))
+                .result
                 .unwrap()
                 .status()
                 == 200
@@ -119,6 +153,7 @@ mod tests {
             assert!(
                 handler
                     .with_retries(|| get(format!("http://{}", server_address())))
+                    .result
                     .unwrap()
                     .status()
                     == 500
@@ -141,6 +176,7 @@ mod tests {
         assert!(
             handler
                 .with_retries(|| get(format!("http://{}", server_address())))
+                .result
                 .unwrap()
                 .status()
                 == 500
@@ -152,6 +188,7 @@ mod tests {
         assert!(
             handler
                 .with_retries(|| get(format!("http://{}", server_address())))
+                .result
                 .unwrap()
                 .status()
                 == 200
@@ -168,6 +205,7 @@ mod tests {
             assert!(
                 handler
                     .with_retries(|| get(format!("http://{}", server_address())))
+                    .result
                     .unwrap()
                     .status()
                     == 500
@@ -176,6 +214,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_retries_exhausted() {
+        let handler = Retrier::new(RetryConfig {
+            strategy: RetryStrategy::Always,
+            max_retry_count: 2,
+            base_wait: Duration::from_secs(0),
+            backoff_factor: 0.0,
+        });
+
+        let err = mock("GET", "/").with_status(503).expect(3).create();
+        let outcome = handler.with_retries(|| get(format!("http://{}", server_address())));
+        assert_eq!(outcome.result.unwrap().status(), 503);
+        assert!(outcome.retries_exhausted);
+        err.assert();
+
+        let err = mock("GET", "/").with_status(400).expect(1).create();
+        let outcome = handler.with_retries(|| get(format!("http://{}", server_address())));
+        assert_eq!(outcome.result.unwrap().status(), 400);
+        assert!(!outcome.retries_exhausted);
+        err.assert();
+    }
+
     #[test]
     fn test_timeout_retry() {
         let handler = Retrier::new(RetryConfig {
@@ -204,6 +264,7 @@ mod tests {
                     let _ = r.text()?;
                     unreachable!()
                 }))
+            .result
             .unwrap_err()
             .is_timeout());
         timeout.assert();
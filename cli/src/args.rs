@@ -1,7 +1,7 @@
 use crate::{
     commands::{
         config::ConfigArgs, create::CreateArgs, delete::DeleteArgs, get::GetArgs, parse::ParseArgs,
-        update::UpdateArgs,
+        schema::SchemaKind, update::UpdateArgs,
     },
     printer::OutputFormat,
 };
@@ -13,6 +13,10 @@ use structopt::StructOpt;
 /// re is the command line interface to reinfer clusters.
 #[derive(Debug, StructOpt)]
 #[structopt(
+    after_help = "EXIT CODES:\n    1  Other errors\n    3  Authentication or permission error\n    \
+                  4  Resource not found\n    5  Invalid request\n    6  Server error\n    \
+                  7  Network error\n    8  Local IO error\n    \
+                  9  Transient error, after all retries",
     global_settings = &[
         structopt::clap::AppSettings::ColoredHelp,
         structopt::clap::AppSettings::InferSubcommands,
@@ -27,9 +31,24 @@ pub struct Args {
     /// Specify what context to use. Overrides the current context, if any.
     pub context: Option<String>,
 
-    #[structopt(short = "v", long = "verbose")]
-    /// Enable more verbose logging.
-    pub verbose: bool,
+    #[structopt(short = "v", long = "verbose", parse(from_occurrences))]
+    /// Enable more verbose logging. Pass twice (-vv) to also trace request and response bodies
+    /// (debug builds only).
+    pub verbose: u8,
+
+    #[structopt(short = "q", long = "quiet")]
+    /// Only log errors and don't display progress bars. Takes precedence over --verbose.
+    pub quiet: bool,
+
+    #[structopt(long = "log-format")]
+    /// Format of the logs written to stderr. One of: human, json. Can also be set with the
+    /// REINFER_CLI_LOG_FORMAT environment variable. Progress bars are disabled when using json.
+    pub log_format: Option<LogFormat>,
+
+    #[structopt(long = "error-output", default_value = "human")]
+    /// How to report a failed command. One of: human, json. With json, a JSON object with the
+    /// error chain, any API status and request id, and the exit code is written to stdout.
+    pub error_output: ErrorOutput,
 
     #[structopt(long = "endpoint", parse(try_from_str))]
     /// Specify what endpoint to use. Overrides the one from the current
@@ -48,12 +67,81 @@ pub struct Args {
     /// URL for an HTTP proxy that will be used for all requests if specified
     pub proxy: Option<Url>,
 
+    #[structopt(long = "cache-dir", parse(from_os_str))]
+    /// Directory in which to cache responses of GET requests (e.g. `get datasets`, `get
+    /// sources`). Cached responses are revalidated with the API using ETags.
+    pub cache_dir: Option<PathBuf>,
+
+    #[structopt(long = "print-curl")]
+    /// Print an equivalent curl command to stderr for each API request (with the token masked)
+    pub print_curl: bool,
+
+    #[structopt(long = "print-curl-only")]
+    /// Like --print-curl, but don't actually send the requests. The command stops at the first
+    /// request, so this is most useful to generate reproductions of a single API call.
+    pub print_curl_only: bool,
+
+    #[structopt(long = "split-wait-ms", default_value = "0")]
+    /// Milliseconds to wait between the requests for each item when a batch upload which
+    /// resumes on error is split after failing. The wait doubles after each failed item.
+    pub split_wait_ms: u64,
+
+    #[structopt(long = "max-split-size")]
+    /// Largest failed batch to split into requests for each item when uploading with resume on
+    /// error. Larger failed batches are skipped as a whole.
+    pub max_split_size: Option<usize>,
+
+    #[structopt(long = "user-agent")]
+    /// User agent to send with each request. Can also be set with the REINFER_CLI_USER_AGENT
+    /// environment variable. Defaults to `reinfer-cli/<version>`.
+    pub user_agent: Option<String>,
+
+    #[structopt(long = "pool-max-idle-per-host")]
+    /// Maximum number of idle connections to keep open for reuse. Defaults to no limit. When
+    /// lowering it, keep it at least as high as --num-threads (or the concurrency of the
+    /// command), otherwise concurrent requests will keep opening new TLS connections.
+    pub pool_max_idle_per_host: Option<usize>,
+
+    #[structopt(long = "pool-idle-timeout-secs")]
+    /// Seconds to keep idle connections open for reuse. Defaults to 90.
+    pub pool_idle_timeout_secs: Option<u64>,
+
+    #[structopt(long = "http1-only")]
+    /// Only use HTTP/1.1, for networks with proxies which mishandle HTTP/2.
+    pub http1_only: bool,
+
+    #[structopt(long = "attachment-timeout-seconds")]
+    /// Timeout for requests which download or upload attachments and audio, which can take much
+    /// longer than other requests. Defaults to the timeout for all requests (240 seconds).
+    pub attachment_timeout_seconds: Option<u64>,
+
+    #[structopt(long = "max-response-bytes")]
+    /// Largest response body to accept from the API, e.g. for attachments. Defaults to 2 GiB.
+    pub max_response_bytes: Option<u64>,
+
+    #[structopt(long = "owner", global = true)]
+    /// Project to assume for source, dataset and bucket names given without one, so that
+    /// `my-source` means `<owner>/my-source`. Names given as `project/name` are used as is.
+    pub owner: Option<String>,
+
+    #[structopt(long = "no-preflight")]
+    /// Don't fetch the current user to check that the endpoint can be reached before running the
+    /// command.
+    pub no_preflight: bool,
+
     #[structopt(short = "o", long = "output", default_value = "table")]
-    /// Output format. One of: json, table
+    /// Output format. One of: json, yaml, table
     ///
-    /// Output is provided in table format on stdout by default.
+    /// Output is provided in table format on stdout by default. With yaml, each resource is
+    /// emitted as a separate `---`-separated document. Commands which stream comments or emails
+    /// always write JSONL.
     pub output: OutputFormat,
 
+    #[structopt(long = "columns", use_delimiter = true)]
+    /// Comma separated list of columns to display when using the table output format, e.g.
+    /// `--columns name,id,title`. Defaults to all columns for the resource type.
+    pub columns: Option<Vec<String>>,
+
     #[structopt(subcommand)]
     pub command: Command,
 
@@ -111,6 +199,10 @@ pub enum Command {
         #[structopt(subcommand)]
         parse_args: ParseArgs,
     },
+
+    #[structopt(name = "schema")]
+    /// Output the JSON schema of an input format (comments)
+    Schema { kind: SchemaKind },
 }
 
 #[derive(Debug)]
@@ -130,3 +222,44 @@ impl FromStr for Shell {
         }
     }
 }
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ErrorOutput {
+    #[default]
+    Human,
+    Json,
+}
+
+impl FromStr for ErrorOutput {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "human" => Ok(ErrorOutput::Human),
+            "json" => Ok(ErrorOutput::Json),
+            _ => Err(anyhow!("unknown error output: '{}'", string)),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "human" => Ok(LogFormat::Human),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(anyhow!(
+                "unknown log format: '{}', expected one of: human, json",
+                string
+            )),
+        }
+    }
+}
@@ -37,6 +37,11 @@ pub enum ConfigArgs {
         #[structopt(long = "proxy")]
         /// URL for an HTTP proxy that will be used for all requests if specified
         proxy: Option<Option<Url>>,
+
+        #[structopt(long = "proxy-exclusions", use_delimiter = true)]
+        /// Comma separated hosts, domain suffixes or CIDR blocks which should bypass the proxy
+        /// (in addition to those in the `NO_PROXY` environment variable)
+        proxy_exclusions: Option<Vec<String>>,
     },
 
     /// Output the token for a given context or the current one if unspecified.
@@ -109,9 +114,7 @@ pub fn run(
             for context in contexts.iter() {
                 let active = config
                     .get_current_context()
-                    .map_or(false, |current_context| {
-                        current_context.name == context.name
-                    });
+                    .is_some_and(|current_context| current_context.name == context.name);
                 table.add_row(row![
                     if active { "    ->" } else { "" },
                     if active {
@@ -148,6 +151,7 @@ pub fn run(
             token,
             accept_invalid_certificates,
             proxy,
+            proxy_exclusions,
         } => {
             add_or_edit_context(
                 name,
@@ -155,6 +159,7 @@ pub fn run(
                 endpoint,
                 *accept_invalid_certificates,
                 proxy,
+                proxy_exclusions,
                 config.clone(),
                 config_path,
             )?;
@@ -268,17 +273,20 @@ fn parse_context_from_url(
         &Some(url),
         false,
         &None,
+        &None,
         config,
         config_path,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_or_edit_context(
     name: &Option<String>,
     token: &Option<String>,
     endpoint: &Option<Url>,
     accept_invalid_certificates: bool,
     proxy: &Option<Option<Url>>,
+    proxy_exclusions: &Option<Vec<String>>,
     mut config: ReinferConfig,
     config_path: impl AsRef<Path>,
 ) -> Result<()> {
@@ -351,6 +359,12 @@ fn add_or_edit_context(
                 .as_ref()
                 .and_then(|context| context.proxy.clone())
         }),
+        proxy_exclusions: proxy_exclusions.clone().unwrap_or_else(|| {
+            existing_context
+                .as_ref()
+                .map(|context| context.proxy_exclusions.clone())
+                .unwrap_or_default()
+        }),
     };
 
     let update_existing = existing_context.is_some();
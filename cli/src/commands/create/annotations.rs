@@ -1,4 +1,5 @@
 use crate::progress::{Options as ProgressOptions, Progress};
+use crate::utils::{NameArg, Resolver};
 use anyhow::{Context, Result};
 use colored::Colorize;
 use log::info;
@@ -29,11 +30,11 @@ pub struct CreateAnnotationsArgs {
 
     #[structopt(short = "s", long = "source")]
     /// Name or id of the source containing the annotated comments
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
 
     #[structopt(short = "d", long = "dataset")]
     /// Dataset (name or id) where to push the annotations. The dataset must contain the source.
-    dataset: DatasetIdentifier,
+    dataset: NameArg<DatasetIdentifier>,
 
     #[structopt(long)]
     /// Don't display a progress bar (only applicable when --file is used).
@@ -48,14 +49,19 @@ pub struct CreateAnnotationsArgs {
     resume_on_error: bool,
 }
 
-pub fn create(client: &Client, args: &CreateAnnotationsArgs, pool: &mut Pool) -> Result<()> {
-    let source = client
-        .get_source(args.source.clone())
+pub fn create(
+    client: &Client,
+    resolver: &Resolver,
+    args: &CreateAnnotationsArgs,
+    pool: &mut Pool,
+) -> Result<()> {
+    let source = resolver
+        .get_source(client, resolver.resolve(&args.source)?)
         .with_context(|| format!("Unable to get source {}", args.source))?;
     let source_name = source.full_name();
 
-    let dataset = client
-        .get_dataset(args.dataset.clone())
+    let dataset = resolver
+        .get_dataset(client, resolver.resolve(&args.dataset)?)
         .with_context(|| format!("Unable to get dataset {}", args.dataset))?;
     let dataset_name = dataset.full_name();
 
@@ -1,4 +1,7 @@
-use crate::printer::Printer;
+use crate::{
+    printer::Printer,
+    utils::{NameArg, Resolver},
+};
 use anyhow::{Context, Result};
 use log::info;
 use reinfer_client::{BucketFullName, BucketType, Client, NewBucket};
@@ -8,27 +11,33 @@ use structopt::StructOpt;
 pub struct CreateBucketArgs {
     #[structopt(name = "bucket-name")]
     /// Full name of the new bucket <owner>/<name>
-    name: BucketFullName,
+    name: NameArg<BucketFullName>,
 
     #[structopt(long = "title")]
     /// Set the title of the new bucket
     title: Option<String>,
 
-    #[structopt(default_value, long = "type")]
-    /// Set the type of the new bucket. Currently, this must be "emails".
+    #[structopt(default_value, long = "type", possible_values = BucketType::NAMES)]
+    /// Set the type of the new bucket.
     bucket_type: BucketType,
 }
 
-pub fn create(client: &Client, args: &CreateBucketArgs, printer: &Printer) -> Result<()> {
+pub fn create(
+    client: &Client,
+    resolver: &Resolver,
+    args: &CreateBucketArgs,
+    printer: &Printer,
+) -> Result<()> {
     let CreateBucketArgs {
         name,
         title,
         bucket_type,
     } = args;
+    let name = resolver.resolve(name)?;
 
     let bucket = client
         .create_bucket(
-            name,
+            &name,
             NewBucket {
                 title: title.as_deref(),
                 bucket_type: *bucket_type,
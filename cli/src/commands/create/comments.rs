@@ -1,3 +1,4 @@
+use crate::utils::{NameArg, Resolver};
 use crate::{
     commands::{
         create::annotations::{
@@ -6,24 +7,27 @@ use crate::{
         ensure_uip_user_consents_to_ai_unit_charge, LocalAttachmentPath,
     },
     progress::{Options as ProgressOptions, Progress},
+    utils,
 };
-use anyhow::{anyhow, ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use colored::Colorize;
-use log::{debug, info};
+use log::{debug, info, warn};
 use reinfer_client::{
     resources::attachments::AttachmentMetadata, Client, CommentId, DatasetFullName,
     DatasetIdentifier, NewAnnotatedComment, NewComment, Source, SourceId, SourceIdentifier,
 };
 use scoped_threadpool::Pool;
+use serde::Deserialize;
 use std::{
-    collections::HashSet,
-    fs::File,
-    io::{self, BufRead, BufReader, Seek},
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
+    time::Instant,
 };
 
 use structopt::StructOpt;
@@ -33,17 +37,19 @@ use super::annotations::AttachmentStatistic;
 #[derive(Debug, StructOpt)]
 pub struct CreateCommentsArgs {
     #[structopt(short = "f", long = "file", parse(from_os_str))]
-    /// Path to JSON file with comments. If not specified, stdin will be used.
+    /// Path to JSON file with comments. If not specified, stdin will be used. Paths ending in
+    /// `.gz` are decompressed; checking them for duplicate ids reads the file twice. Run
+    /// `re schema comments` for the JSON schema of each line.
     comments_path: Option<PathBuf>,
 
     #[structopt(short = "s", long = "source")]
     /// Name or id of the source where the comments will be uploaded.
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
 
     #[structopt(short = "d", long = "dataset")]
     /// Optionally, a dataset (name or id) where to push the annotations. The
     /// dataset must contain the source.
-    dataset: Option<DatasetIdentifier>,
+    dataset: Option<NameArg<DatasetIdentifier>>,
 
     #[structopt(long = "batch-size", default_value = "128")]
     /// Number of comments to batch in a single request.
@@ -76,12 +82,41 @@ pub struct CreateCommentsArgs {
     /// Whether to attempt to resume processing on error
     resume_on_error: bool,
 
+    #[structopt(
+        long = "failed-ids-file",
+        parse(from_os_str),
+        requires = "resume-on-error"
+    )]
+    /// Path where to write the ids of the comments which failed to upload, one per line (only
+    /// applicable with --resume-on-error).
+    failed_ids_path: Option<PathBuf>,
+
     #[structopt(short = "a", long = "attachments", parse(from_os_str))]
     /// Path to folder containing the attachemtns to upload
     attachments_dir: Option<PathBuf>,
+
+    #[structopt(
+        long = "attachments-manifest",
+        parse(from_os_str),
+        conflicts_with = "attachments-dir"
+    )]
+    /// Path to a jsonl file of `{"comment_id": .., "path": .., "index": ..}` records, with
+    /// attachment files to upload for the comments once they are created. Relative paths are
+    /// resolved against the directory of the manifest.
+    attachments_manifest: Option<PathBuf>,
+
+    #[structopt(long = "skip-missing-attachments")]
+    /// Upload comments whose attachment or audio files are missing without those files, instead
+    /// of failing before the upload starts.
+    skip_missing_attachments: bool,
 }
 
-pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Result<()> {
+pub fn create(
+    client: &Client,
+    resolver: &Resolver,
+    args: &CreateCommentsArgs,
+    pool: &mut Pool,
+) -> Result<()> {
     if !args.no_charge && !args.yes {
         ensure_uip_user_consents_to_ai_unit_charge(client.base_url())?;
     }
@@ -99,22 +134,30 @@ pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Re
         )
     }
 
-    let source = client
-        .get_source(args.source.clone())
+    let attachments_manifest = match &args.attachments_manifest {
+        Some(manifest_path) => {
+            read_attachments_manifest(manifest_path, args.skip_missing_attachments)?
+        }
+        None => AttachmentsManifest::new(),
+    };
+
+    let source = resolver
+        .get_source(client, resolver.resolve(&args.source)?)
         .with_context(|| format!("Unable to get source {}", args.source))?;
 
     let source_name = source.full_name();
 
     let dataset_name = match args.dataset.as_ref() {
         Some(dataset_ident) => Some(
-            client
-                .get_dataset(dataset_ident.clone())
+            resolver
+                .get_dataset(client, resolver.resolve(dataset_ident)?)
                 .with_context(|| format!("Unable to get dataset {}", args.source))?
                 .full_name(),
         ),
         None => None,
     };
 
+    let start = Instant::now();
     let statistics = match &args.comments_path {
         Some(comments_path) => {
             info!(
@@ -123,38 +166,46 @@ pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Re
                 source_name.0,
                 source.id.0,
             );
-            let mut file =
-                BufReader::new(File::open(comments_path).with_context(|| {
-                    format!("Could not open file `{}`", comments_path.display())
-                })?);
-            let file_metadata = file.get_ref().metadata().with_context(|| {
+            let file_metadata = fs::metadata(comments_path).with_context(|| {
                 format!(
                     "Could not get file metadata for `{}`",
                     comments_path.display()
                 )
             })?;
 
-            if !args.allow_duplicates {
-                debug!(
-                    "Checking `{}` for duplicate comment ids",
-                    comments_path.display(),
-                );
-                check_no_duplicate_ids(&mut file)?;
-
-                file.rewind().with_context(|| {
-                    "Unable to seek to file start after checking for duplicate ids"
-                })?;
+            debug!("Checking `{}` before uploading", comments_path.display());
+            // The file is opened again for the upload below, as compressed files can't be
+            // rewound.
+            let missing_files = check_comments(
+                utils::open_input_file(comments_path)?,
+                args.allow_duplicates,
+                args.attachments_dir.as_deref(),
+            )?;
+            if !missing_files.is_empty() {
+                let missing_files = missing_files
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n- ");
+                if args.skip_missing_attachments {
+                    warn!("Skipping missing attachment files:\n- {missing_files}");
+                } else {
+                    bail!(
+                        "Missing attachment files, pass --skip-missing-attachments to upload without them:\n- {missing_files}"
+                    );
+                }
             }
+            let file = utils::open_input_file(comments_path)?;
 
             let statistics = Arc::new(Statistics::new());
             let progress = if args.no_progress {
                 None
             } else {
-                Some(progress_bar(
-                    file_metadata.len(),
-                    &statistics,
-                    args.overwrite,
-                ))
+                // Progress is tracked in uncompressed bytes, so the total is unknown for
+                // compressed files.
+                let total_bytes =
+                    (!utils::is_gzip_path(comments_path)).then_some(file_metadata.len());
+                Some(progress_bar(total_bytes, &statistics, args.overwrite))
             };
             upload_comments_from_reader(
                 client,
@@ -169,6 +220,8 @@ pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Re
                 pool,
                 args.resume_on_error,
                 &args.attachments_dir,
+                &attachments_manifest,
+                args.skip_missing_attachments,
             )?;
             if let Some(mut progress) = progress {
                 progress.done();
@@ -198,11 +251,17 @@ pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Re
                 pool,
                 args.resume_on_error,
                 &args.attachments_dir,
+                &attachments_manifest,
+                args.skip_missing_attachments,
             )?;
             statistics
         }
     };
 
+    if let Some(failed_ids_path) = &args.failed_ids_path {
+        write_failed_ids(failed_ids_path, &statistics.failed_comment_ids())?;
+    }
+
     if args.overwrite {
         info!(
             concat!(
@@ -226,6 +285,17 @@ pub fn create(client: &Client, args: &CreateCommentsArgs, pool: &mut Pool) -> Re
             statistics.num_failed_comments()
         );
     }
+    info!(
+        "{}",
+        utils::throughput_summary(
+            start.elapsed(),
+            &[
+                (statistics.num_uploaded(), "comments"),
+                (statistics.num_annotations(), "annotations"),
+                (statistics.num_attachments(), "attachments"),
+            ]
+        )
+    );
 
     Ok(())
 }
@@ -262,20 +332,128 @@ fn read_comments_iter<'a>(
     })
 }
 
-fn check_no_duplicate_ids(comments: impl BufRead) -> Result<()> {
+/// Check for duplicate ids (unless allowed) and return the attachment and audio files referenced
+/// by the comments which can't be read.
+fn check_comments(
+    comments: impl BufRead,
+    allow_duplicates: bool,
+    attachments_dir: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
     let mut seen = HashSet::new();
+    let mut missing_files = Vec::new();
     for read_comment_result in read_comments_iter(comments, None) {
         let new_comment = read_comment_result?;
-        let id = new_comment.comment.id;
+        let id = &new_comment.comment.id;
 
-        if !seen.insert(id.clone()) {
+        if !allow_duplicates && !seen.insert(id.clone()) {
             return Err(anyhow!("Duplicate comments with id {}", id.0));
         }
+
+        let attachment_paths = attachments_dir.into_iter().flat_map(|attachments_dir| {
+            new_comment
+                .comment
+                .attachments
+                .iter()
+                .enumerate()
+                .map(|(index, attachment)| {
+                    local_attachment_path(id, index, &attachment.name, attachments_dir)
+                })
+        });
+        for path in attachment_paths.chain(new_comment.audio_path.clone()) {
+            if File::open(&path).is_err() {
+                missing_files.push(path);
+            }
+        }
+    }
+
+    Ok(missing_files)
+}
+
+#[derive(Debug, Deserialize)]
+struct AttachmentsManifestEntry {
+    comment_id: CommentId,
+    path: PathBuf,
+    index: usize,
+}
+
+/// Attachment files to upload for each comment, as `(index, path)`.
+type AttachmentsManifest = HashMap<CommentId, Vec<(usize, PathBuf)>>;
+
+fn read_attachments_manifest(
+    manifest_path: &Path,
+    skip_missing_attachments: bool,
+) -> Result<AttachmentsManifest> {
+    let manifest_dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut manifest = AttachmentsManifest::new();
+    let mut missing_files = Vec::new();
+    for (index, line) in utils::open_input_file(manifest_path)?.lines().enumerate() {
+        let line = line.with_context(|| format!("Could not read line {}", index + 1))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: AttachmentsManifestEntry = serde_json::from_str(&line).with_context(|| {
+            format!(
+                "Could not parse attachments manifest entry at line {}",
+                index + 1
+            )
+        })?;
+
+        let path = manifest_dir.join(entry.path);
+        if File::open(&path).is_err() {
+            missing_files.push(path.display().to_string());
+            continue;
+        }
+        manifest
+            .entry(entry.comment_id)
+            .or_default()
+            .push((entry.index, path));
+    }
+
+    if !missing_files.is_empty() {
+        let missing_files = missing_files.join("\n- ");
+        if skip_missing_attachments {
+            warn!("Skipping missing attachment files:\n- {missing_files}");
+        } else {
+            bail!(
+                "Missing attachment files, pass --skip-missing-attachments to upload without them:\n- {missing_files}"
+            );
+        }
     }
+    Ok(manifest)
+}
 
+fn write_failed_ids(path: &Path, ids: &[String]) -> Result<()> {
+    let mut file = utils::create_output_file(path)?;
+    for id in ids {
+        writeln!(file, "{id}")
+            .with_context(|| format!("Could not write to `{}`", path.display()))?;
+    }
+    file.flush()
+        .with_context(|| format!("Could not write to `{}`", path.display()))?;
+    if !ids.is_empty() {
+        info!(
+            "Wrote the ids of {} failed comments to `{}`",
+            ids.len(),
+            path.display()
+        );
+    }
     Ok(())
 }
 
+fn local_attachment_path(
+    comment_id: &CommentId,
+    index: usize,
+    name: &str,
+    attachments_dir: &Path,
+) -> PathBuf {
+    LocalAttachmentPath {
+        index,
+        name: name.to_owned(),
+        parent_dir: attachments_dir.join(&comment_id.0),
+    }
+    .path()
+}
+
 fn upload_local_attachment(
     comment_id: &CommentId,
     attachment: &mut AttachmentMetadata,
@@ -284,13 +462,10 @@ fn upload_local_attachment(
     attachments_dir: &Path,
     source_id: &SourceId,
 ) -> Result<()> {
-    let local_attachemnt = LocalAttachmentPath {
-        index,
-        name: attachment.name.clone(),
-        parent_dir: attachments_dir.join(&comment_id.0),
-    };
+    let local_attachment =
+        local_attachment_path(comment_id, index, &attachment.name, attachments_dir);
 
-    match client.upload_comment_attachment(source_id, comment_id, index, &local_attachemnt.path()) {
+    match client.upload_comment_attachment(source_id, comment_id, index, &local_attachment) {
         Ok(response) => {
             attachment.attachment_reference = None;
             attachment.content_hash = Some(response.content_hash);
@@ -310,9 +485,19 @@ fn upload_attachments_for_comments(
     statistics: &Statistics,
     source_id: &SourceId,
     resume_on_error: bool,
+    skip_missing_attachments: bool,
 ) -> Result<()> {
     for comment in comments.iter_mut() {
         for (index, attachment) in comment.attachments.iter_mut().enumerate() {
+            if skip_missing_attachments
+                && !local_attachment_path(&comment.id, index, &attachment.name, attachments_dir)
+                    .is_file()
+            {
+                attachment.attachment_reference = None;
+                statistics.add_failed_attachment();
+                continue;
+            }
+
             match upload_local_attachment(
                 &comment.id,
                 attachment,
@@ -345,15 +530,18 @@ fn upload_batch_of_comments(
     comments_to_put: &mut Vec<NewComment>,
     comments_to_sync: &mut Vec<NewComment>,
     audio_paths: &mut Vec<(CommentId, PathBuf)>,
+    manifest_attachments: &mut Vec<(CommentId, usize, PathBuf)>,
     no_charge: bool,
     attachments_dir: &Option<PathBuf>,
     resume_on_error: bool,
+    skip_missing_attachments: bool,
 ) -> Result<()> {
     let mut uploaded = 0;
     let mut new = 0;
     let mut updated = 0;
     let mut unchanged = 0;
-    let mut failed = 0;
+    let mut put_failed = 0;
+    let mut sync_failed = 0;
 
     // Upload comments
     if !comments_to_put.is_empty() {
@@ -365,6 +553,7 @@ fn upload_batch_of_comments(
                 statistics,
                 &source.id,
                 resume_on_error,
+                skip_missing_attachments,
             )?;
         }
 
@@ -376,13 +565,14 @@ fn upload_batch_of_comments(
                     no_charge,
                 )
                 .context("Could not put batch of comments")?;
-            failed += result.num_failed;
+            put_failed = result.num_failed;
+            statistics.add_failed_comment_ids(result.failed_ids);
         } else {
             client
                 .put_comments(&source.full_name(), comments_to_put.to_vec(), no_charge)
                 .context("Could not put batch of comments")?;
         }
-        uploaded += comments_to_put.len() - failed;
+        uploaded += comments_to_put.len() - put_failed;
     }
 
     if !comments_to_sync.is_empty() {
@@ -394,6 +584,7 @@ fn upload_batch_of_comments(
                 statistics,
                 &source.id,
                 resume_on_error,
+                skip_missing_attachments,
             )?;
         }
         let result = if resume_on_error {
@@ -404,7 +595,8 @@ fn upload_batch_of_comments(
                     no_charge,
                 )
                 .context("Could not sync batch of comments")?;
-            failed += result.num_failed;
+            sync_failed = result.num_failed;
+            statistics.add_failed_comment_ids(result.failed_ids);
             result.response
         } else {
             client
@@ -412,7 +604,7 @@ fn upload_batch_of_comments(
                 .context("Could not sync batch of comments")?
         };
 
-        uploaded += comments_to_sync.len() - failed;
+        uploaded += comments_to_sync.len() - sync_failed;
         new += result.new;
         updated += result.updated;
         unchanged += result.unchanged;
@@ -423,11 +615,14 @@ fn upload_batch_of_comments(
         new,
         updated,
         unchanged,
-        failed,
+        failed: put_failed + sync_failed,
     });
 
     // Upload audio
     for (comment_id, audio_path) in audio_paths.iter() {
+        if skip_missing_attachments && !audio_path.is_file() {
+            continue;
+        }
         client
             .put_comment_audio(&source.id, comment_id, audio_path)
             .with_context(|| {
@@ -438,9 +633,33 @@ fn upload_batch_of_comments(
                 )
             })?;
     }
+
+    // Upload attachments from the manifest, now that their comments exist
+    for (comment_id, index, path) in manifest_attachments.iter() {
+        match client
+            .upload_comment_attachment(&source.id, comment_id, *index, path)
+            .with_context(|| {
+                format!(
+                    "Could not upload attachment file at `{}` for comment id `{}`",
+                    path.display(),
+                    comment_id.0,
+                )
+            }) {
+            Ok(_) => statistics.add_attachment(),
+            Err(err) => {
+                if resume_on_error {
+                    statistics.add_failed_attachment();
+                } else {
+                    return Err(err);
+                }
+            }
+        }
+    }
+
     comments_to_put.clear();
     comments_to_sync.clear();
     audio_paths.clear();
+    manifest_attachments.clear();
 
     Ok(())
 }
@@ -459,6 +678,8 @@ fn upload_comments_from_reader(
     pool: &mut Pool,
     resume_on_error: bool,
     attachments_dir: &Option<PathBuf>,
+    attachments_manifest: &AttachmentsManifest,
+    skip_missing_attachments: bool,
 ) -> Result<()> {
     assert!(batch_size > 0);
 
@@ -466,6 +687,7 @@ fn upload_comments_from_reader(
     let mut comments_to_sync = Vec::new();
     let mut annotations = Vec::new();
     let mut audio_paths = Vec::new();
+    let mut manifest_attachments = Vec::new();
 
     // if --overwrite, everything will go to comments_to_sync, so put the default capacity there.
     if overwrite {
@@ -487,7 +709,7 @@ fn upload_comments_from_reader(
                 },
                 labelling: new_comment.labelling,
                 entities: new_comment.entities,
-                moon_forms: new_comment.moon_forms.map(Into::into),
+                moon_forms: new_comment.moon_forms,
             });
         }
 
@@ -495,6 +717,14 @@ fn upload_comments_from_reader(
             audio_paths.push((new_comment.comment.id.clone(), audio_path));
         }
 
+        if let Some(attachments) = attachments_manifest.get(&new_comment.comment.id) {
+            manifest_attachments.extend(
+                attachments
+                    .iter()
+                    .map(|(index, path)| (new_comment.comment.id.clone(), *index, path.clone())),
+            );
+        }
+
         if should_sync_comment(&new_comment.comment.id) {
             comments_to_sync.push(new_comment.comment);
         } else {
@@ -509,9 +739,11 @@ fn upload_comments_from_reader(
                 &mut comments_to_put,
                 &mut comments_to_sync,
                 &mut audio_paths,
+                &mut manifest_attachments,
                 no_charge,
                 attachments_dir,
                 resume_on_error,
+                skip_missing_attachments,
             )?;
         }
 
@@ -524,9 +756,11 @@ fn upload_comments_from_reader(
                     &mut comments_to_put,
                     &mut comments_to_sync,
                     &mut audio_paths,
+                    &mut manifest_attachments,
                     no_charge,
                     attachments_dir,
                     resume_on_error,
+                    skip_missing_attachments,
                 )?;
 
                 upload_batch_of_annotations(
@@ -550,9 +784,11 @@ fn upload_comments_from_reader(
             &mut comments_to_put,
             &mut comments_to_sync,
             &mut audio_paths,
+            &mut manifest_attachments,
             no_charge,
             attachments_dir,
             resume_on_error,
+            skip_missing_attachments,
         )?;
     }
 
@@ -593,6 +829,7 @@ pub struct Statistics {
     failed_comments: AtomicUsize,
     attachments: AtomicUsize,
     failed_attachments: AtomicUsize,
+    failed_comment_ids: Mutex<Vec<String>>,
 }
 
 impl AnnotationStatistic for Statistics {
@@ -625,9 +862,24 @@ impl Statistics {
             failed_comments: AtomicUsize::new(0),
             attachments: AtomicUsize::new(0),
             failed_attachments: AtomicUsize::new(0),
+            failed_comment_ids: Mutex::new(Vec::new()),
         }
     }
 
+    fn add_failed_comment_ids(&self, ids: Vec<String>) {
+        self.failed_comment_ids
+            .lock()
+            .expect("failed comment ids lock poisoned")
+            .extend(ids);
+    }
+
+    fn failed_comment_ids(&self) -> Vec<String> {
+        self.failed_comment_ids
+            .lock()
+            .expect("failed comment ids lock poisoned")
+            .clone()
+    }
+
     #[inline]
     fn add_bytes_read(&self, bytes_read: usize) {
         self.bytes_read.fetch_add(bytes_read, Ordering::SeqCst);
@@ -808,7 +1060,7 @@ fn basic_statistics(statistics: &Statistics) -> (u64, String) {
 }
 
 fn progress_bar(
-    total_bytes: u64,
+    total_bytes: Option<u64>,
     statistics: &Arc<Statistics>,
     use_detailed_statistics: bool,
 ) -> Progress {
@@ -819,15 +1071,19 @@ fn progress_bar(
             basic_statistics
         },
         statistics,
-        Some(total_bytes),
+        total_bytes,
         ProgressOptions { bytes_units: true },
     )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{check_no_duplicate_ids, read_comments_iter, Statistics};
-    use std::io::{BufReader, Cursor};
+    use super::{check_comments, read_attachments_manifest, read_comments_iter, Statistics};
+    use std::{
+        env, fs,
+        io::{BufReader, Cursor},
+        path::PathBuf,
+    };
 
     const SAMPLE_DUPLICATES: &str = include_str!("../../../tests/samples/duplicates.jsonl");
 
@@ -845,7 +1101,7 @@ mod tests {
     #[test]
     fn check_detects_duplicates() {
         let reader = BufReader::new(Cursor::new(SAMPLE_DUPLICATES));
-        let result = check_no_duplicate_ids(reader);
+        let result = check_comments(reader, false, None);
 
         assert!(result.is_err());
         assert!(result
@@ -853,4 +1109,45 @@ mod tests {
             .to_string()
             .contains("Duplicate comments with id"));
     }
+
+    #[test]
+    fn check_detects_missing_audio_files() {
+        let comments = concat!(
+            r#"{"comment":{"id":"1","timestamp":"2024-01-01T00:00:00Z","messages":[]},"#,
+            r#""audio_path":"missing.wav"}"#,
+        );
+        let reader = BufReader::new(Cursor::new(comments));
+
+        assert_eq!(
+            check_comments(reader, false, None).unwrap(),
+            vec![PathBuf::from("missing.wav")]
+        );
+    }
+
+    #[test]
+    fn test_read_attachments_manifest() {
+        let dir = env::temp_dir().join(format!("re-manifest-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.pdf"), b"pdf").unwrap();
+        let manifest_path = dir.join("manifest.jsonl");
+        fs::write(
+            &manifest_path,
+            concat!(
+                r#"{"comment_id":"1","path":"a.pdf","index":0}"#,
+                "\n",
+                r#"{"comment_id":"1","path":"missing.pdf","index":1}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        assert!(read_attachments_manifest(&manifest_path, false).is_err());
+        let manifest = read_attachments_manifest(&manifest_path, true).unwrap();
+        assert_eq!(
+            manifest.values().collect::<Vec<_>>(),
+            vec![&vec![(0, dir.join("a.pdf"))]]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -1,4 +1,5 @@
 use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
 use anyhow::{anyhow, bail, Context, Error, Result};
 use log::info;
 use reinfer_client::{
@@ -14,7 +15,7 @@ use structopt::StructOpt;
 pub struct CreateDatasetArgs {
     #[structopt(name = "owner-name/dataset-name")]
     /// Full name of the new dataset <owner>/<name>
-    name: DatasetFullName,
+    name: NameArg<DatasetFullName>,
 
     #[structopt(long = "title")]
     /// Set the title of the new dataset
@@ -33,7 +34,7 @@ pub struct CreateDatasetArgs {
 
     #[structopt(short = "s", long = "source")]
     /// Names or ids of the sources in the dataset
-    sources: Vec<SourceIdentifier>,
+    sources: Vec<NameArg<SourceIdentifier>>,
 
     #[structopt(short = "e", long = "entity-defs", default_value = "[]")]
     /// Entity defs to create at dataset creation, as json
@@ -77,7 +78,12 @@ pub struct CreateDatasetArgs {
     zero_shot: Option<bool>,
 }
 
-pub fn create(client: &Client, args: &CreateDatasetArgs, printer: &Printer) -> Result<()> {
+pub fn create(
+    client: &Client,
+    resolver: &Resolver,
+    args: &CreateDatasetArgs,
+    printer: &Printer,
+) -> Result<()> {
     let CreateDatasetArgs {
         name,
         title,
@@ -95,13 +101,14 @@ pub fn create(client: &Client, args: &CreateDatasetArgs, printer: &Printer) -> R
         gen_ai,
         zero_shot,
     } = args;
+    let name = resolver.resolve(name)?;
 
     let source_ids = {
         let mut source_ids = Vec::with_capacity(sources.len());
         for source in sources.iter() {
             source_ids.push(
-                client
-                    .get_source(source.clone())
+                resolver
+                    .get_source(client, resolver.resolve(source)?)
                     .context("Operation to get source has failed")?
                     .id,
             );
@@ -150,7 +157,7 @@ pub fn create(client: &Client, args: &CreateDatasetArgs, printer: &Printer) -> R
     };
     let dataset = client
         .create_dataset(
-            name,
+            &name,
             NewDataset {
                 source_ids: &source_ids,
                 title: title.as_deref(),
@@ -1,6 +1,6 @@
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use colored::Colorize;
-use log::info;
+use log::{info, warn};
 use reinfer_client::{Bucket, BucketIdentifier, Client, NewEmail};
 use std::{
     fs::{self, File},
@@ -14,8 +14,12 @@ use std::{
 use structopt::StructOpt;
 
 use crate::{
-    commands::ensure_uip_user_consents_to_ai_unit_charge,
+    commands::{
+        ensure_uip_user_consents_to_ai_unit_charge,
+        parse::{emls::read_eml_to_new_email, get_files_in_directory},
+    },
     progress::{Options as ProgressOptions, Progress},
+    utils::{NameArg, Resolver},
 };
 
 #[derive(Debug, StructOpt)]
@@ -24,16 +28,39 @@ pub struct CreateEmailsArgs {
     /// Path to JSON file with emails. If not specified, stdin will be used.
     emails_path: Option<PathBuf>,
 
+    #[structopt(
+        long = "from-eml-dir",
+        parse(from_os_str),
+        conflicts_with = "emails-path"
+    )]
+    /// Directory of `.eml` files to upload as emails, instead of a JSON file. The Message-Id and
+    /// Date headers are required, and the file name is used as the mailbox.
+    eml_dir: Option<PathBuf>,
+
+    #[structopt(long = "replace-invalid-utf8", requires = "eml-dir")]
+    /// Replace invalid UTF-8 sequences in `.eml` files, instead of failing. By default the
+    /// original bytes of each file are stored unchanged as its MIME content.
+    replace_invalid_utf8: bool,
+
+    #[structopt(
+        long = "keep-raw-mime",
+        requires = "eml-dir",
+        conflicts_with = "replace-invalid-utf8"
+    )]
+    /// Store the original bytes of each `.eml` file as its MIME content, failing for files which
+    /// are not valid UTF-8. This is the default, the flag only makes it explicit.
+    keep_raw_mime: bool,
+
     #[structopt(short = "b", long = "bucket")]
     /// Name of the bucket where the emails will be uploaded.
-    bucket: BucketIdentifier,
+    bucket: NameArg<BucketIdentifier>,
 
     #[structopt(long = "batch-size", default_value = "128")]
     /// Number of emails to batch in a single request.
     batch_size: usize,
 
     #[structopt(long)]
-    /// Don't display a progress bar (only applicable when --file is used).
+    /// Don't display a progress bar (only applicable when --file or --from-eml-dir is used).
     no_progress: bool,
 
     #[structopt(short = "n", long = "no-charge")]
@@ -45,21 +72,67 @@ pub struct CreateEmailsArgs {
     yes: bool,
 
     #[structopt(long = "resume-on-error")]
-    /// Whether to attempt to resume processing on error
+    /// Whether to attempt to resume processing on error. Emails which fail to upload are skipped
+    /// and counted in the summary.
     resume_on_error: bool,
 }
 
-pub fn create(client: &Client, args: &CreateEmailsArgs) -> Result<()> {
+pub fn create(client: &Client, resolver: &Resolver, args: &CreateEmailsArgs) -> Result<()> {
+    ensure!(args.batch_size > 0, "--batch-size must be greater than 0");
+
     if !args.no_charge && !args.yes {
         ensure_uip_user_consents_to_ai_unit_charge(client.base_url())?;
     }
 
     let bucket = client
-        .get_bucket(args.bucket.clone())
+        .get_bucket(resolver.resolve(&args.bucket)?)
         .with_context(|| format!("Unable to get bucket {}", args.bucket))?;
 
-    let statistics = match &args.emails_path {
-        Some(emails_path) => {
+    let statistics = match (&args.emails_path, &args.eml_dir) {
+        (_, Some(eml_dir)) => {
+            info!(
+                "Uploading emails from eml files in `{}` to bucket `{}` [id: {}]",
+                eml_dir.display(),
+                bucket.full_name(),
+                bucket.id,
+            );
+            let eml_paths: Vec<PathBuf> = get_files_in_directory(eml_dir, "eml", true)
+                .with_context(|| format!("Could not read directory `{}`", eml_dir.display()))?
+                .into_iter()
+                .map(|entry| entry.path())
+                .collect();
+            let total_bytes = eml_paths
+                .iter()
+                .filter_map(|path| fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+
+            let statistics = Arc::new(Statistics::new());
+            let progress = if args.no_progress {
+                None
+            } else {
+                Some(progress_bar(total_bytes, &statistics))
+            };
+            upload_emails(
+                client,
+                &bucket,
+                read_emls_iter(
+                    &eml_paths,
+                    args.replace_invalid_utf8 && !args.keep_raw_mime,
+                    args.resume_on_error,
+                    &statistics,
+                ),
+                args.batch_size,
+                &statistics,
+                args.no_charge,
+                args.resume_on_error,
+            )?;
+            if let Some(mut progress) = progress {
+                progress.done();
+            }
+            Arc::try_unwrap(statistics).unwrap()
+        }
+        (Some(emails_path), None) => {
             info!(
                 "Uploading emails from file `{}` to bucket `{}` [id: {}]",
                 emails_path.display(),
@@ -82,10 +155,10 @@ pub fn create(client: &Client, args: &CreateEmailsArgs) -> Result<()> {
             } else {
                 Some(progress_bar(file_metadata.len(), &statistics))
             };
-            upload_emails_from_reader(
+            upload_emails(
                 client,
                 &bucket,
-                file,
+                read_emails_iter(file, &statistics),
                 args.batch_size,
                 &statistics,
                 args.no_charge,
@@ -96,17 +169,17 @@ pub fn create(client: &Client, args: &CreateEmailsArgs) -> Result<()> {
             }
             Arc::try_unwrap(statistics).unwrap()
         }
-        None => {
+        (None, None) => {
             info!(
                 "Uploading emails from stdin to bucket `{}` [id: {}]",
                 bucket.full_name(),
                 bucket.id,
             );
             let statistics = Statistics::new();
-            upload_emails_from_reader(
+            upload_emails(
                 client,
                 &bucket,
-                BufReader::new(io::stdin()),
+                read_emails_iter(BufReader::new(io::stdin()), &statistics),
                 args.batch_size,
                 &statistics,
                 args.no_charge,
@@ -117,42 +190,86 @@ pub fn create(client: &Client, args: &CreateEmailsArgs) -> Result<()> {
     };
 
     info!(
-        concat!("Successfully uploaded {} emails",),
+        "Successfully uploaded {} emails. {} skipped",
         statistics.num_uploaded(),
+        statistics.num_failed(),
     );
 
     Ok(())
 }
 
-fn upload_emails_from_reader(
+fn read_emails_iter<'a>(
+    mut emails: impl BufRead + 'a,
+    statistics: &'a Statistics,
+) -> impl Iterator<Item = Result<NewEmail>> + 'a {
+    let mut line = String::new();
+    let mut line_number: u32 = 0;
+    std::iter::from_fn(move || {
+        line_number += 1;
+        line.clear();
+
+        let read_result = emails
+            .read_line(&mut line)
+            .with_context(|| format!("Could not read line {line_number} from input stream"));
+
+        match read_result {
+            Ok(0) => return None,
+            Ok(bytes_read) => statistics.add_bytes_read(bytes_read),
+            Err(e) => return Some(Err(e)),
+        }
+
+        Some(
+            serde_json::from_str::<NewEmail>(line.trim_end()).with_context(|| {
+                format!("Could not parse email at line {line_number} from input stream")
+            }),
+        )
+    })
+}
+
+/// Reads each `.eml` file as an email. With `resume_on_error`, files which cannot be read are
+/// counted as failed and skipped.
+fn read_emls_iter<'a>(
+    eml_paths: &'a [PathBuf],
+    replace_invalid_utf8: bool,
+    resume_on_error: bool,
+    statistics: &'a Statistics,
+) -> impl Iterator<Item = Result<NewEmail>> + 'a {
+    eml_paths.iter().filter_map(move |path| {
+        let new_email = read_eml_to_new_email(path, replace_invalid_utf8)
+            .with_context(|| format!("Could not read email from `{}`", path.display()));
+        if let Ok(metadata) = fs::metadata(path) {
+            statistics.add_bytes_read(metadata.len() as usize);
+        }
+        match new_email {
+            Err(error) if resume_on_error => {
+                warn!("{error:#}");
+                statistics.add_emails(StatisticsUpdate {
+                    uploaded: 0,
+                    failed: 1,
+                });
+                None
+            }
+            new_email => Some(new_email),
+        }
+    })
+}
+
+fn upload_emails(
     client: &Client,
     bucket: &Bucket,
-    mut emails: impl BufRead,
+    mut emails: impl Iterator<Item = Result<NewEmail>>,
     batch_size: usize,
     statistics: &Statistics,
     no_charge: bool,
     resume_on_error: bool,
 ) -> Result<()> {
     assert!(batch_size > 0);
-    let mut line_number = 1;
-    let mut line = String::new();
     let mut batch = Vec::with_capacity(batch_size);
     let mut eof = false;
     while !eof {
-        line.clear();
-        let bytes_read = emails
-            .read_line(&mut line)
-            .with_context(|| format!("Could not read line {line_number} from input stream"))?;
-
-        if bytes_read == 0 {
-            eof = true;
-        } else {
-            statistics.add_bytes_read(bytes_read);
-            let new_email =
-                serde_json::from_str::<NewEmail>(line.trim_end()).with_context(|| {
-                    format!("Could not parse email at line {line_number} from input stream")
-                })?;
-            batch.push(new_email);
+        match emails.next() {
+            None => eof = true,
+            Some(new_email) => batch.push(new_email?),
         }
 
         if batch.len() == batch_size || (!batch.is_empty() && eof) {
@@ -178,8 +295,6 @@ fn upload_emails_from_reader(
                 batch.clear();
             }
         }
-
-        line_number += 1;
     }
 
     Ok(())
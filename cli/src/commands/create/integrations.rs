@@ -67,16 +67,7 @@ fn overwrite_integration(
         bail!("New integration is same as existing integration")
     }
 
-    let old_json_str = serde_json::to_string_pretty(&old_integration)?;
-    let new_json_str = serde_json::to_string_pretty(&new_integration)?;
-
-    for diff in diff::lines(&old_json_str, &new_json_str) {
-        match diff {
-            diff::Result::Left(l) => println!("{}", format!("-{}", l).red()),
-            diff::Result::Both(l, _) => println!("{}", format!(" {}", l).dimmed()),
-            diff::Result::Right(r) => println!("{}", format!("+{}", r).green()),
-        }
-    }
+    print_integration_diff(&old_integration, new_integration)?;
 
     if Confirm::new()
         .with_prompt(
@@ -91,6 +82,24 @@ fn overwrite_integration(
     }
 }
 
+/// Print a line-by-line diff of the JSON representations of two integrations.
+pub fn print_integration_diff(
+    old_integration: &NewIntegration,
+    new_integration: &NewIntegration,
+) -> Result<()> {
+    let old_json_str = serde_json::to_string_pretty(old_integration)?;
+    let new_json_str = serde_json::to_string_pretty(new_integration)?;
+
+    for diff in diff::lines(&old_json_str, &new_json_str) {
+        match diff {
+            diff::Result::Left(l) => println!("{}", format!("-{}", l).red()),
+            diff::Result::Both(l, _) => println!("{}", format!(" {}", l).dimmed()),
+            diff::Result::Right(r) => println!("{}", format!("+{}", r).green()),
+        }
+    }
+    Ok(())
+}
+
 fn read_integration(path: &PathBuf) -> Result<NewIntegration> {
     let integration_str = std::fs::read_to_string(path)
         .with_context(|| format!("Could not open file `{}`", path.display()))?;
@@ -17,7 +17,7 @@ use self::{
     project::CreateProjectArgs, quota::CreateQuotaArgs, source::CreateSourceArgs,
     stream_exception::CreateStreamExceptionArgs, streams::CreateStreamsArgs, user::CreateUserArgs,
 };
-use crate::printer::Printer;
+use crate::{printer::Printer, utils::Resolver};
 use anyhow::Result;
 use reinfer_client::Client;
 use scoped_threadpool::Pool;
@@ -85,26 +85,31 @@ pub enum CreateArgs {
 pub fn run(
     create_args: &CreateArgs,
     client: Client,
+    resolver: &Resolver,
     printer: &Printer,
     pool: &mut Pool,
 ) -> Result<()> {
     match create_args {
-        CreateArgs::Bucket(bucket_args) => bucket::create(&client, bucket_args, printer),
-        CreateArgs::Source(source_args) => source::create(&client, source_args, printer),
-        CreateArgs::Dataset(dataset_args) => dataset::create(&client, dataset_args, printer),
+        CreateArgs::Bucket(bucket_args) => bucket::create(&client, resolver, bucket_args, printer),
+        CreateArgs::Source(source_args) => source::create(&client, resolver, source_args, printer),
+        CreateArgs::Dataset(dataset_args) => {
+            dataset::create(&client, resolver, dataset_args, printer)
+        }
         CreateArgs::Project(project_args) => project::create(&client, project_args, printer),
-        CreateArgs::Comments(comments_args) => comments::create(&client, comments_args, pool),
+        CreateArgs::Comments(comments_args) => {
+            comments::create(&client, resolver, comments_args, pool)
+        }
         CreateArgs::Annotations(annotations_args) => {
-            annotations::create(&client, annotations_args, pool)
+            annotations::create(&client, resolver, annotations_args, pool)
         }
-        CreateArgs::Emails(emails_args) => emails::create(&client, emails_args),
+        CreateArgs::Emails(emails_args) => emails::create(&client, resolver, emails_args),
         CreateArgs::User(user_args) => user::create(&client, user_args, printer),
         CreateArgs::StreamException(stream_exception_args) => {
             stream_exception::create(&client, stream_exception_args, printer)
         }
         CreateArgs::Quota(quota_args) => quota::create(&client, quota_args),
         CreateArgs::Stream(stream_args) | CreateArgs::Streams(stream_args) => {
-            streams::create(&client, stream_args)
+            streams::create(&client, resolver, stream_args)
         }
         CreateArgs::Integration(integration_args) | CreateArgs::Integrations(integration_args) => {
             integrations::create(&client, integration_args)
@@ -1,7 +1,8 @@
-use crate::printer::Printer;
-use anyhow::{Context, Result};
-use log::info;
+use crate::{printer::Printer, utils::poll_until};
+use anyhow::{bail, Context, Result};
+use log::{debug, info};
 use reinfer_client::{Client, NewProject, ProjectName, UserId};
+use std::time::Duration;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -21,6 +22,14 @@ pub struct CreateProjectArgs {
     #[structopt(long = "user-ids", required = true)]
     /// The ids of users to be given initial control of the new project
     user_ids: Vec<UserId>,
+
+    #[structopt(long)]
+    /// Wait until the new project is returned by the API, as creation completes asynchronously
+    wait: bool,
+
+    #[structopt(long, default_value = "300")]
+    /// Maximum number of seconds to wait for the project to exist, when using `--wait`
+    wait_timeout: u64,
 }
 
 pub fn create(client: &Client, args: &CreateProjectArgs, printer: &Printer) -> Result<()> {
@@ -29,6 +38,8 @@ pub fn create(client: &Client, args: &CreateProjectArgs, printer: &Printer) -> R
         title,
         description,
         user_ids,
+        wait,
+        wait_timeout,
     } = args;
 
     let project = client
@@ -42,6 +53,36 @@ pub fn create(client: &Client, args: &CreateProjectArgs, printer: &Printer) -> R
         )
         .context("Operation to create a project has failed")?;
     info!("New project `{}` created successfully", project.name.0,);
+
+    if *wait {
+        wait_for_project_creation(client, name, Duration::from_secs(*wait_timeout))?;
+    }
+
     printer.print_resources(&[project])?;
     Ok(())
 }
+
+fn wait_for_project_creation(
+    client: &Client,
+    project_name: &ProjectName,
+    timeout: Duration,
+) -> Result<()> {
+    let created = poll_until(timeout, || match client.get_project(project_name) {
+        Ok(_) => Ok(true),
+        Err(error) if !error.is_not_found() => {
+            Err(error).context("Operation to get project has failed.")
+        }
+        Err(_) => {
+            debug!("Project {} does not exist yet, waiting", project_name.0);
+            Ok(false)
+        }
+    })?;
+    if !created {
+        bail!(
+            "Project {} does not exist after waiting {} seconds",
+            project_name.0,
+            timeout.as_secs()
+        );
+    }
+    Ok(())
+}
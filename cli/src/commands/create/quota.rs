@@ -1,5 +1,5 @@
-use anyhow::{anyhow, Context, Result};
-use log::info;
+use anyhow::{anyhow, bail, Context, Result};
+use log::{error, info};
 use reinfer_client::{
     resources::{
         quota::{CreateQuota, TenantQuotaKind},
@@ -7,6 +7,12 @@ use reinfer_client::{
     },
     Client,
 };
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
 use structopt::{clap::ArgGroup, StructOpt};
 
 #[derive(Debug, StructOpt)]
@@ -20,17 +26,47 @@ pub struct CreateQuotaArgs {
     /// UiPath tenant ID for which to set the quota
     uipath_tenant_id: Option<UiPathTenantId>,
 
-    #[structopt(long = "quota-kind")]
+    #[structopt(long = "quota-kind", required_unless = "from-file")]
     /// Kind of quota to set
-    tenant_quota_kind: TenantQuotaKind,
+    tenant_quota_kind: Option<TenantQuotaKind>,
 
-    #[structopt(long = "limit")]
+    #[structopt(long = "limit", required_unless = "from-file")]
     /// New value of the quota to set
-    hard_limit: u64,
+    hard_limit: Option<u64>,
 
     #[structopt(long = "auto-increase-up-to")]
     /// If set, will also change the `auto-increase-up-to` value of the quota
     auto_increase_up_to: Option<u64>,
+
+    #[structopt(long = "from-file", parse(from_os_str), group = "tenant-id")]
+    /// Path to a JSONL file of quotas to set, one per line, e.g.
+    /// `{"tenant_id": {"reinfer": "..."}, "kind": "sources", "options": {"hard_limit": 10}}`.
+    /// Use `{"uipath": "..."}` for UiPath tenant IDs.
+    from_file: Option<PathBuf>,
+}
+
+/// A single line of a `--from-file` quotas file.
+#[derive(Debug, Deserialize)]
+struct QuotaLine {
+    tenant_id: QuotaLineTenantId,
+    kind: TenantQuotaKind,
+    options: CreateQuota,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum QuotaLineTenantId {
+    Reinfer(ReinferTenantId),
+    Uipath(UiPathTenantId),
+}
+
+impl From<QuotaLineTenantId> for TenantId {
+    fn from(tenant_id: QuotaLineTenantId) -> Self {
+        match tenant_id {
+            QuotaLineTenantId::Reinfer(tenant_id) => TenantId::Reinfer(tenant_id),
+            QuotaLineTenantId::Uipath(tenant_id) => TenantId::UiPath(tenant_id),
+        }
+    }
 }
 
 pub fn create(client: &Client, args: &CreateQuotaArgs) -> Result<()> {
@@ -40,8 +76,13 @@ pub fn create(client: &Client, args: &CreateQuotaArgs) -> Result<()> {
         tenant_quota_kind,
         hard_limit,
         auto_increase_up_to,
+        from_file,
     } = args;
 
+    if let Some(path) = from_file {
+        return create_from_file(client, path);
+    }
+
     let tenant_id: TenantId = match (reinfer_tenant_id, uipath_tenant_id) {
         (Some(tenant_id), None) => TenantId::Reinfer(tenant_id.to_owned()),
         (None, Some(tenant_id)) => TenantId::UiPath(tenant_id.to_owned()),
@@ -51,6 +92,9 @@ pub fn create(client: &Client, args: &CreateQuotaArgs) -> Result<()> {
             ))
         }
     };
+    let (Some(tenant_quota_kind), Some(hard_limit)) = (tenant_quota_kind, hard_limit) else {
+        bail!("Both `--quota-kind` and `--limit` are required when not using `--from-file`")
+    };
 
     client
         .create_quota(
@@ -69,3 +113,84 @@ pub fn create(client: &Client, args: &CreateQuotaArgs) -> Result<()> {
     );
     Ok(())
 }
+
+fn create_from_file(client: &Client, path: &PathBuf) -> Result<()> {
+    let file = BufReader::new(
+        File::open(path).with_context(|| format!("Could not open file `{}`", path.display()))?,
+    );
+
+    let mut num_set = 0;
+    let mut num_failed = 0;
+    for (index, line) in file.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.with_context(|| format!("Could not read line {line_number}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = serde_json::from_str::<QuotaLine>(&line)
+            .context("Could not parse quota")
+            .and_then(
+                |QuotaLine {
+                     tenant_id,
+                     kind,
+                     options,
+                 }| {
+                    let tenant_id = TenantId::from(tenant_id);
+                    client
+                        .create_quota(&tenant_id, kind, options)
+                        .context("Operation to set quota has failed")?;
+                    Ok((tenant_id, kind))
+                },
+            );
+
+        match result {
+            Ok((tenant_id, kind)) => {
+                num_set += 1;
+                info!(
+                    "Line {line_number}: quota `{kind}` set successfully in tenant with id `{tenant_id}`"
+                );
+            }
+            Err(error) => {
+                num_failed += 1;
+                error!("Line {line_number}: {error:#}");
+            }
+        }
+    }
+
+    if num_failed > 0 {
+        bail!("Failed to set {num_failed} quota(s), {num_set} set successfully")
+    }
+    info!("Set {num_set} quota(s) successfully");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quota_line() {
+        let QuotaLine {
+            tenant_id,
+            kind,
+            options,
+        } = serde_json::from_str(
+            r#"{"tenant_id": {"uipath": "abc"}, "kind": "sources", "options": {"hard_limit": 10}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            TenantId::from(tenant_id),
+            TenantId::UiPath("abc".parse().unwrap())
+        );
+        assert_eq!(kind, TenantQuotaKind::Sources);
+        assert_eq!(
+            options,
+            CreateQuota {
+                hard_limit: 10,
+                auto_increase_up_to: None
+            }
+        );
+    }
+}
@@ -1,8 +1,12 @@
-use crate::printer::Printer;
-use anyhow::{Context, Result};
+use crate::{
+    printer::Printer,
+    utils::{NameArg, Resolver},
+};
+use anyhow::{bail, Context, Result};
 use log::info;
 use reinfer_client::{
-    BucketIdentifier, Client, NewSource, SourceFullName, SourceKind, TransformTag,
+    Bucket, BucketIdentifier, BucketType, Client, NewBucket, NewSource, SourceFullName, SourceKind,
+    TransformTag,
 };
 use structopt::StructOpt;
 
@@ -10,7 +14,7 @@ use structopt::StructOpt;
 pub struct CreateSourceArgs {
     #[structopt(name = "source-name")]
     /// Full name of the new source <owner>/<name>
-    name: SourceFullName,
+    name: NameArg<SourceFullName>,
 
     #[structopt(long = "title")]
     /// Set the title of the new source
@@ -30,7 +34,11 @@ pub struct CreateSourceArgs {
 
     #[structopt(long = "bucket")]
     /// Bucket to pull emails from.
-    bucket: Option<BucketIdentifier>,
+    bucket: Option<NameArg<BucketIdentifier>>,
+
+    #[structopt(long = "create-bucket", requires = "bucket")]
+    /// Create the bucket given by --bucket <owner>/<name> if it doesn't exist
+    create_bucket: bool,
 
     #[structopt(long = "kind")]
     /// Set the kind of the new source
@@ -41,7 +49,12 @@ pub struct CreateSourceArgs {
     transform_tag: Option<TransformTag>,
 }
 
-pub fn create(client: &Client, args: &CreateSourceArgs, printer: &Printer) -> Result<()> {
+pub fn create(
+    client: &Client,
+    resolver: &Resolver,
+    args: &CreateSourceArgs,
+    printer: &Printer,
+) -> Result<()> {
     let CreateSourceArgs {
         name,
         title,
@@ -49,24 +62,22 @@ pub fn create(client: &Client, args: &CreateSourceArgs, printer: &Printer) -> Re
         language,
         should_translate,
         bucket,
+        create_bucket,
         kind,
         transform_tag,
     } = args;
+    let name = resolver.resolve(name)?;
 
-    let bucket_id = match bucket.to_owned() {
-        Some(BucketIdentifier::Id(bucket_id)) => Some(bucket_id),
-        Some(full_name @ BucketIdentifier::FullName(_)) => Some(
-            client
-                .get_bucket(full_name)
-                .context("Fetching bucket for id.")?
-                .id,
-        ),
+    let bucket_id = match bucket {
+        Some(bucket) => {
+            Some(resolve_bucket(client, &resolver.resolve(bucket)?, *create_bucket)?.id)
+        }
         None => None,
     };
 
     let source = client
         .create_source(
-            name,
+            &name,
             NewSource {
                 title: title.as_deref(),
                 description: description.as_deref(),
@@ -87,3 +98,40 @@ pub fn create(client: &Client, args: &CreateSourceArgs, printer: &Printer) -> Re
     printer.print_resources(&[source])?;
     Ok(())
 }
+
+/// Get the bucket the source should be linked to, optionally creating it if it doesn't exist.
+fn resolve_bucket(client: &Client, bucket: &BucketIdentifier, create: bool) -> Result<Bucket> {
+    match client.get_bucket(bucket.clone()) {
+        Ok(bucket) => Ok(bucket),
+        Err(error) if error.is_not_found() => match (bucket, create) {
+            (BucketIdentifier::FullName(name), true) => {
+                let bucket = client
+                    .create_bucket(
+                        name,
+                        NewBucket {
+                            title: None,
+                            bucket_type: BucketType::Emails,
+                        },
+                    )
+                    .context("Operation to create a bucket has failed")?;
+                info!(
+                    "New bucket `{}` [id: {}] created successfully",
+                    bucket.full_name(),
+                    bucket.id,
+                );
+                Ok(bucket)
+            }
+            (BucketIdentifier::Id(_), true) => {
+                bail!(
+                    "Bucket `{}` doesn't exist, and can only be created by name",
+                    bucket
+                )
+            }
+            (_, false) => bail!(
+                "Bucket `{}` doesn't exist, pass --create-bucket to create it",
+                bucket
+            ),
+        },
+        Err(error) => Err(error).context("Operation to get bucket has failed"),
+    }
+}
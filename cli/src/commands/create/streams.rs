@@ -6,27 +6,29 @@ use std::{
 
 use anyhow::{Context, Result};
 use log::info;
-use reinfer_client::ModelVersion;
 use reinfer_client::{resources::stream::NewStream, Client, DatasetIdentifier};
 
 use structopt::StructOpt;
 
+use crate::utils::ModelVersionArg;
+use crate::utils::{NameArg, Resolver};
+
 #[derive(Debug, StructOpt)]
 pub struct CreateStreamsArgs {
     #[structopt(short = "d", long = "dataset")]
     /// Dataset where the streams should be created
-    dataset_id: DatasetIdentifier,
+    dataset_id: NameArg<DatasetIdentifier>,
 
     #[structopt(short = "f", long = "file", parse(from_os_str))]
     /// Path to JSON file with streams
     path: PathBuf,
 
     #[structopt(short = "v", long = "model-version")]
-    /// The model version for the new streams to use
-    model_version: ModelVersion,
+    /// The model version for the new streams to use, or `latest`
+    model_version: ModelVersionArg,
 }
 
-pub fn create(client: &Client, args: &CreateStreamsArgs) -> Result<()> {
+pub fn create(client: &Client, resolver: &Resolver, args: &CreateStreamsArgs) -> Result<()> {
     let CreateStreamsArgs {
         path,
         dataset_id,
@@ -37,12 +39,13 @@ pub fn create(client: &Client, args: &CreateStreamsArgs) -> Result<()> {
         File::open(path).with_context(|| format!("Could not open file `{}`", path.display()))?,
     );
 
-    let dataset = client.get_dataset(dataset_id.clone())?;
+    let dataset = resolver.get_dataset(client, resolver.resolve(dataset_id)?)?;
+    let model_version = model_version.resolve(client, &dataset.full_name())?;
 
     for read_stream_result in read_streams_iter(file) {
         let mut new_stream = read_stream_result?;
 
-        new_stream.set_model_version(model_version);
+        new_stream.set_model_version(&model_version);
 
         client.put_stream(&dataset.full_name(), &new_stream)?;
         info!("Created stream {}", new_stream.name.0)
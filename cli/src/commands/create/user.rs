@@ -72,10 +72,17 @@ pub fn create(client: &Client, args: &CreateUserArgs, printer: &Printer) -> Resu
     );
 
     if *send_welcome_email {
-        client
-            .send_welcome_email(user.id.clone())
-            .context("Operation to send welcome email failed")?;
-        log::info!("Welcome email sent for user '{}'", user.username.0);
+        // The user exists at this point, so failing to send the email shouldn't fail the command.
+        match client.send_welcome_email(user.id.clone()) {
+            Ok(()) => log::info!("Welcome email sent for user '{}'", user.username.0),
+            Err(error) => log::warn!(
+                "User '{}' was created, but sending the welcome email failed: {}",
+                user.username.0,
+                error
+            ),
+        }
+    } else {
+        log::info!("No welcome email sent for user '{}'", user.username.0);
     }
 
     printer.print_resources(&[user])?;
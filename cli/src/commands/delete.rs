@@ -1,20 +1,30 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use colored::Colorize;
-use log::info;
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use dialoguer::Confirm;
+use log::{debug, info};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::channel,
+        Arc,
+    },
+    time::Duration,
 };
 use structopt::StructOpt;
 
 use reinfer_client::{
-    resources::{bucket::GetKeyedSyncStateIdsRequest, project::ForceDeleteProject},
+    resources::{
+        bucket::{GetKeyedSyncStateIdsRequest, KeyedSyncStateId},
+        project::ForceDeleteProject,
+    },
     BucketIdentifier, Client, CommentId, CommentsIter, CommentsIterTimerange, DatasetIdentifier,
-    ProjectName, Source, SourceIdentifier, UserIdentifier,
+    ProjectName, Source, SourceId, SourceIdentifier, SourceKind, UserIdentifier,
 };
+use scoped_threadpool::Pool;
 
 use crate::progress::{Options as ProgressOptions, Progress};
+use crate::utils::{poll_until, NameArg, Resolver};
 
 #[derive(Debug, StructOpt)]
 pub enum DeleteArgs {
@@ -23,7 +33,33 @@ pub enum DeleteArgs {
     Source {
         #[structopt(name = "source")]
         /// Name or id of the source to delete
-        source: SourceIdentifier,
+        source: NameArg<SourceIdentifier>,
+
+        #[structopt(long)]
+        /// Wait until the source is no longer returned by the API, as deletion completes
+        /// asynchronously
+        wait: bool,
+
+        #[structopt(long, default_value = "300")]
+        /// Maximum number of seconds to wait for the deletion to complete, when using `--wait`
+        wait_timeout: u64,
+    },
+
+    #[structopt(name = "sources")]
+    /// Delete all sources matching a filter. Only lists the matching sources unless --yes is
+    /// passed.
+    Sources {
+        #[structopt(long)]
+        /// Only delete sources whose full name <owner>/<name> starts with this prefix
+        name_prefix: Option<String>,
+
+        #[structopt(long)]
+        /// Only delete sources of this kind
+        kind: Option<SourceKind>,
+
+        #[structopt(long)]
+        /// Delete the matching sources, instead of only listing them
+        yes: bool,
     },
 
     #[structopt(name = "comments")]
@@ -31,7 +67,7 @@ pub enum DeleteArgs {
     Comments {
         #[structopt(short = "s", long = "source")]
         /// Name or id of the source to delete comments from
-        source: SourceIdentifier,
+        source: NameArg<SourceIdentifier>,
 
         #[structopt(name = "comment id")]
         /// Ids of the comments to delete
@@ -43,7 +79,7 @@ pub enum DeleteArgs {
     BulkComments {
         #[structopt(short = "s", long = "source")]
         /// Name or id of the source to delete comments from
-        source: SourceIdentifier,
+        source: NameArg<SourceIdentifier>,
 
         #[structopt(long, parse(try_from_str))]
         /// Whether to delete comments that are annotated in any of the datasets
@@ -72,7 +108,7 @@ pub enum DeleteArgs {
     Bucket {
         #[structopt(name = "bucket")]
         /// Name or id of the bucket to delete
-        bucket: BucketIdentifier,
+        bucket: NameArg<BucketIdentifier>,
     },
 
     #[structopt(name = "dataset")]
@@ -80,7 +116,7 @@ pub enum DeleteArgs {
     Dataset {
         #[structopt(name = "dataset")]
         /// Name or id of the dataset to delete
-        dataset: DatasetIdentifier,
+        dataset: NameArg<DatasetIdentifier>,
     },
 
     #[structopt(name = "user")]
@@ -99,29 +135,104 @@ pub enum DeleteArgs {
         project: ProjectName,
 
         #[structopt(long)]
-        /// Force deletion of the project, even if it's not empty.
+        /// Force deletion of the project, even if it's not empty. All the datasets, sources and
+        /// buckets in the project are deleted with it.
         force: bool,
+
+        #[structopt(long, requires = "force")]
+        /// Don't ask for confirmation before force deleting
+        yes: bool,
     },
 
     #[structopt(name = "keyed-sync-states")]
-    /// Delete keyed sync states
+    /// Delete (reset) keyed sync states, forcing the affected folders to be re-ingested
     KeyedSyncStates {
         /// The bucket to delete keyed sync states for
-        bucket: BucketIdentifier,
+        bucket: NameArg<BucketIdentifier>,
+
+        /// The mailbox to delete keyed sync states for. Required unless --key is given
+        mailbox_name: Option<String>,
 
-        /// The mailbox to delete keyed sync states for
-        mailbox_name: String,
+        #[structopt(long = "key")]
+        /// Ids of specific keyed sync states to delete, instead of all of the mailbox's
+        keys: Vec<KeyedSyncStateId>,
+
+        #[structopt(long)]
+        /// Only print the keyed sync states which would be deleted
+        dry_run: bool,
+
+        #[structopt(long)]
+        /// Don't ask for confirmation before deleting
+        yes: bool,
     },
 }
 
-pub fn run(delete_args: &DeleteArgs, client: Client) -> Result<()> {
+pub fn run(
+    delete_args: &DeleteArgs,
+    client: Client,
+    resolver: &Resolver,
+    pool: &mut Pool,
+) -> Result<()> {
     match delete_args {
-        DeleteArgs::Source { source } => {
+        DeleteArgs::Source {
+            source,
+            wait,
+            wait_timeout,
+        } => {
+            // Resolve the id up front, so that waiting doesn't depend on the name.
+            let source_id = resolver
+                .get_source(&client, resolver.resolve(source)?)
+                .context("Operation to get source has failed.")?
+                .id;
             client
-                .delete_source(source.clone())
+                .delete_source(source_id.clone())
                 .context("Operation to delete source has failed.")?;
+            if *wait {
+                wait_for_source_deletion(&client, source_id, Duration::from_secs(*wait_timeout))?;
+            }
             log::info!("Deleted source.");
         }
+        DeleteArgs::Sources {
+            name_prefix,
+            kind,
+            yes,
+        } => {
+            if name_prefix.is_none() && kind.is_none() {
+                bail!("At least one of --name-prefix or --kind is required");
+            }
+            let sources: Vec<Source> = client
+                .get_sources()
+                .context("Operation to list sources has failed.")?
+                .into_iter()
+                .filter(|source| {
+                    name_prefix
+                        .as_ref()
+                        .is_none_or(|prefix| source.full_name().0.starts_with(prefix))
+                        && kind.as_ref().is_none_or(|kind| source.kind == *kind)
+                })
+                .collect();
+
+            if sources.is_empty() {
+                info!("No sources match the filter.");
+                return Ok(());
+            }
+
+            info!(
+                "{} {} source(s):",
+                if *yes { "Deleting" } else { "Would delete" },
+                sources.len()
+            );
+            for source in &sources {
+                println!("{} [id: {}]", source.full_name().0, source.id.0);
+            }
+            if !yes {
+                info!("Pass --yes to delete these sources.");
+                return Ok(());
+            }
+
+            delete_sources(&client, &sources, pool)?;
+            info!("Deleted {} source(s).", sources.len());
+        }
         DeleteArgs::User { user } => {
             client
                 .delete_user(user.clone())
@@ -130,7 +241,7 @@ pub fn run(delete_args: &DeleteArgs, client: Client) -> Result<()> {
         }
         DeleteArgs::Comments { source, comments } => {
             client
-                .delete_comments(source.clone(), comments)
+                .delete_comments(resolver.resolve(source)?, comments)
                 .context("Operation to delete comments has failed.")?;
             log::info!("Deleted comments.");
         }
@@ -141,7 +252,7 @@ pub fn run(delete_args: &DeleteArgs, client: Client) -> Result<()> {
             to_timestamp,
             no_progress,
         } => {
-            let source = client.get_source(source_identifier.clone())?;
+            let source = resolver.get_source(&client, resolver.resolve(source_identifier)?)?;
             let show_progress = !no_progress;
             delete_comments_in_period(
                 &client,
@@ -157,49 +268,205 @@ pub fn run(delete_args: &DeleteArgs, client: Client) -> Result<()> {
         }
         DeleteArgs::Dataset { dataset } => {
             client
-                .delete_dataset(dataset.clone())
+                .delete_dataset(resolver.resolve(dataset)?)
                 .context("Operation to delete dataset has failed.")?;
             log::info!("Deleted dataset.");
         }
         DeleteArgs::Bucket { bucket } => {
             client
-                .delete_bucket(bucket.clone())
+                .delete_bucket(resolver.resolve(bucket)?)
                 .context("Operation to delete bucket has failed.")?;
             log::info!("Deleted bucket.");
         }
-        DeleteArgs::Project { project, force } => {
+        DeleteArgs::Project {
+            project,
+            force,
+            yes,
+        } => {
             let force_delete = if *force {
+                let contents = ProjectContents::get(&client, project)?;
+                let prompt = format!(
+                    "Delete project `{}` and its {}?",
+                    project.0,
+                    contents.describe()
+                );
+                if !yes && !Confirm::new().with_prompt(prompt).interact()? {
+                    bail!("Operation aborted by user")
+                }
                 ForceDeleteProject::Yes
             } else {
                 ForceDeleteProject::No
             };
-            client
-                .delete_project(project, force_delete)
-                .context("Operation to delete project has failed.")?;
+            if let Err(error) = client.delete_project(project, force_delete) {
+                let error = anyhow::Error::new(error);
+                // The server refuses to delete a project which isn't empty, explain why
+                let contents = match force_delete {
+                    ForceDeleteProject::Yes => None,
+                    ForceDeleteProject::No => ProjectContents::get(&client, project).ok(),
+                };
+                return Err(match contents {
+                    Some(contents) if !contents.is_empty() => error.context(format!(
+                        "Project `{}` is not empty, it contains {}. Pass --force to delete them \
+                         along with the project.",
+                        project.0,
+                        contents.describe()
+                    )),
+                    _ => error.context("Operation to delete project has failed."),
+                });
+            }
             log::info!("Deleted project.");
         }
         DeleteArgs::KeyedSyncStates {
             bucket,
             mailbox_name,
+            keys,
+            dry_run,
+            yes,
         } => {
-            let bucket = client.get_bucket(bucket.clone())?;
+            let bucket = client.get_bucket(resolver.resolve(bucket)?)?;
+
+            let keyed_sync_state_ids = match (mailbox_name, keys.is_empty()) {
+                (_, false) => keys.clone(),
+                (Some(mailbox_name), true) => client
+                    .get_keyed_sync_state_ids(
+                        &bucket.id,
+                        &GetKeyedSyncStateIdsRequest {
+                            mailbox_name: mailbox_name.clone(),
+                        },
+                    )
+                    .context("Operation to get keyed sync state ids has failed.")?,
+                (None, true) => bail!("Either a mailbox name or at least one --key is required"),
+            };
 
-            let keyed_sync_state_ids = client.get_keyed_sync_state_ids(
-                &bucket.id,
-                &GetKeyedSyncStateIdsRequest {
-                    mailbox_name: mailbox_name.clone(),
-                },
-            )?;
+            if keyed_sync_state_ids.is_empty() {
+                info!("No keyed sync states to delete.");
+                return Ok(());
+            }
+
+            if *dry_run {
+                info!(
+                    "Would delete {} keyed sync state(s) in bucket `{}`:",
+                    keyed_sync_state_ids.len(),
+                    bucket.full_name().0
+                );
+                for id in &keyed_sync_state_ids {
+                    println!("{}", id.0);
+                }
+                return Ok(());
+            }
+
+            let prompt = format!(
+                "Delete {} keyed sync state(s) in bucket `{}`? The affected folders will be \
+                 re-ingested.",
+                keyed_sync_state_ids.len(),
+                bucket.full_name().0
+            );
+            if !yes && !Confirm::new().with_prompt(prompt).interact()? {
+                bail!("Operation aborted by user")
+            }
 
             for id in keyed_sync_state_ids {
-                client.delete_keyed_sync_state(&bucket.id, &id)?;
-                info!("Delete keyed sync state {}", id.0)
+                client
+                    .delete_keyed_sync_state(&bucket.id, &id)
+                    .context("Operation to delete keyed sync state has failed.")?;
+                info!("Deleted keyed sync state {}", id.0)
             }
         }
     };
     Ok(())
 }
 
+fn delete_sources(client: &Client, sources: &[Source], pool: &mut Pool) -> Result<()> {
+    let (sender, receiver) = channel();
+
+    pool.scoped(|scope| {
+        for source in sources {
+            let sender = sender.clone();
+            scope.execute(move || {
+                let result = client
+                    .delete_source(source.id.clone())
+                    .with_context(|| {
+                        format!(
+                            "Operation to delete source `{}` has failed.",
+                            source.full_name().0
+                        )
+                    })
+                    .map(|()| debug!("Deleted source `{}`", source.full_name().0));
+                sender.send(result).expect("Could not send result");
+            });
+        }
+    });
+
+    drop(sender);
+    receiver.iter().collect::<Result<Vec<_>>>()?;
+    Ok(())
+}
+
+/// Number of resources owned by a project, which are deleted along with it when forced.
+struct ProjectContents {
+    num_datasets: usize,
+    num_sources: usize,
+    num_buckets: usize,
+}
+
+impl ProjectContents {
+    fn get(client: &Client, project: &ProjectName) -> Result<Self> {
+        let num_datasets = client
+            .get_datasets()
+            .context("Operation to list datasets has failed.")?
+            .iter()
+            .filter(|dataset| dataset.owner.0 == project.0)
+            .count();
+        let num_sources = client
+            .get_sources()
+            .context("Operation to list sources has failed.")?
+            .iter()
+            .filter(|source| source.owner.0 == project.0)
+            .count();
+        let num_buckets = client
+            .get_buckets()
+            .context("Operation to list buckets has failed.")?
+            .iter()
+            .filter(|bucket| bucket.owner.0 == project.0)
+            .count();
+        Ok(Self {
+            num_datasets,
+            num_sources,
+            num_buckets,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.num_datasets == 0 && self.num_sources == 0 && self.num_buckets == 0
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} dataset(s), {} source(s) and {} bucket(s)",
+            self.num_datasets, self.num_sources, self.num_buckets
+        )
+    }
+}
+
+fn wait_for_source_deletion(client: &Client, source_id: SourceId, timeout: Duration) -> Result<()> {
+    let deleted = poll_until(timeout, || match client.get_source(source_id.clone()) {
+        Err(error) if error.is_not_found() => Ok(true),
+        Err(error) => Err(error).context("Operation to get source has failed."),
+        Ok(_) => {
+            debug!("Source {} still exists, waiting", source_id.0);
+            Ok(false)
+        }
+    })?;
+    if !deleted {
+        bail!(
+            "Source {} still exists after waiting {} seconds",
+            source_id.0,
+            timeout.as_secs()
+        );
+    }
+    Ok(())
+}
+
 fn delete_comments_in_period(
     client: &Client,
     source: Source,
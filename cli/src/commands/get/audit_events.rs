@@ -15,12 +15,17 @@ pub struct GetAuditEventsArgs {
     #[structopt(short = "M", long = "maximum")]
     /// Maximum Timestamp for audit events
     maximum_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "max-items")]
+    /// Stop after fetching this many audit events
+    max_items: Option<usize>,
 }
 
 pub fn get(client: &Client, args: &GetAuditEventsArgs, printer: &Printer) -> Result<()> {
     let GetAuditEventsArgs {
         minimum_timestamp,
         maximum_timestamp,
+        max_items,
     } = args;
 
     let mut continuation = None;
@@ -35,6 +40,17 @@ pub fn get(client: &Client, args: &GetAuditEventsArgs, printer: &Printer) -> Res
 
         all_printable_events.append(&mut printable_events);
 
+        if let Some(max_items) = max_items {
+            if all_printable_events.len() > *max_items {
+                all_printable_events.truncate(*max_items);
+                info!(
+                    "Stopped after {} events as the `--max-items` limit was reached.",
+                    all_printable_events.len()
+                );
+                break;
+            }
+        }
+
         if audit_events.continuation.is_none() {
             break;
         } else {
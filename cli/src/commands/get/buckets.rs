@@ -1,24 +1,34 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::mpsc::channel};
 
 use anyhow::{Context, Result};
 use log::info;
 use reinfer_client::{BucketIdentifier, Client};
+use scoped_threadpool::Pool;
 use structopt::StructOpt;
 
-use crate::printer::{PrintableBucket, Printer};
+use crate::{
+    printer::{PrintableBucket, Printer},
+    utils::{NameArg, Resolver},
+};
 
 #[derive(Debug, StructOpt)]
 pub struct GetBucketsArgs {
     #[structopt(name = "bucket")]
     /// If specified, only list this bucket (name or id)
-    bucket: Option<BucketIdentifier>,
+    bucket: Option<NameArg<BucketIdentifier>>,
 
-    #[structopt(long = "stats")]
-    /// Whether to include bucket statistics in response
+    #[structopt(long = "stats", alias = "with-stats")]
+    /// Whether to include bucket statistics (number of emails) in response
     include_stats: bool,
 }
 
-pub fn get(client: &Client, args: &GetBucketsArgs, printer: &Printer) -> Result<()> {
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetBucketsArgs,
+    printer: &Printer,
+    pool: &mut Pool,
+) -> Result<()> {
     let GetBucketsArgs {
         bucket,
         include_stats,
@@ -26,7 +36,7 @@ pub fn get(client: &Client, args: &GetBucketsArgs, printer: &Printer) -> Result<
 
     let buckets = if let Some(bucket) = bucket {
         vec![client
-            .get_bucket(bucket.clone())
+            .get_bucket(resolver.resolve(bucket)?)
             .context("Operation to list buckets has failed.")?]
     } else {
         let mut buckets = client
@@ -41,15 +51,27 @@ pub fn get(client: &Client, args: &GetBucketsArgs, printer: &Printer) -> Result<
     let mut bucket_stats: HashMap<_, _> = HashMap::new();
 
     if *include_stats {
-        buckets.iter().try_for_each(|bucket| -> Result<()> {
-            info!("Getting statistics for bucket {}", bucket.full_name().0);
-            let stats = client
-                .get_bucket_statistics(&bucket.full_name())
-                .context("Could not get statistics for bucket")?;
+        let (sender, receiver) = channel();
 
-            bucket_stats.insert(bucket.id.clone(), stats);
-            Ok(())
-        })?;
+        pool.scoped(|scope| {
+            buckets.iter().for_each(|bucket| {
+                let sender = sender.clone();
+                scope.execute(move || {
+                    info!("Getting statistics for bucket {}", bucket.full_name().0);
+                    let stats = client
+                        .get_bucket_statistics(&bucket.full_name())
+                        .context("Could not get statistics for bucket")
+                        .map(|stats| (bucket.id.clone(), stats));
+                    sender.send(stats).expect("Could not send error");
+                });
+            });
+        });
+
+        drop(sender);
+        for result in receiver.iter() {
+            let (bucket_id, stats) = result?;
+            bucket_stats.insert(bucket_id, stats);
+        }
     }
 
     let printable_buckets: Vec<PrintableBucket> = buckets
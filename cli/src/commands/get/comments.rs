@@ -3,7 +3,7 @@ use anyhow::{anyhow, bail, Context, Error, Result};
 use chrono::{DateTime, Utc};
 use colored::Colorize;
 use dialoguer::{Input, MultiSelect, Select};
-use log::info;
+use log::{info, warn};
 use ordered_float::NotNan;
 use rand::Rng;
 use regex::Regex;
@@ -11,7 +11,7 @@ use reinfer_client::{
     resources::{
         comment::{
             CommentTimestampFilter, MessagesFilter, PropertyFilter, ReviewedFilterEnum,
-            UserPropertiesFilter,
+            ThreadPropertiesFilter, UserPropertiesFilter,
         },
         dataset::{
             Attribute, AttributeFilter, AttributeFilterEnum, OrderEnum, QueryRequestParams,
@@ -20,35 +20,39 @@ use reinfer_client::{
         source::StatisticsRequestParams as SourceStatisticsRequestParams,
     },
     AnnotatedComment, Client, Comment, CommentFilter, CommentId, CommentPredictionsThreshold,
-    CommentsIterTimerange, DatasetFullName, DatasetIdentifier, Entities, HasAnnotations, Labelling,
-    ModelVersion, PredictedLabel, PropertyValue, Source, SourceIdentifier,
-    DEFAULT_LABEL_GROUP_NAME,
+    CommentsIter, CommentsIterTimerange, DatasetFullName, DatasetIdentifier, Entities, EntityName,
+    HasAnnotations, Labelling, ModelVersion, PredictedLabel, PropertyValue, Source, SourceId,
+    SourceIdentifier, DEFAULT_LABEL_GROUP_NAME,
 };
+use scoped_threadpool::Pool;
 use serde::Deserialize;
 use std::{
-    collections::HashMap,
-    fs::{create_dir, File},
-    io::{self, BufWriter, Write},
+    collections::{HashMap, HashSet},
+    fs::create_dir,
+    io::{self, BufRead, Write},
     path::{Path, PathBuf},
     str::FromStr,
     sync::{
         atomic::{AtomicUsize, Ordering},
+        mpsc::channel,
         Arc,
     },
 };
 use structopt::StructOpt;
 
+use crate::utils::{closest_names, NameArg, Resolver};
 use crate::{
-    commands::LocalAttachmentPath,
+    commands::{AttachmentDeduplicator, LocalAttachmentPath},
     printer::print_resources_as_json,
     progress::{Options as ProgressOptions, Progress},
+    utils::{self, ModelVersionArg, OutputFile},
 };
 
 #[derive(Debug, StructOpt)]
 pub struct GetSingleCommentArgs {
     #[structopt(long = "source")]
     /// Source name or id
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
 
     #[structopt(name = "comment-id")]
     /// Comment id.
@@ -61,13 +65,25 @@ pub struct GetSingleCommentArgs {
 
 #[derive(Debug, StructOpt)]
 pub struct GetManyCommentsArgs {
-    #[structopt(name = "source")]
+    #[structopt(
+        name = "source",
+        required_unless_one = &["extra-sources", "all-dataset-sources"]
+    )]
     /// Source name or id
-    source: SourceIdentifier,
+    source: Option<NameArg<SourceIdentifier>>,
+
+    #[structopt(long = "source")]
+    /// Another source name or id to download comments from, into the same output. Can be given
+    /// several times.
+    extra_sources: Vec<NameArg<SourceIdentifier>>,
+
+    #[structopt(long = "all-dataset-sources", requires = "dataset")]
+    /// Download comments from all the sources of --dataset
+    all_dataset_sources: bool,
 
     #[structopt(short = "d", long = "dataset")]
     /// Dataset name or id
-    dataset: Option<DatasetIdentifier>,
+    dataset: Option<NameArg<DatasetIdentifier>>,
 
     #[structopt(long)]
     /// Don't display a progress bar (only applicable when --file is used).
@@ -79,7 +95,8 @@ pub struct GetManyCommentsArgs {
 
     #[structopt(long = "model-version")]
     /// Get predicted labels and entities from the specified model version rather than latest.
-    model_version: Option<u32>,
+    /// Use `latest` for the newest pinned model version.
+    model_version: Option<ModelVersionArg>,
 
     #[structopt(long = "reviewed-only")]
     /// Download reviewed comments only.
@@ -93,6 +110,16 @@ pub struct GetManyCommentsArgs {
     /// Ending timestamp for comments to retrieve (inclusive).
     to_timestamp: Option<DateTime<Utc>>,
 
+    #[structopt(
+        long = "since-export",
+        parse(from_os_str),
+        conflicts_with = "from-timestamp"
+    )]
+    /// Path to a previous jsonl export of this command. Only comments with a timestamp at or
+    /// after the latest comment in it are retrieved, so the output can be appended to it. Comments
+    /// at exactly that timestamp are retrieved again, so deduplicate by id when merging.
+    since_export: Option<PathBuf>,
+
     #[structopt(long = "senders")]
     /// Filter to comments only from these senders
     senders: Option<Vec<String>>,
@@ -102,17 +129,48 @@ pub struct GetManyCommentsArgs {
     recipients: Option<Vec<String>>,
 
     #[structopt(short = "f", long = "file", parse(from_os_str))]
-    /// Path where to write comments as JSON. If not specified, stdout will be used.
+    /// Path where to write comments. If not specified, stdout will be used. Paths ending in
+    /// `.gz` are gzip compressed.
     path: Option<PathBuf>,
 
     #[structopt(short = "l", long = "label-filter")]
     /// Regex filter to select which labels you want to download predictions for
     label_filter: Option<Regex>,
 
+    #[structopt(long = "predicted-label")]
+    /// Only download comments where this label was predicted by --model-version. Can be given
+    /// several times to match any of the labels.
+    predicted_labels: Vec<String>,
+
+    #[structopt(long = "prediction-threshold")]
+    /// Minimum confidence for a --predicted-label to count as predicted, between 0 and 1
+    /// [default: 0.5]
+    prediction_threshold: Option<f64>,
+
+    #[structopt(long = "assigned-label")]
+    /// Only download reviewed comments where this label was assigned. Can be given several times
+    /// to match any of the labels.
+    assigned_labels: Vec<String>,
+
     #[structopt(short = "p", long = "user-property-filter")]
     /// The user property filter to use as a json string
     property_filter: Option<StructExt<UserPropertiesFilter>>,
 
+    #[structopt(long = "thread-property")]
+    /// Filter on a thread property, as `name=value` to include or `name!=value` to exclude
+    /// comments with that value. Can be given several times.
+    thread_property_filters: Vec<ThreadPropertyFilterArg>,
+
+    #[structopt(long = "include-thread-properties", conflicts_with = "dataset")]
+    /// Include the thread properties of each comment. Only applicable when downloading from a
+    /// source without --dataset.
+    include_thread_properties: bool,
+
+    #[structopt(long = "page-size", conflicts_with = "dataset")]
+    /// Number of comments to request per page, at most 256. Defaults to 64. Only applicable when
+    /// downloading from a source without --dataset.
+    page_size: Option<usize>,
+
     #[structopt(long = "interactive-user-property-filter")]
     /// Open a dialog to interactively construct the user property filter to use
     interactive_property_filter: bool,
@@ -136,10 +194,83 @@ pub struct GetManyCommentsArgs {
     #[structopt(long = "--stop-after")]
     /// Stop downloading comments after X comments (stops in following batch)
     stop_after: Option<usize>,
+
+    #[structopt(long = "format", default_value = "jsonl")]
+    /// Format to write comments in. One of: jsonl, csv
+    format: CommentsFormat,
+
+    #[structopt(long = "attachment-concurrency", default_value = "8")]
+    /// Maximum number of attachments to download in parallel (only applicable with
+    /// --attachments). Bounded by --num-threads.
+    attachment_concurrency: usize,
+
+    #[structopt(long = "max-attachment-size")]
+    /// Skip attachments larger than this many bytes (only applicable with --attachments)
+    max_attachment_size: Option<u64>,
+
+    #[structopt(long = "dedup-attachments")]
+    /// Write attachments with identical content only once, hard linking (or copying, where
+    /// linking is not possible) later copies to the first one
+    dedup_attachments: bool,
+
+    #[structopt(long = "flatten-entities")]
+    /// In csv format, add one column per entity kind of the dataset, containing the extracted
+    /// values joined with `; `
+    flatten_entities: bool,
+
+    #[structopt(long = "split-by")]
+    /// Split the output file into several files. One of: day, hour (by comment timestamp, e.g.
+    /// `comments-2024-08-07.jsonl`) or count:N (N comments per file, e.g. `comments-00000.jsonl`).
+    /// The attachments directory is shared by all files.
+    split_by: Option<SplitBy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    Day,
+    Hour,
+    Count(usize),
+}
+
+impl FromStr for SplitBy {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "day" => Ok(Self::Day),
+            "hour" => Ok(Self::Hour),
+            _ => match string.strip_prefix("count:").map(str::parse::<usize>) {
+                Some(Ok(count)) if count > 0 => Ok(Self::Count(count)),
+                _ => Err(anyhow!(
+                    "expected one of day, hour or count:N with N > 0, got '{}'",
+                    string
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentsFormat {
+    #[default]
+    Jsonl,
+    Csv,
+}
+
+impl FromStr for CommentsFormat {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "jsonl" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow!("unknown comments format: '{}'", string)),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct StructExt<T>(pub T);
+pub(crate) struct StructExt<T>(pub T);
 
 impl<T: serde::de::DeserializeOwned> FromStr for StructExt<T> {
     type Err = Error;
@@ -155,25 +286,21 @@ impl<T: serde::de::DeserializeOwned> FromStr for StructExt<T> {
     }
 }
 
-pub fn get_single(client: &Client, args: &GetSingleCommentArgs) -> Result<()> {
+pub fn get_single(client: &Client, resolver: &Resolver, args: &GetSingleCommentArgs) -> Result<()> {
     let GetSingleCommentArgs {
         source,
         comment_id,
         path,
     } = args;
     let file: Option<Box<dyn Write>> = match path {
-        Some(path) => Some(Box::new(
-            File::create(path)
-                .with_context(|| format!("Could not open file for writing `{}`", path.display()))
-                .map(BufWriter::new)?,
-        )),
+        Some(path) => Some(Box::new(utils::create_output_file(path)?)),
         None => None,
     };
 
     let stdout = io::stdout();
     let mut writer: Box<dyn Write> = file.unwrap_or_else(|| Box::new(stdout.lock()));
-    let source = client
-        .get_source(source.to_owned())
+    let source = resolver
+        .get_source(client, resolver.resolve(source)?)
         .context("Operation to get source has failed.")?;
     let comment = client.get_comment(&source.full_name(), comment_id)?;
     print_resources_as_json(
@@ -379,6 +506,78 @@ pub fn get_user_properties_filter_interactively(summary: &Summary) -> Result<Use
     Ok(UserPropertiesFilter(property_filters))
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadPropertyFilterArg {
+    name: String,
+    value: PropertyValue,
+    exclude: bool,
+}
+
+impl FromStr for ThreadPropertyFilterArg {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let (name, value, exclude) = if let Some((name, value)) = string.split_once("!=") {
+            (name, value, true)
+        } else if let Some((name, value)) = string.split_once('=') {
+            (name, value, false)
+        } else {
+            bail!("Expected a thread property filter as `name=value` or `name!=value`, got `{string}`")
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Expected a thread property name before `=` in `{string}`")
+        }
+
+        let value = value.trim();
+        Ok(Self {
+            name: name.to_owned(),
+            value: match value.parse::<NotNan<f64>>() {
+                Ok(number) => PropertyValue::Number(number),
+                Err(_) => PropertyValue::String(value.to_owned()),
+            },
+            exclude,
+        })
+    }
+}
+
+fn get_thread_properties_filter(filters: &[ThreadPropertyFilterArg]) -> ThreadPropertiesFilter {
+    let mut property_filters: HashMap<String, PropertyFilter> = HashMap::new();
+    for filter in filters {
+        let property_filter = property_filters.entry(filter.name.clone()).or_default();
+        if filter.exclude {
+            property_filter.not_one_of.push(filter.value.clone());
+        } else {
+            property_filter.one_of.push(filter.value.clone());
+        }
+    }
+    ThreadPropertiesFilter(property_filters)
+}
+
+/// Check that every thread property filter refers to a thread property in the dataset summary.
+fn check_thread_property_names(
+    filters: &[ThreadPropertyFilterArg],
+    summary: &Summary,
+) -> Result<()> {
+    let Some(thread_properties) = &summary.thread_properties else {
+        warn!("Dataset summary has no thread properties, cannot check thread property names");
+        return Ok(());
+    };
+
+    let valid_names: Vec<&str> = thread_properties.full_names().collect();
+    for filter in filters {
+        if !valid_names.contains(&filter.name.as_str()) {
+            bail!(
+                "Unknown thread property `{}`, expected one of: {}",
+                filter.name,
+                valid_names.join(", ")
+            )
+        }
+    }
+    Ok(())
+}
+
 fn get_possible_values_for_string_property(
     dataset_summary: &Summary,
     property_name: &String,
@@ -395,19 +594,273 @@ fn get_possible_values_for_string_property(
         .collect())
 }
 
+/// Writes pages of comments in the requested format.
+#[allow(clippy::large_enum_variant)]
+enum CommentsWriter<W: Write> {
+    Jsonl(W),
+    Csv {
+        writer: csv::Writer<W>,
+        entity_names: Option<Vec<EntityName>>,
+        wrote_headers: bool,
+    },
+    Split(Box<SplitCommentsWriter>),
+}
+
+impl<W: Write> CommentsWriter<W> {
+    fn new(writer: W, format: CommentsFormat, entity_names: Option<Vec<EntityName>>) -> Self {
+        match format {
+            CommentsFormat::Jsonl => Self::Jsonl(writer),
+            CommentsFormat::Csv => Self::Csv {
+                writer: csv::Writer::from_writer(writer),
+                entity_names,
+                wrote_headers: false,
+            },
+        }
+    }
+
+    fn write(&mut self, comments: impl IntoIterator<Item = AnnotatedComment>) -> Result<()> {
+        match self {
+            Self::Jsonl(writer) => print_resources_as_json(comments, writer),
+            Self::Csv {
+                writer,
+                entity_names,
+                wrote_headers,
+            } => {
+                if !*wrote_headers {
+                    writer
+                        .write_record(csv_headers(entity_names.as_deref()))
+                        .context("Could not write CSV headers")?;
+                    *wrote_headers = true;
+                }
+                for comment in comments {
+                    writer
+                        .write_record(csv_record(&comment, entity_names.as_deref()))
+                        .context("Could not write CSV record")?;
+                }
+                writer.flush().context("Could not flush CSV writer")
+            }
+            Self::Split(writer) => writer.write(comments),
+        }
+    }
+
+    /// Flush any buffered output and return the underlying writer. Split writers finish their
+    /// own files and return `None`.
+    fn into_inner(self) -> Result<Option<W>> {
+        match self {
+            Self::Jsonl(writer) => Ok(Some(writer)),
+            Self::Csv { writer, .. } => writer
+                .into_inner()
+                .map(Some)
+                .map_err(|error| Error::new(error.into_error()))
+                .context("Could not flush CSV writer"),
+            Self::Split(mut writer) => writer.finish().map(|()| None),
+        }
+    }
+}
+
+/// Writes comments to a sequence of files, rotating based on the comment timestamp or count.
+struct SplitCommentsWriter {
+    path: PathBuf,
+    split_by: SplitBy,
+    format: CommentsFormat,
+    entity_names: Option<Vec<EntityName>>,
+    current: Option<(String, CommentsWriter<OutputFile>)>,
+    /// Files which were already written to, and should be appended to if revisited.
+    written_keys: HashSet<String>,
+    num_written: usize,
+}
+
+impl SplitCommentsWriter {
+    fn new(
+        path: PathBuf,
+        split_by: SplitBy,
+        format: CommentsFormat,
+        entity_names: Option<Vec<EntityName>>,
+    ) -> Self {
+        Self {
+            path,
+            split_by,
+            format,
+            entity_names,
+            current: None,
+            written_keys: HashSet::new(),
+            num_written: 0,
+        }
+    }
+
+    fn key(&self, comment: &AnnotatedComment) -> String {
+        match self.split_by {
+            SplitBy::Day => comment.comment.timestamp.format("%Y-%m-%d").to_string(),
+            SplitBy::Hour => comment.comment.timestamp.format("%Y-%m-%dT%H").to_string(),
+            SplitBy::Count(count) => format!("{:05}", self.num_written / count),
+        }
+    }
+
+    fn write(&mut self, comments: impl IntoIterator<Item = AnnotatedComment>) -> Result<()> {
+        let mut batch = Vec::new();
+        for comment in comments {
+            let key = self.key(&comment);
+            if self.current.as_ref().map(|(current, _)| current) != Some(&key) {
+                self.write_batch(&mut batch)?;
+                self.rotate(key)?;
+            }
+            batch.push(comment);
+            self.num_written += 1;
+        }
+        self.write_batch(&mut batch)
+    }
+
+    fn write_batch(&mut self, batch: &mut Vec<AnnotatedComment>) -> Result<()> {
+        match &mut self.current {
+            Some((_, writer)) if !batch.is_empty() => writer.write(batch.drain(..)),
+            _ => Ok(()),
+        }
+    }
+
+    fn rotate(&mut self, key: String) -> Result<()> {
+        self.finish()?;
+
+        let path = split_file_path(&self.path, &key);
+        let append = !self.written_keys.insert(key.clone());
+        let file = utils::open_output_file(&path, append)?;
+
+        let mut writer = CommentsWriter::new(file, self.format, self.entity_names.clone());
+        if let CommentsWriter::Csv { wrote_headers, .. } = &mut writer {
+            *wrote_headers = append;
+        }
+        self.current = Some((key, writer));
+        Ok(())
+    }
+
+    /// Finish writing the current file, if any.
+    fn finish(&mut self) -> Result<()> {
+        if let Some((key, writer)) = self.current.take() {
+            if let Some(file) = writer.into_inner()? {
+                file.finish().with_context(|| {
+                    format!(
+                        "Could not finish writing `{}`",
+                        split_file_path(&self.path, &key).display()
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Insert the key before the extension of the path, e.g. `comments.jsonl` -> `comments-key.jsonl`
+/// and `comments.jsonl.gz` -> `comments-key.jsonl.gz`.
+fn split_file_path(path: &Path, key: &str) -> PathBuf {
+    if utils::is_gzip_path(path) {
+        let mut split_path = split_file_path(&path.with_extension(""), key).into_os_string();
+        split_path.push(".gz");
+        return split_path.into();
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}-{key}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{key}"),
+    };
+    path.with_file_name(file_name)
+}
+
+const CSV_HEADERS: &[&str] = &[
+    "id",
+    "uid",
+    "thread_id",
+    "timestamp",
+    "from",
+    "subject",
+    "text",
+    "assigned_labels",
+];
+
+fn csv_headers(entity_names: Option<&[EntityName]>) -> Vec<String> {
+    CSV_HEADERS
+        .iter()
+        .map(|header| header.to_string())
+        .chain(
+            entity_names
+                .unwrap_or_default()
+                .iter()
+                .map(|name| name.0.clone()),
+        )
+        .collect()
+}
+
+fn csv_record(comment: &AnnotatedComment, entity_names: Option<&[EntityName]>) -> Vec<String> {
+    let first_message = comment.comment.messages.first();
+    let assigned_labels = comment
+        .labelling
+        .iter()
+        .flatten()
+        .flat_map(|labelling| labelling.assigned.iter())
+        .map(|label| label.name.0.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let mut record = vec![
+        comment.comment.id.0.clone(),
+        comment.comment.uid.0.clone(),
+        comment
+            .comment
+            .thread_id
+            .as_ref()
+            .map(|thread_id| thread_id.0.clone())
+            .unwrap_or_default(),
+        comment.comment.timestamp.to_rfc3339(),
+        first_message
+            .and_then(|message| message.from.clone())
+            .unwrap_or_default(),
+        first_message
+            .and_then(|message| message.subject.as_ref())
+            .map(|subject| subject.text.clone())
+            .unwrap_or_default(),
+        first_message
+            .map(|message| message.body.text.clone())
+            .unwrap_or_default(),
+        assigned_labels,
+    ];
+
+    if let Some(entity_names) = entity_names {
+        // Use reviewed entities if there are any, otherwise fall back to predictions.
+        let entities = comment.entities.as_ref().map(|entities| {
+            if entities.assigned.is_empty() {
+                entities.predicted.as_deref().unwrap_or_default()
+            } else {
+                entities.assigned.as_slice()
+            }
+        });
+        record.extend(entity_names.iter().map(|entity_name| {
+            entities
+                .unwrap_or_default()
+                .iter()
+                .filter(|entity| entity.name == *entity_name)
+                .map(|entity| entity.formatted_value.as_str())
+                .collect::<Vec<_>>()
+                .join("; ")
+        }));
+    }
+    record
+}
+
 #[derive(Default)]
 struct OutputLocations {
-    jsonl_file: Option<BufWriter<std::fs::File>>,
+    output_file: Option<OutputFile>,
     attachments_dir: Option<PathBuf>,
 }
 
-fn get_output_locations(path: &Option<PathBuf>, attachments: bool) -> Result<OutputLocations> {
+fn get_output_locations(
+    path: &Option<PathBuf>,
+    attachments: bool,
+    create_output_file: bool,
+) -> Result<OutputLocations> {
     if let Some(path) = path {
-        let jsonl_file = Some(
-            File::create(path)
-                .with_context(|| format!("Could not open file for writing `{}`", path.display()))
-                .map(BufWriter::new)?,
-        );
+        let output_file = if create_output_file {
+            Some(utils::create_output_file(path)?)
+        } else {
+            None
+        };
 
         let attachments_dir = if attachments {
             let attachments_dir = path
@@ -429,7 +882,7 @@ fn get_output_locations(path: &Option<PathBuf>, attachments: bool) -> Result<Out
         };
 
         Ok(OutputLocations {
-            jsonl_file,
+            output_file,
             attachments_dir,
         })
     } else {
@@ -437,9 +890,16 @@ fn get_output_locations(path: &Option<PathBuf>, attachments: bool) -> Result<Out
     }
 }
 
-pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
+pub fn get_many(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetManyCommentsArgs,
+    pool: &mut Pool,
+) -> Result<()> {
     let GetManyCommentsArgs {
         source,
+        extra_sources,
+        all_dataset_sources,
         dataset,
         no_progress,
         include_predictions,
@@ -447,10 +907,17 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         reviewed_only,
         from_timestamp,
         to_timestamp,
+        since_export,
         path,
         label_filter,
+        predicted_labels,
+        prediction_threshold,
+        assigned_labels,
         attachment_type_filters,
         property_filter: user_property_filter,
+        thread_property_filters,
+        include_thread_properties,
+        page_size,
         interactive_property_filter: interative_property_filter,
         recipients,
         senders,
@@ -458,7 +925,29 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         only_with_attachments,
         shuffle,
         stop_after,
+        format,
+        flatten_entities,
+        attachment_concurrency,
+        dedup_attachments,
+        max_attachment_size,
+        split_by,
     } = args;
+    let dataset = &dataset
+        .as_ref()
+        .map(|dataset| resolver.resolve(dataset))
+        .transpose()?;
+
+    let from_timestamp = match since_export {
+        Some(since_export) => {
+            let latest = latest_comment_timestamp(since_export)?;
+            match latest {
+                Some(latest) => info!("Getting comments since {latest}"),
+                None => info!("No comments in previous export, getting all comments"),
+            }
+            latest
+        }
+        None => *from_timestamp,
+    };
 
     let by_timerange = from_timestamp.is_some() || to_timestamp.is_some();
     if reviewed_only.unwrap_or_default() && by_timerange {
@@ -495,6 +984,27 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         bail!("The `label_filter` and `model_version` options are mutually exclusive.")
     }
 
+    if !predicted_labels.is_empty() && (dataset.is_none() || model_version.is_none()) {
+        bail!("Cannot use a predicted label filter when `dataset` and `model_version` are not provided.")
+    }
+
+    if prediction_threshold.is_some() && predicted_labels.is_empty() {
+        bail!("The `prediction_threshold` option can only be used with `predicted_label`.")
+    }
+
+    let prediction_threshold = prediction_threshold.unwrap_or(0.5);
+    if !(0.0..=1.0).contains(&prediction_threshold) {
+        bail!("The `prediction_threshold` option must be between 0 and 1.")
+    }
+
+    if !predicted_labels.is_empty() && reviewed_only {
+        bail!("The `predicted_label` and `reviewed_only` options are mutually exclusive.")
+    }
+
+    if !assigned_labels.is_empty() && dataset.is_none() {
+        bail!("Cannot use an assigned label filter when `dataset` is not provided.")
+    }
+
     if (user_property_filter.is_some() || *interative_property_filter) && dataset.is_none() {
         bail!("Cannot use a property filter when `dataset` is not provided.")
     }
@@ -503,6 +1013,14 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         bail!("The `reviewed_only` and `property_filter` options are mutually exclusive.")
     }
 
+    if !thread_property_filters.is_empty() && dataset.is_none() {
+        bail!("Cannot use a thread property filter when `dataset` is not provided.")
+    }
+
+    if !thread_property_filters.is_empty() && reviewed_only {
+        bail!("The `reviewed_only` and `thread_property` options are mutually exclusive.")
+    }
+
     if user_property_filter.is_some() && *interative_property_filter {
         bail!("The `interative_property_filter` and `property_filter` options are mutually exclusive.")
     }
@@ -519,46 +1037,86 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         bail!("Cannot shuffle data when dataset is not provided")
     }
 
+    if *attachment_concurrency == 0 {
+        bail!("The `attachment_concurrency` option must be greater than 0.")
+    }
+
+    if *flatten_entities && *format != CommentsFormat::Csv {
+        bail!("The `flatten_entities` option can only be used with the csv format.")
+    }
+
+    if *flatten_entities && dataset.is_none() {
+        bail!("Cannot flatten entities when `dataset` is not provided.")
+    }
+
+    if path.is_none() && split_by.is_some() {
+        bail!("Cannot split output when no file is provided")
+    }
+
+    let model_version = match (model_version, dataset) {
+        (Some(model_version), Some(dataset_id)) => {
+            let dataset_name = resolver
+                .get_dataset(client, dataset_id.clone())
+                .context("Operation to get dataset has failed.")?
+                .full_name();
+            Some(model_version.resolve(client, &dataset_name)?.0)
+        }
+        (Some(ModelVersionArg::Version(version)), None) => Some(*version),
+        (Some(ModelVersionArg::Latest), None) => {
+            bail!("Cannot use the `latest` model version when `dataset` is not provided.")
+        }
+        (None, _) => None,
+    };
+
     let OutputLocations {
-        jsonl_file,
+        output_file,
         attachments_dir,
-    } = get_output_locations(path, include_attachment_content.unwrap_or_default())?;
+    } = get_output_locations(
+        path,
+        include_attachment_content.unwrap_or_default(),
+        split_by.is_none(),
+    )?;
 
     let mut label_attribute_filter: Option<AttributeFilter> = None;
     if let (Some(dataset_id), Some(filter)) = (dataset, label_filter) {
-        label_attribute_filter = get_label_attribute_filter(client, dataset_id.clone(), filter)?;
+        label_attribute_filter =
+            get_label_attribute_filter(client, resolver, dataset_id.clone(), filter)?;
         // Exit early if no labels match label filter
         if label_attribute_filter.is_none() {
             return Ok(());
         }
     }
 
+    let predicted_labels = match dataset {
+        Some(dataset_id) if !predicted_labels.is_empty() => {
+            resolve_label_names(client, resolver, dataset_id.clone(), predicted_labels)?
+        }
+        _ => Vec::new(),
+    };
+
+    let assigned_labels = match dataset {
+        Some(dataset_id) if !assigned_labels.is_empty() => {
+            resolve_label_names(client, resolver, dataset_id.clone(), assigned_labels)?
+        }
+        _ => Vec::new(),
+    };
+
     let mut attachment_property_types_filter: Option<AttributeFilter> = None;
 
     if !attachment_type_filters.is_empty() {
-        attachment_property_types_filter = Some(AttributeFilter {
-            attribute: Attribute::AttachmentPropertyTypes,
-            filter: AttributeFilterEnum::StringAnyOf {
-                any_of: attachment_type_filters.to_vec(),
-            },
-        });
+        attachment_property_types_filter = Some(attachment_types_filter(attachment_type_filters));
     }
 
     let mut only_with_attachments_filter: Option<AttributeFilter> = None;
     if only_with_attachments.unwrap_or_default() {
-        only_with_attachments_filter = Some(AttributeFilter {
-            attribute: Attribute::AttachmentPropertyNumAttachments,
-            filter: AttributeFilterEnum::NumberRange {
-                minimum: Some(1),
-                maximum: None,
-            },
-        });
+        only_with_attachments_filter = Some(with_attachments_filter());
     }
 
     let user_properties_filter = if let Some(filter) = user_property_filter {
         Some(filter.0.clone())
     } else if *interative_property_filter {
-        let dataset = client.get_dataset(dataset.clone().context("Could not get dataset")?)?;
+        let dataset =
+            resolver.get_dataset(client, dataset.clone().context("Could not get dataset")?)?;
         let summary_response = client.dataset_summary(&dataset.full_name(), &Default::default())?;
         Some(get_user_properties_filter_interactively(
             &summary_response.summary,
@@ -567,6 +1125,18 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
         None
     };
 
+    let thread_properties_filter = if thread_property_filters.is_empty() {
+        None
+    } else {
+        let dataset =
+            resolver.get_dataset(client, dataset.clone().context("Could not get dataset")?)?;
+        let summary_response = client
+            .dataset_summary(&dataset.full_name(), &Default::default())
+            .context("Operation to get dataset summary has failed.")?;
+        check_thread_property_names(thread_property_filters, &summary_response.summary)?;
+        Some(get_thread_properties_filter(thread_property_filters))
+    };
+
     let messages_filter = MessagesFilter {
         from: senders.as_ref().map(|senders| {
             PropertyFilter::new(
@@ -593,41 +1163,164 @@ pub fn get_many(client: &Client, args: &GetManyCommentsArgs) -> Result<()> {
     let download_options = CommentDownloadOptions {
         dataset_identifier: dataset.clone(),
         include_predictions: include_predictions.unwrap_or(false),
-        model_version: *model_version,
+        model_version,
         reviewed_only,
         timerange: CommentsIterTimerange {
-            from: *from_timestamp,
+            from: from_timestamp,
             to: *to_timestamp,
         },
         show_progress: !no_progress,
         label_attribute_filter,
+        predicted_labels,
+        prediction_threshold,
+        assigned_labels,
         user_properties_filter,
+        thread_properties_filter,
+        include_thread_properties: *include_thread_properties,
+        page_size: utils::clamp_page_size(*page_size, CommentsIter::MAX_PAGE_SIZE),
         attachment_property_types_filter,
         messages_filter: Some(messages_filter),
         attachments_dir,
         only_with_attachments_filter,
         shuffle: shuffle.unwrap_or(false),
         stop_after: *stop_after,
+        attachment_concurrency: *attachment_concurrency,
+        attachment_deduplicator: dedup_attachments.then(AttachmentDeduplicator::default),
+        max_attachment_size: *max_attachment_size,
     };
 
-    if let Some(file) = jsonl_file {
-        download_comments(client, source.clone(), file, download_options)
+    let sources = resolve_sources(
+        client,
+        resolver,
+        source.iter().chain(extra_sources),
+        if *all_dataset_sources {
+            dataset.as_ref()
+        } else {
+            None
+        },
+    )?;
+
+    let entity_names = match (flatten_entities, dataset) {
+        (true, Some(dataset)) => Some(
+            resolver
+                .get_dataset(client, dataset.clone())
+                .context("Operation to get dataset has failed.")?
+                .entity_defs
+                .into_iter()
+                .map(|entity_def| entity_def.name)
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    if let (Some(split_by), Some(path)) = (split_by, path) {
+        let mut writer = CommentsWriter::<OutputFile>::Split(Box::new(SplitCommentsWriter::new(
+            path.clone(),
+            *split_by,
+            *format,
+            entity_names,
+        )));
+        download_comments(
+            client,
+            resolver,
+            &sources,
+            &mut writer,
+            &download_options,
+            pool,
+        )?;
+        writer.into_inner().map(|_| ())
+    } else if let Some(file) = output_file {
+        let mut writer = CommentsWriter::new(file, *format, entity_names);
+        download_comments(
+            client,
+            resolver,
+            &sources,
+            &mut writer,
+            &download_options,
+            pool,
+        )?;
+        match writer.into_inner()? {
+            Some(file) => file
+                .finish()
+                .context("Could not finish writing output file"),
+            None => Ok(()),
+        }
     } else {
         download_comments(
             client,
-            source.clone(),
-            io::stdout().lock(),
-            download_options,
+            resolver,
+            &sources,
+            &mut CommentsWriter::new(io::stdout().lock(), *format, entity_names),
+            &download_options,
+            pool,
         )
     }
 }
 
+/// The sources to download comments from: the given ones, and all the sources of `dataset` if
+/// any, without duplicates.
+fn resolve_sources<'a>(
+    client: &Client,
+    resolver: &Resolver,
+    identifiers: impl Iterator<Item = &'a NameArg<SourceIdentifier>>,
+    dataset: Option<&DatasetIdentifier>,
+) -> Result<Vec<Source>> {
+    let mut sources: Vec<Source> = Vec::new();
+    if let Some(dataset) = dataset {
+        let dataset = resolver
+            .get_dataset(client, dataset.clone())
+            .context("Operation to get dataset has failed.")?;
+        for source_id in dataset.source_ids {
+            sources.push(
+                resolver
+                    .get_source(client, source_id)
+                    .context("Operation to get source has failed.")?,
+            );
+        }
+    }
+    for identifier in identifiers {
+        let source = resolver
+            .get_source(client, resolver.resolve(identifier)?)
+            .context("Operation to get source has failed.")?;
+        if !sources.iter().any(|existing| existing.id == source.id) {
+            sources.push(source);
+        }
+    }
+    if sources.is_empty() {
+        bail!("There are no sources to download comments from.")
+    }
+    Ok(sources)
+}
+
+/// Get the latest comment timestamp in a previous jsonl export.
+fn latest_comment_timestamp(path: &Path) -> Result<Option<DateTime<Utc>>> {
+    let file = utils::open_input_file(path)?;
+
+    let mut latest: Option<DateTime<Utc>> = None;
+    for (index, line) in file.lines().enumerate() {
+        let line = line.with_context(|| format!("Could not read line {}", index + 1))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let comment: AnnotatedComment = serde_json::from_str(&line).with_context(|| {
+            format!(
+                "Could not parse comment on line {} of `{}`, is it a jsonl export?",
+                index + 1,
+                path.display()
+            )
+        })?;
+        latest = latest.max(Some(comment.comment.timestamp));
+    }
+    Ok(latest)
+}
+
 fn get_label_attribute_filter(
     client: &Client,
+    resolver: &Resolver,
     dataset_id: DatasetIdentifier,
     filter: &Regex,
 ) -> Result<Option<AttributeFilter>> {
-    let dataset = client.get_dataset(dataset_id)?;
+    let dataset = resolver.get_dataset(client, dataset_id)?;
 
     let label_names: Vec<String> = dataset
         .label_defs
@@ -650,6 +1343,64 @@ fn get_label_attribute_filter(
     }
 }
 
+/// Filter to comments with an attachment of any of `attachment_types`.
+pub(crate) fn attachment_types_filter(attachment_types: &[String]) -> AttributeFilter {
+    AttributeFilter {
+        attribute: Attribute::AttachmentPropertyTypes,
+        filter: AttributeFilterEnum::StringAnyOf {
+            any_of: attachment_types.to_vec(),
+        },
+    }
+}
+
+/// Filter to comments with at least one attachment.
+pub(crate) fn with_attachments_filter() -> AttributeFilter {
+    AttributeFilter {
+        attribute: Attribute::AttachmentPropertyNumAttachments,
+        filter: AttributeFilterEnum::NumberRange {
+            minimum: Some(1),
+            maximum: None,
+        },
+    }
+}
+
+pub(crate) fn resolve_label_names(
+    client: &Client,
+    resolver: &Resolver,
+    dataset_id: DatasetIdentifier,
+    names: &[String],
+) -> Result<Vec<String>> {
+    let dataset = resolver
+        .get_dataset(client, dataset_id)
+        .context("Operation to get dataset has failed.")?;
+
+    let label_names: Vec<String> = dataset
+        .label_defs
+        .iter()
+        .map(|label_def| label_def.name.0.clone())
+        .collect();
+
+    for name in names {
+        if !label_names.contains(name) {
+            let suggestions = closest_names(name, &label_names);
+            if suggestions.is_empty() {
+                bail!(
+                    "Label `{}` does not exist in dataset {}",
+                    name,
+                    dataset.full_name().0
+                )
+            }
+            bail!(
+                "Label `{}` does not exist in dataset {}, did you mean one of:\n- {}",
+                name,
+                dataset.full_name().0,
+                suggestions.join("\n- ")
+            )
+        }
+    }
+    Ok(names.to_vec())
+}
+
 struct CommentDownloadOptions {
     dataset_identifier: Option<DatasetIdentifier>,
     include_predictions: bool,
@@ -658,16 +1409,31 @@ struct CommentDownloadOptions {
     timerange: CommentsIterTimerange,
     show_progress: bool,
     label_attribute_filter: Option<AttributeFilter>,
+    predicted_labels: Vec<String>,
+    prediction_threshold: f64,
+    assigned_labels: Vec<String>,
     attachment_property_types_filter: Option<AttributeFilter>,
     user_properties_filter: Option<UserPropertiesFilter>,
+    thread_properties_filter: Option<ThreadPropertiesFilter>,
+    include_thread_properties: bool,
+    page_size: Option<usize>,
     messages_filter: Option<MessagesFilter>,
     attachments_dir: Option<PathBuf>,
     only_with_attachments_filter: Option<AttributeFilter>,
     shuffle: bool,
     stop_after: Option<usize>,
+    attachment_concurrency: usize,
+    attachment_deduplicator: Option<AttachmentDeduplicator>,
+    max_attachment_size: Option<u64>,
 }
 
 impl CommentDownloadOptions {
+    /// Whether `--stop-after` comments were already downloaded.
+    fn should_stop(&self, statistics: &Statistics) -> bool {
+        self.stop_after
+            .is_some_and(|stop_after| statistics.num_downloaded() >= stop_after)
+    }
+
     fn get_attribute_filters(&self) -> Vec<AttributeFilter> {
         let mut filters: Vec<AttributeFilter> = Vec::new();
 
@@ -689,14 +1455,14 @@ impl CommentDownloadOptions {
 
 fn download_comments(
     client: &Client,
-    source_identifier: SourceIdentifier,
-    mut writer: impl Write,
-    options: CommentDownloadOptions,
+    resolver: &Resolver,
+    sources: &[Source],
+    writer: &mut CommentsWriter<impl Write>,
+    options: &CommentDownloadOptions,
+    pool: &mut Pool,
 ) -> Result<()> {
-    let source = client
-        .get_source(source_identifier)
-        .context("Operation to get source has failed.")?;
     let statistics = Arc::new(Statistics::new());
+    let source_ids: Vec<_> = sources.iter().map(|source| source.id.clone()).collect();
 
     let make_progress = |dataset_name: Option<&DatasetFullName>| -> Result<Progress> {
         let comment_filter = CommentFilter {
@@ -704,13 +1470,14 @@ fn download_comments(
                 minimum: options.timerange.from,
                 maximum: options.timerange.to,
             }),
-            sources: vec![source.id.clone()],
+            sources: source_ids.clone(),
             reviewed: if options.reviewed_only {
                 Some(ReviewedFilterEnum::OnlyReviewed)
             } else {
                 None
             },
             user_properties: options.user_properties_filter.clone(),
+            thread_properties: options.thread_properties_filter.clone(),
             messages: options.messages_filter.clone(),
         };
 
@@ -727,13 +1494,22 @@ fn download_comments(
                 .context("Operation to get dataset comment count has failed..")?
                 .num_comments as u64
         } else {
-            *client
-                .get_source_statistics(
-                    &source.full_name(),
-                    &SourceStatisticsRequestParams { comment_filter },
-                )
-                .context("Operation to get source comment count has failed..")?
-                .num_comments as u64
+            let mut total_comments = 0;
+            for source in sources {
+                total_comments += *client
+                    .get_source_statistics(
+                        &source.full_name(),
+                        &SourceStatisticsRequestParams {
+                            comment_filter: CommentFilter {
+                                sources: vec![source.id.clone()],
+                                ..comment_filter.clone()
+                            },
+                        },
+                    )
+                    .context("Operation to get source comment count has failed..")?
+                    .num_comments as u64;
+            }
+            total_comments
         };
 
         Ok(get_comments_progress_bar(
@@ -749,8 +1525,8 @@ fn download_comments(
     };
 
     if let Some(dataset_identifier) = &options.dataset_identifier {
-        let dataset = client
-            .get_dataset(dataset_identifier.clone())
+        let dataset = resolver
+            .get_dataset(client, dataset_identifier.clone())
             .context("Operation to get dataset has failed.")?;
         let dataset_name = dataset.full_name();
         let _progress = if options.show_progress {
@@ -760,16 +1536,30 @@ fn download_comments(
         };
 
         if options.reviewed_only {
-            get_reviewed_comments_in_bulk(
+            for source in sources {
+                if options.should_stop(&statistics) {
+                    break;
+                }
+                get_reviewed_comments_in_bulk(
+                    client,
+                    &dataset_name,
+                    source,
+                    &statistics,
+                    writer,
+                    options,
+                    pool,
+                )?;
+            }
+        } else {
+            get_comments_from_uids(
                 client,
                 dataset_name,
-                source,
+                source_ids,
                 &statistics,
                 writer,
                 options,
+                pool,
             )?;
-        } else {
-            get_comments_from_uids(client, dataset_name, source, &statistics, writer, &options)?;
         }
     } else {
         let _progress = if options.show_progress {
@@ -777,29 +1567,28 @@ fn download_comments(
         } else {
             None
         };
-        for page in client.get_comments_iter(&source.full_name(), None, options.timerange) {
-            let page = page.context("Operation to get comments has failed.")?;
-
-            if options
-                .stop_after
-                .is_some_and(|stop_after| statistics.num_downloaded() >= stop_after)
+        'sources: for source in sources {
+            for page in client
+                .get_comments_iter(&source.full_name(), options.page_size, options.timerange)
+                .include_thread_properties(options.include_thread_properties)
             {
-                break;
-            }
+                let page = page.context("Operation to get comments has failed.")?;
 
-            statistics.add_comments(page.len());
+                if options.should_stop(&statistics) {
+                    break 'sources;
+                }
+
+                statistics.add_comments(page.len());
 
-            print_resources_as_json(
-                page.into_iter().map(|comment| AnnotatedComment {
+                writer.write(page.into_iter().map(|mut comment| AnnotatedComment {
+                    thread_properties: comment.thread_properties.take(),
                     comment,
                     labelling: None,
                     entities: None,
-                    thread_properties: None,
                     moon_forms: None,
                     label_properties: None,
-                }),
-                &mut writer,
-            )?;
+                }))?;
+            }
         }
     }
     log::info!(
@@ -816,10 +1605,11 @@ pub const DEFAULT_QUERY_PAGE_SIZE: usize = 512;
 fn get_comments_from_uids(
     client: &Client,
     dataset_name: DatasetFullName,
-    source: Source,
+    source_ids: Vec<SourceId>,
     statistics: &Arc<Statistics>,
-    mut writer: impl Write,
+    writer: &mut CommentsWriter<impl Write>,
     options: &CommentDownloadOptions,
+    pool: &mut Pool,
 ) -> Result<()> {
     let mut params = QueryRequestParams {
         attribute_filters: options.get_attribute_filters(),
@@ -831,7 +1621,8 @@ fn get_comments_from_uids(
                 maximum: options.timerange.to,
             }),
             user_properties: options.user_properties_filter.clone(),
-            sources: vec![source.id],
+            thread_properties: options.thread_properties_filter.clone(),
+            sources: source_ids,
             messages: options.messages_filter.clone(),
         },
         limit: Some(DEFAULT_QUERY_PAGE_SIZE),
@@ -845,19 +1636,19 @@ fn get_comments_from_uids(
     };
 
     for page in client.get_dataset_query_iter(&dataset_name, &mut params) {
-        let page = page.context("Operation to get comments has failed.")?;
+        let mut page = page.context("Operation to get comments has failed.")?;
         if page.is_empty() {
             return Ok(());
         }
 
-        if options
-            .stop_after
-            .is_some_and(|stop_after| statistics.num_downloaded() >= stop_after)
-        {
+        if options.should_stop(statistics) {
             break;
         }
 
-        statistics.add_comments(page.len());
+        // Label filters are applied locally, as the query endpoint only filters on label names
+        if !options.assigned_labels.is_empty() {
+            page.retain(|comment| has_any_assigned_label(comment, &options.assigned_labels));
+        }
 
         if let Some(model_version) = &options.model_version {
             let predictions = client
@@ -871,9 +1662,9 @@ fn get_comments_from_uids(
                 .context("Operation to get predictions has failed.")?;
             // since predict-comments endpoint doesn't return some fields,
             // they are set to None or [] here
-            let comments: Vec<_> = page
+            let mut comments: Vec<_> = page
                 .into_iter()
-                .zip(predictions.into_iter())
+                .zip(predictions)
                 .map(|(comment, prediction)| AnnotatedComment {
                     comment: comment.comment,
                     labelling: Some(vec![Labelling {
@@ -909,17 +1700,28 @@ fn get_comments_from_uids(
                 })
                 .collect();
 
-            if let Some(attachments_dir) = &options.attachments_dir {
-                comments.iter().try_for_each(|comment| -> Result<()> {
-                    download_comment_attachments(
-                        client,
-                        attachments_dir,
-                        &comment.comment,
-                        statistics,
+            if !options.predicted_labels.is_empty() {
+                comments.retain(|comment| {
+                    has_any_predicted_label(
+                        comment,
+                        &options.predicted_labels,
+                        options.prediction_threshold,
                     )
-                })?;
+                });
             }
-            print_resources_as_json(comments, &mut writer)?;
+            statistics.add_comments(comments.len());
+
+            if let Some(attachments_dir) = &options.attachments_dir {
+                download_comment_attachments(
+                    client,
+                    attachments_dir,
+                    comments.iter().map(|comment| &comment.comment),
+                    statistics,
+                    pool,
+                    options,
+                )?;
+            }
+            writer.write(comments)?;
         } else {
             let comments: Vec<_> = page
                 .into_iter()
@@ -933,81 +1735,131 @@ fn get_comments_from_uids(
                     annotated_comment
                 })
                 .collect();
+            statistics.add_comments(comments.len());
+
             if let Some(attachments_dir) = &options.attachments_dir {
-                comments.iter().try_for_each(|comment| -> Result<()> {
-                    download_comment_attachments(
-                        client,
-                        attachments_dir,
-                        &comment.comment,
-                        statistics,
-                    )
-                })?;
+                download_comment_attachments(
+                    client,
+                    attachments_dir,
+                    comments.iter().map(|comment| &comment.comment),
+                    statistics,
+                    pool,
+                    options,
+                )?;
             }
 
-            print_resources_as_json(comments, &mut writer)?;
+            writer.write(comments)?;
         }
     }
     Ok(())
 }
 
-fn download_comment_attachments(
+/// Download the attachments of the given comments in parallel, at most
+/// `options.attachment_concurrency` at a time. Each worker picks up the next attachment as soon as
+/// it is done with the previous one. Attachments which already exist locally or are larger than
+/// `options.max_attachment_size` are skipped.
+fn download_comment_attachments<'a>(
     client: &Client,
     attachments_dir: &Path,
-    comment: &Comment,
+    comments: impl Iterator<Item = &'a Comment>,
     statistics: &Arc<Statistics>,
+    pool: &mut Pool,
+    options: &CommentDownloadOptions,
 ) -> Result<()> {
-    comment
-        .attachments
-        .iter()
-        .enumerate()
-        .try_for_each(|(idx, attachment)| -> Result<()> {
-            if let Some(attachment_reference) = &attachment.attachment_reference {
-                let local_attachment = LocalAttachmentPath {
-                    index: idx,
-                    name: attachment.name.clone(),
-                    parent_dir: attachments_dir.join(&comment.id.0),
-                };
-
-                if !local_attachment.exists() {
-                    let attachment_buf = client.get_attachment(attachment_reference)?;
-
-                    if local_attachment.write(attachment_buf)? {
-                        statistics.add_attachments(1);
+    let deduplicator = options.attachment_deduplicator.as_ref();
+    let to_download: Vec<_> = comments
+        .flat_map(|comment| {
+            comment
+                .attachments
+                .iter()
+                .enumerate()
+                .filter_map(move |(idx, attachment)| {
+                    if options
+                        .max_attachment_size
+                        .is_some_and(|max_size| attachment.size > max_size)
+                    {
+                        return None;
+                    }
+                    let attachment_reference = attachment.attachment_reference.as_ref()?;
+                    let local_attachment = LocalAttachmentPath {
+                        index: idx,
+                        name: attachment.name.clone(),
+                        parent_dir: attachments_dir.join(&comment.id.0),
                     };
+                    (!local_attachment.exists()).then_some((local_attachment, attachment_reference))
+                })
+        })
+        .collect();
+
+    let next_index = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+
+    pool.scoped(|scope| {
+        for _ in 0..options.attachment_concurrency.min(to_download.len()) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            let to_download = &to_download;
+            scope.execute(move || {
+                while let Some((local_attachment, attachment_reference)) =
+                    to_download.get(next_index.fetch_add(1, Ordering::SeqCst))
+                {
+                    let result = local_attachment
+                        .download(client, attachment_reference, deduplicator)
+                        .map(|written| {
+                            if written {
+                                statistics.add_attachments(1);
+                            }
+                        });
+                    if result.is_err() {
+                        // Stop all workers from picking up more attachments
+                        next_index.store(to_download.len(), Ordering::SeqCst);
+                    }
+                    sender.send(result).expect("Could not send result");
                 }
-            }
-            Ok(())
-        })?;
+            });
+        }
+    });
+
+    drop(sender);
+    receiver.iter().collect::<Result<Vec<_>>>()?;
     Ok(())
 }
 
 fn get_reviewed_comments_in_bulk(
     client: &Client,
-    dataset_name: DatasetFullName,
-    source: Source,
+    dataset_name: &DatasetFullName,
+    source: &Source,
     statistics: &Arc<Statistics>,
-    mut writer: impl Write,
-    options: CommentDownloadOptions,
+    writer: &mut CommentsWriter<impl Write>,
+    options: &CommentDownloadOptions,
+    pool: &mut Pool,
 ) -> Result<()> {
     for page in
-        client.get_labellings_iter(&dataset_name, &source.id, options.include_predictions, None)
+        client.get_labellings_iter(dataset_name, &source.id, options.include_predictions, None)
     {
-        let page = page.context("Operation to get labellings has failed.")?;
+        let mut page = page.context("Operation to get labellings has failed.")?;
 
-        if options
-            .stop_after
-            .is_some_and(|stop_after| statistics.num_downloaded() >= stop_after)
-        {
+        if options.should_stop(statistics) {
             break;
         }
 
+        // The labellings endpoint does not support attribute filters
+        if !options.assigned_labels.is_empty() {
+            page.retain(|comment| has_any_assigned_label(comment, &options.assigned_labels));
+        }
+
         statistics.add_comments(page.len());
         statistics.add_annotated(page.len());
 
         if let Some(attachments_dir) = &options.attachments_dir {
-            page.iter().try_for_each(|comment| -> Result<()> {
-                download_comment_attachments(client, attachments_dir, &comment.comment, statistics)
-            })?;
+            download_comment_attachments(
+                client,
+                attachments_dir,
+                page.iter().map(|comment| &comment.comment),
+                statistics,
+                pool,
+                options,
+            )?;
         }
 
         let comments = page.into_iter().map(|comment| {
@@ -1018,11 +1870,32 @@ fn get_reviewed_comments_in_bulk(
             }
         });
 
-        print_resources_as_json(comments, &mut writer)?;
+        writer.write(comments)?;
     }
     Ok(())
 }
 
+fn has_any_assigned_label(comment: &AnnotatedComment, label_names: &[String]) -> bool {
+    comment.labelling.iter().flatten().any(|labelling| {
+        labelling
+            .assigned
+            .iter()
+            .any(|label| label_names.contains(&label.name.0))
+    })
+}
+
+fn has_any_predicted_label(
+    comment: &AnnotatedComment,
+    label_names: &[String],
+    threshold: f64,
+) -> bool {
+    comment.labelling.iter().flatten().any(|labelling| {
+        labelling.predicted.iter().flatten().any(|label| {
+            *label.probability >= threshold && label_names.contains(&label.name.to_label_name().0)
+        })
+    })
+}
+
 #[derive(Debug)]
 pub struct Statistics {
     downloaded: AtomicUsize,
@@ -1105,3 +1978,87 @@ fn get_comments_progress_bar(
         ProgressOptions { bytes_units: false },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_by() {
+        assert_eq!("day".parse::<SplitBy>().unwrap(), SplitBy::Day);
+        assert_eq!("count:100".parse::<SplitBy>().unwrap(), SplitBy::Count(100));
+        assert!("count:0".parse::<SplitBy>().is_err());
+        assert!("week".parse::<SplitBy>().is_err());
+
+        assert_eq!(
+            split_file_path(Path::new("out/comments.jsonl"), "2024-08-07"),
+            Path::new("out/comments-2024-08-07.jsonl")
+        );
+        assert_eq!(
+            split_file_path(Path::new("comments.jsonl.gz"), "00001"),
+            Path::new("comments-00001.jsonl.gz")
+        );
+        assert_eq!(
+            split_file_path(Path::new("comments"), "00001"),
+            Path::new("comments-00001")
+        );
+    }
+
+    #[test]
+    fn test_thread_properties_filter() {
+        let filters: Vec<ThreadPropertyFilterArg> = [
+            "first_sender=a@x.com",
+            "first_sender!=b@x.com",
+            "num_messages=1",
+            "queue=billing",
+        ]
+        .iter()
+        .map(|filter| filter.parse().unwrap())
+        .collect();
+        assert!("first_sender".parse::<ThreadPropertyFilterArg>().is_err());
+        assert!("=billing".parse::<ThreadPropertyFilterArg>().is_err());
+
+        let ThreadPropertiesFilter(filter) = get_thread_properties_filter(&filters);
+        assert_eq!(
+            filter["first_sender"].one_of,
+            vec![PropertyValue::String("a@x.com".to_owned())]
+        );
+        assert_eq!(
+            filter["first_sender"].not_one_of,
+            vec![PropertyValue::String("b@x.com".to_owned())]
+        );
+        assert_eq!(
+            filter["num_messages"].one_of,
+            vec![PropertyValue::Number(NotNan::new(1.0).unwrap())]
+        );
+        assert_eq!(
+            filter["queue"].one_of,
+            vec![PropertyValue::String("billing".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_check_thread_property_names() {
+        let summary: Summary = serde_json::from_value(serde_json::json!({
+            "user_properties": {"string": [], "number": []},
+            "thread_properties": {
+                "string": [{"full_name": "first_sender", "values": []}],
+                "number": [{"full_name": "num_messages"}]
+            }
+        }))
+        .unwrap();
+
+        let valid: Vec<ThreadPropertyFilterArg> = ["first_sender=a@x.com", "num_messages!=1"]
+            .iter()
+            .map(|filter| filter.parse().unwrap())
+            .collect();
+        assert!(check_thread_property_names(&valid, &summary).is_ok());
+
+        let invalid: Vec<ThreadPropertyFilterArg> = vec!["frist_sender=a@x.com".parse().unwrap()];
+        let error = check_thread_property_names(&invalid, &summary)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("frist_sender"));
+        assert!(error.contains("first_sender, num_messages"));
+    }
+}
@@ -3,14 +3,15 @@ use std::{
     collections::HashMap,
     fs, mem,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
 };
 
-use anyhow::{bail, Context, Result};
-use chrono::{DateTime, Duration, NaiveDate, Utc};
+use anyhow::{anyhow, bail, ensure, Context, Error, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
 use colored::Colorize;
 use csv::Writer;
 use dialoguer::{FuzzySelect, Input, MultiSelect};
@@ -42,7 +43,68 @@ const OUTPUT_FOLDER_PREFIX: &str = "LabelTrendReport";
 const MAX_COMMENT_SAMPLE: u64 = 150000;
 
 #[derive(Debug, StructOpt)]
-pub struct GetCustomLabelTrendReportArgs {}
+pub struct GetCustomLabelTrendReportArgs {
+    #[structopt(long = "from")]
+    /// Starting timestamp of the report (inclusive), e.g. 2024-01-01T00:00:00Z. Prompted for if
+    /// not given.
+    from_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "to")]
+    /// Ending timestamp of the report (inclusive). Prompted for if not given.
+    to_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "granularity", default_value = "day", possible_values = &["day", "week", "month"])]
+    /// Size of the time buckets in the report. Weeks start on Monday.
+    granularity: Granularity,
+
+    #[structopt(long = "output-dir", parse(from_os_str))]
+    /// Directory in which to write the report CSVs. Defaults to a new folder on the desktop.
+    output_dir: Option<PathBuf>,
+}
+
+/// Size of the time buckets which label counts are grouped into, one row per bucket.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl FromStr for Granularity {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            "month" => Ok(Granularity::Month),
+            _ => Err(anyhow!("unknown granularity: '{}'", string)),
+        }
+    }
+}
+
+impl Granularity {
+    /// The first date of the bucket containing `date`.
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday().into())
+            }
+            Granularity::Month => date.with_day(1).expect("every month has a first day"),
+        }
+    }
+
+    /// The first date of the bucket after the one starting at `bucket_start`.
+    fn next_bucket_start(self, bucket_start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => bucket_start + Duration::days(1),
+            Granularity::Week => bucket_start + Duration::weeks(1),
+            Granularity::Month => bucket_start + Months::new(1),
+        }
+    }
+}
 
 struct LabelTrendReportParams {
     pub start_timestamp: DateTime<Utc>,
@@ -256,9 +318,22 @@ pub fn get_comment_count(
 
 pub fn get(
     client: &Client,
-    _args: &GetCustomLabelTrendReportArgs,
+    args: &GetCustomLabelTrendReportArgs,
     _printer: &Printer,
 ) -> Result<()> {
+    let GetCustomLabelTrendReportArgs {
+        from_timestamp,
+        to_timestamp,
+        granularity,
+        output_dir,
+    } = args;
+    if let (Some(from_timestamp), Some(to_timestamp)) = (from_timestamp, to_timestamp) {
+        ensure!(
+            from_timestamp <= to_timestamp,
+            "--from must not be after --to"
+        );
+    }
+
     let dataset = get_dataset_selection(client)?;
 
     let (summary_response, labellers) = get_dataset_info(client, &dataset)?;
@@ -287,10 +362,18 @@ pub fn get(
     let user_property_filters =
         get_user_properties_filter_interactively(&summary_response.summary)?;
 
-    let start_timestamp =
-        get_timestamp("What date do you want to start your report from (YYYY-MM-DD)")?;
-    let end_timestamp =
-        get_timestamp("What date do you want to end your report from (YYYY-MM-DD)")?;
+    let start_timestamp = match from_timestamp {
+        Some(from_timestamp) => *from_timestamp,
+        None => get_timestamp("What date do you want to start your report from (YYYY-MM-DD)")?,
+    };
+    let end_timestamp = match to_timestamp {
+        Some(to_timestamp) => *to_timestamp,
+        None => get_timestamp("What date do you want to end your report from (YYYY-MM-DD)")?,
+    };
+    ensure!(
+        start_timestamp <= end_timestamp,
+        "The start of the report must not be after its end"
+    );
 
     let statistics = Arc::new(Statistics::new());
 
@@ -336,13 +419,22 @@ pub fn get(
         model_versions,
         &label_trend_report_params,
         target_comment_count as usize,
+        *granularity,
     )?;
 
     report.scale_sampled_results(report_multiply_ratio as usize);
 
-    let csv_paths = report.write_csvs_to_desktop()?;
+    let csv_paths = match output_dir {
+        Some(output_dir) => {
+            fs::create_dir_all(output_dir).with_context(|| {
+                format!("Could not create output directory {}", output_dir.display())
+            })?;
+            report.write_csvs(output_dir)?
+        }
+        None => report.write_csvs_to_desktop()?,
+    };
     info!(
-        "Saved CSV(s) to desktop:{PATH_PRINT_SEPERATOR}{}",
+        "Saved CSV(s):{PATH_PRINT_SEPERATOR}{}",
         csv_paths
             .iter()
             .map(|path| path.to_string_lossy().to_string())
@@ -477,6 +569,7 @@ fn get_label_trend_report(
     model_version_selections: Vec<ModelVersionSelection>,
     params: &LabelTrendReportParams,
     target_comment_count: usize,
+    granularity: Granularity,
 ) -> Result<Report> {
     let LabelTrendReportParams {
         start_timestamp,
@@ -484,7 +577,11 @@ fn get_label_trend_report(
         ..
     } = params;
 
-    let mut report: Report = Report::new(start_timestamp.date_naive(), end_timestamp.date_naive());
+    let mut report: Report = Report::new(
+        start_timestamp.date_naive(),
+        end_timestamp.date_naive(),
+        granularity,
+    );
 
     for model_version_selection in &model_version_selections {
         match model_version_selection {
@@ -605,6 +702,7 @@ pub struct Report {
     labels: Vec<LabelName>,
     start_date: NaiveDate,
     end_date: NaiveDate,
+    granularity: Granularity,
 }
 
 impl Report {
@@ -616,10 +714,11 @@ impl Report {
         });
     }
 
-    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Self {
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate, granularity: Granularity) -> Self {
         Self {
             start_date,
             end_date,
+            granularity,
             ..Default::default()
         }
     }
@@ -650,7 +749,11 @@ impl Report {
 
             let mut wtr = Writer::from_path(&path)?;
 
-            let date_range = DateRange(self.start_date, self.end_date);
+            let date_range = DateRange {
+                next: self.granularity.bucket_start(self.start_date),
+                end: self.end_date,
+                granularity: self.granularity,
+            };
 
             wtr.write_record(
                 vec!["date".to_string()]
@@ -682,8 +785,9 @@ impl Report {
             self.labels.push(label.clone())
         }
 
+        let bucket_start = self.granularity.bucket_start(date);
         self.get_model_version_entry_mut(model_version)
-            .get_date_entry_mut(date)
+            .get_date_entry_mut(bucket_start)
             .count_label(label);
     }
 
@@ -697,14 +801,19 @@ impl Report {
     }
 }
 
-struct DateRange(NaiveDate, NaiveDate);
+/// The start dates of the buckets from `next` up to and including the one containing `end`.
+struct DateRange {
+    next: NaiveDate,
+    end: NaiveDate,
+    granularity: Granularity,
+}
 
 impl Iterator for DateRange {
     type Item = NaiveDate;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.0 <= self.1 {
-            let next = self.0 + Duration::days(1);
-            Some(mem::replace(&mut self.0, next))
+        if self.next <= self.end {
+            let next = self.granularity.next_bucket_start(self.next);
+            Some(mem::replace(&mut self.next, next))
         } else {
             None
         }
@@ -761,3 +870,41 @@ fn get_progress_bar(total_bytes: u64, statistics: &Arc<Statistics>) -> Progress
         ProgressOptions { bytes_units: false },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(string: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(string, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_date_range_granularity() {
+        let range = |granularity| {
+            DateRange {
+                next: Granularity::bucket_start(granularity, date("2024-01-30")),
+                end: date("2024-03-02"),
+                granularity,
+            }
+            .map(|date| date.to_string())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(range(Granularity::Day).len(), 33);
+        assert_eq!(
+            range(Granularity::Week),
+            [
+                "2024-01-29",
+                "2024-02-05",
+                "2024-02-12",
+                "2024-02-19",
+                "2024-02-26"
+            ]
+        );
+        assert_eq!(
+            range(Granularity::Month),
+            ["2024-01-01", "2024-02-01", "2024-03-01"]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use prettytable::row;
+use reinfer_client::{Client, Dataset, DatasetIdentifier};
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+
+use crate::printer::{DisplayTable, Printer};
+use crate::utils::{NameArg, Resolver};
+
+/// Dataset fields which are expected to differ between any two datasets, or which are compared
+/// separately, definition by definition.
+const IGNORED_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "owner",
+    "created",
+    "last_modified",
+    "label_defs",
+    "entity_defs",
+    "general_fields",
+];
+
+#[derive(Debug, StructOpt)]
+pub struct GetDatasetDiffArgs {
+    #[structopt(long = "left")]
+    /// Name or id of the dataset to compare from
+    left: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "right")]
+    /// Name or id of the dataset to compare to
+    right: NameArg<DatasetIdentifier>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Field,
+    LabelDef,
+    EntityDef,
+    GeneralField,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DatasetDiffEntry {
+    kind: DiffKind,
+    name: String,
+    change: Change,
+    left: Option<Value>,
+    right: Option<Value>,
+}
+
+impl DisplayTable for DatasetDiffEntry {
+    fn to_table_headers() -> prettytable::Row {
+        row![bFg => "Kind", "Name", "Change", "Left", "Right"]
+    }
+
+    fn to_table_row(&self) -> prettytable::Row {
+        let kind = match self.kind {
+            DiffKind::Field => "field",
+            DiffKind::LabelDef => "label",
+            DiffKind::EntityDef => "entity",
+            DiffKind::GeneralField => "general field",
+        };
+        let change = match self.change {
+            Change::Added => "added".green(),
+            Change::Removed => "removed".red(),
+            Change::Changed => "changed".yellow(),
+        };
+        let summary = |value: &Option<Value>| match (self.kind, value) {
+            (_, None) => "".dimmed().to_string(),
+            (DiffKind::Field, Some(value)) => value.to_string(),
+            // Definitions are too large for a table, use `-o json` to see them.
+            (_, Some(_)) => "...".dimmed().to_string(),
+        };
+        row![
+            kind,
+            self.name,
+            change,
+            summary(&self.left),
+            summary(&self.right)
+        ]
+    }
+}
+
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetDatasetDiffArgs,
+    printer: &Printer,
+) -> Result<()> {
+    let GetDatasetDiffArgs { left, right } = args;
+
+    let left = resolver
+        .get_dataset(client, resolver.resolve(left)?)
+        .context("Operation to get left dataset has failed.")?;
+    let right = resolver
+        .get_dataset(client, resolver.resolve(right)?)
+        .context("Operation to get right dataset has failed.")?;
+
+    printer.print_resources(&diff_datasets(&left, &right)?)
+}
+
+fn diff_datasets(left: &Dataset, right: &Dataset) -> Result<Vec<DatasetDiffEntry>> {
+    let fields = |dataset: &Dataset| -> Result<BTreeMap<String, Value>> {
+        let Value::Object(fields) =
+            serde_json::to_value(dataset).context("Could not serialise dataset")?
+        else {
+            unreachable!("datasets serialise to objects")
+        };
+        Ok(fields
+            .into_iter()
+            .filter(|(key, _)| !IGNORED_FIELDS.contains(&key.as_str()))
+            .collect())
+    };
+
+    let mut entries = diff_maps(DiffKind::Field, fields(left)?, fields(right)?);
+    entries.extend(diff_maps(
+        DiffKind::LabelDef,
+        definitions(&left.label_defs, |def| def.name.0.clone())?,
+        definitions(&right.label_defs, |def| def.name.0.clone())?,
+    ));
+    entries.extend(diff_maps(
+        DiffKind::EntityDef,
+        definitions(&left.entity_defs, |def| def.name.0.clone())?,
+        definitions(&right.entity_defs, |def| def.name.0.clone())?,
+    ));
+    entries.extend(diff_maps(
+        DiffKind::GeneralField,
+        definitions(&left.general_fields, |def| def.api_name.clone())?,
+        definitions(&right.general_fields, |def| def.api_name.clone())?,
+    ));
+    Ok(entries)
+}
+
+/// Serialise definitions keyed by name. Ids are dropped, as they are specific to each dataset.
+fn definitions<Definition: Serialize>(
+    definitions: &[Definition],
+    name: impl Fn(&Definition) -> String,
+) -> Result<BTreeMap<String, Value>> {
+    definitions
+        .iter()
+        .map(|definition| {
+            let mut value =
+                serde_json::to_value(definition).context("Could not serialise definition")?;
+            if let Value::Object(map) = &mut value {
+                map.remove("id");
+            }
+            Ok((name(definition), value))
+        })
+        .collect()
+}
+
+fn diff_maps(
+    kind: DiffKind,
+    mut left: BTreeMap<String, Value>,
+    right: BTreeMap<String, Value>,
+) -> Vec<DatasetDiffEntry> {
+    let mut entries = Vec::new();
+    for (name, right_value) in right {
+        match left.remove(&name) {
+            None => entries.push(DatasetDiffEntry {
+                kind,
+                name,
+                change: Change::Added,
+                left: None,
+                right: Some(right_value),
+            }),
+            Some(left_value) if left_value != right_value => entries.push(DatasetDiffEntry {
+                kind,
+                name,
+                change: Change::Changed,
+                left: Some(left_value),
+                right: Some(right_value),
+            }),
+            Some(_) => {}
+        }
+    }
+    entries.extend(left.into_iter().map(|(name, left_value)| DatasetDiffEntry {
+        kind,
+        name,
+        change: Change::Removed,
+        left: Some(left_value),
+        right: None,
+    }));
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_maps() {
+        let left = BTreeMap::from([
+            ("a".to_owned(), json!(1)),
+            ("b".to_owned(), json!(2)),
+            ("c".to_owned(), json!(3)),
+        ]);
+        let right = BTreeMap::from([
+            ("b".to_owned(), json!(2)),
+            ("c".to_owned(), json!(4)),
+            ("d".to_owned(), json!(5)),
+        ]);
+
+        let changes: Vec<_> = diff_maps(DiffKind::LabelDef, left, right)
+            .into_iter()
+            .map(|entry| (entry.name, entry.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("a".to_owned(), Change::Removed),
+                ("c".to_owned(), Change::Changed),
+                ("d".to_owned(), Change::Added),
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use log::info;
+use reinfer_client::{
+    resources::dataset::QueryRequestParams, Client, DatasetFullName, DatasetIdentifier,
+};
+use structopt::StructOpt;
+
+use crate::utils::{NameArg, Resolver};
+use crate::{printer::print_resources_as_json, utils};
+
+#[derive(Debug, StructOpt)]
+pub struct GetDatasetQueryArgs {
+    #[structopt(name = "dataset")]
+    /// Dataset name or id
+    dataset: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "params", parse(from_os_str))]
+    /// Path to a json file with the query to run, with the same fields as the body of a dataset
+    /// query request (`filter`, `attribute_filters`, `order`, `limit`, ...)
+    params_path: PathBuf,
+
+    #[structopt(short = "f", long = "file", parse(from_os_str))]
+    /// Path where to write the resulting comments as jsonl. If not specified, stdout will be
+    /// used. Paths ending in `.gz` are gzip compressed.
+    path: Option<PathBuf>,
+
+    #[structopt(long = "max-results")]
+    /// Stop after this many comments
+    max_results: Option<usize>,
+}
+
+pub fn get(client: &Client, resolver: &Resolver, args: &GetDatasetQueryArgs) -> Result<()> {
+    let GetDatasetQueryArgs {
+        dataset,
+        params_path,
+        path,
+        max_results,
+    } = args;
+
+    let mut params: QueryRequestParams =
+        serde_json::from_reader(utils::open_input_file(params_path)?).with_context(|| {
+            format!(
+                "Could not parse query params from `{}`",
+                params_path.display()
+            )
+        })?;
+
+    let dataset_name = resolver
+        .get_dataset(client, resolver.resolve(dataset)?)
+        .context("Operation to get dataset has failed.")?
+        .full_name();
+
+    let num_results = match path {
+        Some(path) => {
+            let mut file = utils::create_output_file(path)?;
+            let num_results =
+                write_query_results(client, &dataset_name, &mut params, *max_results, &mut file)?;
+            file.finish().with_context(|| {
+                format!("Could not write query results to `{}`", path.display())
+            })?;
+            num_results
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            let num_results = write_query_results(
+                client,
+                &dataset_name,
+                &mut params,
+                *max_results,
+                &mut stdout,
+            )?;
+            stdout.flush().context("Could not write query results")?;
+            num_results
+        }
+    };
+    info!("Successfully queried {} comments", num_results);
+    Ok(())
+}
+
+fn write_query_results(
+    client: &Client,
+    dataset_name: &DatasetFullName,
+    params: &mut QueryRequestParams,
+    max_results: Option<usize>,
+    mut writer: impl Write,
+) -> Result<usize> {
+    let mut num_results = 0;
+    for page in client.get_dataset_query_iter(dataset_name, params) {
+        let mut page = page.context("Operation to query dataset has failed.")?;
+        if let Some(max_results) = max_results {
+            page.truncate(max_results - num_results);
+        }
+        num_results += page.len();
+        print_resources_as_json(&page, &mut writer)?;
+        if max_results.is_some_and(|max_results| num_results >= max_results) {
+            break;
+        }
+    }
+    Ok(num_results)
+}
@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reinfer_client::{
+    resources::{comment::CommentTimestampFilter, dataset::StatisticsRequestParams},
+    Client, CommentFilter, DatasetIdentifier,
+};
+use structopt::StructOpt;
+
+use super::comments::{attachment_types_filter, with_attachments_filter, StructExt};
+use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
+
+#[derive(Debug, StructOpt)]
+pub struct GetDatasetStatisticsArgs {
+    #[structopt(name = "dataset")]
+    /// Dataset name or id
+    dataset: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "filter")]
+    /// Comment filter to apply as a json string, e.g. `{"reviewed": "only_reviewed"}`
+    filter: Option<StructExt<CommentFilter>>,
+
+    #[structopt(long = "from-timestamp")]
+    /// Only count comments at or after this timestamp. Overrides the timestamp in --filter.
+    from_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "to-timestamp")]
+    /// Only count comments at or before this timestamp. Overrides the timestamp in --filter.
+    to_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "attachment-types")]
+    /// Only count comments with attachments of these types
+    attachment_type_filters: Vec<String>,
+
+    #[structopt(long = "only-with-attachments")]
+    /// Only count comments with at least one attachment
+    only_with_attachments: bool,
+}
+
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetDatasetStatisticsArgs,
+    printer: &Printer,
+) -> Result<()> {
+    let GetDatasetStatisticsArgs {
+        dataset,
+        filter,
+        from_timestamp,
+        to_timestamp,
+        attachment_type_filters,
+        only_with_attachments,
+    } = args;
+
+    let dataset_name = resolver
+        .get_dataset(client, resolver.resolve(dataset)?)
+        .context("Operation to get dataset has failed.")?
+        .full_name();
+
+    let comment_filter = get_comment_filter(filter.as_ref(), *from_timestamp, *to_timestamp);
+
+    let mut attribute_filters = Vec::new();
+    if !attachment_type_filters.is_empty() {
+        attribute_filters.push(attachment_types_filter(attachment_type_filters));
+    }
+    if *only_with_attachments {
+        attribute_filters.push(with_attachments_filter());
+    }
+
+    let statistics = client
+        .get_dataset_statistics(
+            &dataset_name,
+            &StatisticsRequestParams {
+                attribute_filters,
+                comment_filter,
+                ..Default::default()
+            },
+        )
+        .context("Operation to get dataset statistics has failed.")?;
+
+    printer.print_resources(&[statistics])
+}
+
+/// The comment filter given as json, with its timestamp range overridden by the given timestamps.
+pub(super) fn get_comment_filter(
+    filter: Option<&StructExt<CommentFilter>>,
+    from_timestamp: Option<DateTime<Utc>>,
+    to_timestamp: Option<DateTime<Utc>>,
+) -> CommentFilter {
+    let mut comment_filter = filter.map(|filter| filter.0.clone()).unwrap_or_default();
+    if from_timestamp.is_some() || to_timestamp.is_some() {
+        let timestamp = comment_filter
+            .timestamp
+            .get_or_insert(CommentTimestampFilter {
+                minimum: None,
+                maximum: None,
+            });
+        timestamp.minimum = from_timestamp.or(timestamp.minimum);
+        timestamp.maximum = to_timestamp.or(timestamp.maximum);
+    }
+    comment_filter
+}
@@ -10,12 +10,13 @@ use scoped_threadpool::Pool;
 use structopt::StructOpt;
 
 use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
 
 #[derive(Debug, StructOpt)]
 pub struct GetDatasetsArgs {
     #[structopt(name = "dataset")]
     /// If specified, only list this dataset (name or id)
-    dataset: Option<DatasetIdentifier>,
+    dataset: Option<NameArg<DatasetIdentifier>>,
 
     #[structopt(long = "stats")]
     /// Whether to include dataset statistics in response
@@ -23,11 +24,12 @@ pub struct GetDatasetsArgs {
 
     #[structopt(long = "source")]
     /// If specified, only list this datasets containing this source (name or id)
-    source_identifier: Option<SourceIdentifier>,
+    source_identifier: Option<NameArg<SourceIdentifier>>,
 }
 
 pub fn get(
     client: &Client,
+    resolver: &Resolver,
     args: &GetDatasetsArgs,
     printer: &Printer,
     pool: &mut Pool,
@@ -38,8 +40,8 @@ pub fn get(
         source_identifier,
     } = args;
     let mut datasets = if let Some(dataset) = dataset {
-        vec![client
-            .get_dataset(dataset.clone())
+        vec![resolver
+            .get_dataset(client, resolver.resolve(dataset)?)
             .context("Operation to list datasets has failed.")?]
     } else {
         let mut datasets = client
@@ -52,7 +54,7 @@ pub fn get(
     };
 
     if let Some(source_id) = source_identifier {
-        let source = client.get_source(source_id.clone())?;
+        let source = resolver.get_source(client, resolver.resolve(source_id)?)?;
 
         datasets.retain(|d| d.source_ids.contains(&source.id));
     }
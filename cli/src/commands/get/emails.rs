@@ -1,11 +1,15 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 
 use colored::Colorize;
-use reinfer_client::{resources::bucket_statistics::Count, BucketIdentifier, Client, EmailId};
+use mailparse::{MailAddr, MailHeaderMap};
+use reinfer_client::{
+    resources::{bucket_statistics::Count, email::Email},
+    BucketFullName, BucketIdentifier, Client, EmailId, EmailsIter,
+};
+use serde::Serialize;
 use std::{
-    fs::File,
-    io::{self, BufWriter, Write},
-    path::PathBuf,
+    io::{self, Write},
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -14,49 +18,252 @@ use std::{
 use structopt::StructOpt;
 
 use crate::{
+    commands::{get::comments::CommentsFormat, LocalAttachmentPath},
     printer::print_resources_as_json,
     progress::{Options as ProgressOptions, Progress},
+    utils::{self, NameArg, Resolver},
 };
 
+#[derive(Debug, StructOpt)]
+pub struct GetSingleEmailArgs {
+    #[structopt(long = "bucket")]
+    /// Bucket name or id
+    bucket: NameArg<BucketIdentifier>,
+
+    #[structopt(long = "id")]
+    /// Email id
+    id: EmailId,
+
+    #[structopt(short = "f", long = "file", parse(from_os_str))]
+    /// Path where to write the email as JSON. If not specified, stdout will be used.
+    path: Option<PathBuf>,
+
+    #[structopt(long = "attachments", parse(from_os_str))]
+    /// Directory where to save the content of the email's attachments, in a subdirectory named
+    /// after the email id
+    attachments_dir: Option<PathBuf>,
+}
+
 #[derive(Debug, StructOpt)]
 pub struct GetManyEmailsArgs {
     #[structopt(name = "bucket")]
     /// Bucket name or id
-    bucket: BucketIdentifier,
+    bucket: NameArg<BucketIdentifier>,
 
     #[structopt(short = "f", long = "file", parse(from_os_str))]
-    /// Path where to write comments as JSON. If not specified, stdout will be used.
+    /// Path where to write emails. If not specified, stdout will be used. Paths ending in `.gz`
+    /// are gzip compressed.
     path: Option<PathBuf>,
 
     #[structopt(name = "id")]
     /// Id of specific email to return
     id: Option<EmailId>,
+
+    #[structopt(long = "max-items")]
+    /// Stop after writing this many emails
+    max_items: Option<usize>,
+
+    #[structopt(long = "format", default_value = "jsonl")]
+    /// Format to write emails in. One of: jsonl, csv. In csv format, the sender, recipients and
+    /// subject are read from the MIME headers, with multiple recipients joined with `; `.
+    format: CommentsFormat,
+
+    #[structopt(long = "page-size")]
+    /// Number of emails to request per page, at most 256. Defaults to 64.
+    page_size: Option<usize>,
+}
+
+pub fn get_single(client: &Client, resolver: &Resolver, args: &GetSingleEmailArgs) -> Result<()> {
+    let GetSingleEmailArgs {
+        bucket,
+        id,
+        path,
+        attachments_dir,
+    } = args;
+
+    let bucket = client
+        .get_bucket(resolver.resolve(bucket)?)
+        .context("Operation to get bucket has failed.")?;
+    let emails = client
+        .get_email(&bucket.full_name(), id.clone())
+        .context("Operation to get email has failed.")?;
+    if emails.is_empty() {
+        return Err(anyhow!(
+            "No email with id `{}` in bucket {}",
+            id.0,
+            bucket.full_name().0
+        ));
+    }
+
+    if let Some(attachments_dir) = attachments_dir {
+        for email in &emails {
+            download_email_attachments(client, attachments_dir, email)?;
+        }
+    }
+
+    match path {
+        Some(path) => print_resources_as_json(emails, utils::create_output_file(path)?),
+        None => print_resources_as_json(emails, io::stdout().lock()),
+    }
+}
+
+fn download_email_attachments(
+    client: &Client,
+    attachments_dir: &Path,
+    email: &Email,
+) -> Result<()> {
+    for (index, attachment) in email.attachments.iter().enumerate() {
+        let Some(attachment_reference) = &attachment.attachment_reference else {
+            log::warn!(
+                "Attachment `{}` of email `{}` has no content to download",
+                attachment.name,
+                email.id.0
+            );
+            continue;
+        };
+        let local_attachment = LocalAttachmentPath {
+            index,
+            name: attachment.name.clone(),
+            parent_dir: attachments_dir.join(&email.id.0),
+        };
+        local_attachment.download(client, attachment_reference, None)?;
+    }
+    Ok(())
 }
 
-pub fn get_many(client: &Client, args: &GetManyEmailsArgs) -> Result<()> {
-    let GetManyEmailsArgs { bucket, path, id } = args;
+pub fn get_many(client: &Client, resolver: &Resolver, args: &GetManyEmailsArgs) -> Result<()> {
+    let GetManyEmailsArgs {
+        bucket,
+        path,
+        id,
+        max_items,
+        format,
+        page_size,
+    } = args;
 
     let file = match path {
-        Some(path) => Some(
-            File::create(path)
-                .with_context(|| format!("Could not open file for writing `{}`", path.display()))
-                .map(BufWriter::new)?,
-        ),
+        Some(path) => Some(utils::create_output_file(path)?),
         None => None,
     };
 
     if let Some(id) = id {
         if let Some(file) = file {
-            return download_email(client, bucket.clone(), id.clone(), file);
+            return download_email(
+                client,
+                resolver.resolve(bucket)?,
+                id.clone(),
+                &mut EmailsWriter::new(file, *format),
+            );
         } else {
-            return download_email(client, bucket.clone(), id.clone(), io::stdout().lock());
+            return download_email(
+                client,
+                resolver.resolve(bucket)?,
+                id.clone(),
+                &mut EmailsWriter::new(io::stdout().lock(), *format),
+            );
         }
     }
 
     if let Some(file) = file {
-        download_emails(client, bucket.clone(), file)
+        download_emails(
+            client,
+            resolver.resolve(bucket)?,
+            &mut EmailsWriter::new(file, *format),
+            *max_items,
+            *page_size,
+        )
     } else {
-        download_emails(client, bucket.clone(), io::stdout().lock())
+        download_emails(
+            client,
+            resolver.resolve(bucket)?,
+            &mut EmailsWriter::new(io::stdout().lock(), *format),
+            *max_items,
+            *page_size,
+        )
+    }
+}
+
+/// Writes pages of emails in the requested format.
+#[allow(clippy::large_enum_variant)]
+enum EmailsWriter<W: Write> {
+    Jsonl(W),
+    Csv(csv::Writer<W>),
+}
+
+impl<W: Write> EmailsWriter<W> {
+    fn new(writer: W, format: CommentsFormat) -> Self {
+        match format {
+            CommentsFormat::Jsonl => Self::Jsonl(writer),
+            CommentsFormat::Csv => Self::Csv(csv::Writer::from_writer(writer)),
+        }
+    }
+
+    fn write(&mut self, bucket_name: &BucketFullName, emails: Vec<Email>) -> Result<()> {
+        match self {
+            Self::Jsonl(writer) => print_resources_as_json(emails, writer),
+            Self::Csv(writer) => {
+                for email in &emails {
+                    writer
+                        .serialize(EmailCsvRecord::new(bucket_name, email))
+                        .context("Could not write email as csv")?;
+                }
+                writer.flush().context("Could not write emails as csv")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct EmailCsvRecord {
+    id: String,
+    bucket: String,
+    mailbox: String,
+    timestamp: String,
+    from: String,
+    to: String,
+    cc: String,
+    subject: String,
+    num_attachments: usize,
+}
+
+impl EmailCsvRecord {
+    fn new(bucket_name: &BucketFullName, email: &Email) -> Self {
+        let headers = mailparse::parse_headers(email.mime_content.0.as_bytes())
+            .map(|(headers, _)| headers)
+            .unwrap_or_default();
+
+        let addresses = |name: &str| -> String {
+            let Some(header) = headers.get_first_header(name) else {
+                return String::new();
+            };
+            match mailparse::addrparse_header(header) {
+                Ok(addresses) => addresses
+                    .iter()
+                    .flat_map(|address| match address {
+                        MailAddr::Single(single) => vec![single.addr.clone()],
+                        MailAddr::Group(group) => group
+                            .addrs
+                            .iter()
+                            .map(|single| single.addr.clone())
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                Err(_) => header.get_value(),
+            }
+        };
+
+        Self {
+            id: email.id.0.clone(),
+            bucket: bucket_name.0.clone(),
+            mailbox: email.mailbox.0.clone(),
+            timestamp: email.timestamp.to_rfc3339(),
+            from: addresses("From"),
+            to: addresses("To"),
+            cc: addresses("Cc"),
+            subject: headers.get_first_value("Subject").unwrap_or_default(),
+            num_attachments: email.attachments.len(),
+        }
     }
 }
 
@@ -64,7 +271,7 @@ fn download_email(
     client: &Client,
     bucket_identifier: BucketIdentifier,
     id: EmailId,
-    mut writer: impl Write,
+    writer: &mut EmailsWriter<impl Write>,
 ) -> Result<()> {
     let bucket = client
         .get_bucket(bucket_identifier)
@@ -72,13 +279,15 @@ fn download_email(
 
     let response = client.get_email(&bucket.full_name(), id)?;
 
-    print_resources_as_json(response, &mut writer)
+    writer.write(&bucket.full_name(), response)
 }
 
 fn download_emails(
     client: &Client,
     bucket_identifier: BucketIdentifier,
-    mut writer: impl Write,
+    writer: &mut EmailsWriter<impl Write>,
+    max_items: Option<usize>,
+    page_size: Option<usize>,
 ) -> Result<()> {
     let bucket = client
         .get_bucket(bucket_identifier)
@@ -94,16 +303,35 @@ fn download_emails(
         Count::LowerBoundBucketCount { value } => value,
         Count::ExactBucketCount { value } => value,
     } as u64;
+    let progress_bytes = match max_items {
+        Some(max_items) => progress_bytes.min(max_items as u64),
+        None => progress_bytes,
+    };
 
     let _progress = get_emails_progress_bar(progress_bytes, &statistics);
 
-    client
-        .get_emails_iter(&bucket.full_name(), None)
-        .try_for_each(|page| {
-            let page = page.context("Operation to get emails has failed.")?;
-            statistics.add_emails(page.len());
-            print_resources_as_json(page.into_iter(), &mut writer)
-        })?;
+    let page_size = utils::clamp_page_size(page_size, EmailsIter::MAX_PAGE_SIZE);
+    for page in client.get_emails_iter(&bucket.full_name(), page_size) {
+        let mut page = page.context("Operation to get emails has failed.")?;
+        // Only stop once there are more emails than the limit, so that downloading exactly
+        // `--max-items` emails isn't reported as cut short
+        let limit_exceeded = max_items.is_some_and(|max_items| {
+            let remaining = max_items.saturating_sub(statistics.num_downloaded());
+            let limit_exceeded = page.len() > remaining;
+            page.truncate(remaining);
+            limit_exceeded
+        });
+        statistics.add_emails(page.len());
+        writer.write(&bucket.full_name(), page)?;
+
+        if limit_exceeded {
+            log::info!(
+                "Stopped after {} emails as the `--max-items` limit was reached.",
+                statistics.num_downloaded()
+            );
+            break;
+        }
+    }
     log::info!(
         "Successfully downloaded {} emails.",
         statistics.num_downloaded(),
@@ -152,3 +380,48 @@ fn get_emails_progress_bar(total_bytes: u64, statistics: &Arc<Statistics>) -> Pr
         ProgressOptions { bytes_units: false },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use reinfer_client::resources::email::{Mailbox, MimeContent};
+
+    #[test]
+    fn test_email_csv_record() {
+        let email = Email {
+            id: EmailId("0123".to_owned()),
+            mailbox: Mailbox("inbox@x.com".to_owned()),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            mime_content: MimeContent(
+                concat!(
+                    "From: Alice <alice@x.com>\r\n",
+                    "To: bob@x.com, Carol <carol@x.com>\r\n",
+                    "Subject: Invoice\r\n",
+                    "\r\n",
+                    "Hello",
+                )
+                .to_owned(),
+            ),
+            metadata: None,
+            attachments: Vec::new(),
+            created_at: None,
+            updated_at: None,
+        };
+
+        assert_eq!(
+            EmailCsvRecord::new(&BucketFullName("project/bucket".to_owned()), &email),
+            EmailCsvRecord {
+                id: "0123".to_owned(),
+                bucket: "project/bucket".to_owned(),
+                mailbox: "inbox@x.com".to_owned(),
+                timestamp: "2024-01-01T00:00:00+00:00".to_owned(),
+                from: "alice@x.com".to_owned(),
+                to: "bob@x.com; carol@x.com".to_owned(),
+                cc: String::new(),
+                subject: "Invoice".to_owned(),
+                num_attachments: 0,
+            }
+        );
+    }
+}
@@ -1,11 +1,18 @@
-use std::{fs::File, io::BufWriter, path::PathBuf};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
 
 use anyhow::{Context, Result};
 use reinfer_client::{resources::integration::Integration, Client, IntegrationFullName};
+use serde_json::Value;
 use structopt::StructOpt;
 
 use crate::printer::{print_resources_as_json, Printer};
 
+const MASKED: &str = "********";
+
 #[derive(Debug, StructOpt)]
 pub struct GetIntegrationsArgs {
     #[structopt(name = "name")]
@@ -15,17 +22,48 @@ pub struct GetIntegrationsArgs {
     #[structopt(short = "f", long = "file", parse(from_os_str))]
     /// Path where to write integrations as JSON. If not specified, stdout will be used.
     path: Option<PathBuf>,
+
+    #[structopt(long = "detail")]
+    /// Print the complete integration objects as pretty JSON, with connection details masked
+    detail: bool,
+
+    #[structopt(long = "show-secrets", requires = "detail")]
+    /// Don't mask connection details when using --detail
+    show_secrets: bool,
 }
 
 pub fn get(client: &Client, args: &GetIntegrationsArgs, printer: &Printer) -> Result<()> {
-    let GetIntegrationsArgs { name, path } = args;
+    let GetIntegrationsArgs {
+        name,
+        path,
+        detail,
+        show_secrets,
+    } = args;
 
     let integrations: Vec<Integration>;
 
     if let Some(name) = name {
-        integrations = vec![client.get_integration(name)?];
+        integrations = vec![client
+            .get_integration(name)
+            .context("Operation to get integration has failed.")?];
     } else {
-        integrations = client.get_integrations()?;
+        integrations = client
+            .get_integrations()
+            .context("Operation to list integrations has failed.")?;
+    }
+
+    if *detail {
+        return match path {
+            Some(path) => {
+                let file = File::create(path)
+                    .with_context(|| {
+                        format!("Could not open file for writing `{}`", path.display())
+                    })
+                    .map(BufWriter::new)?;
+                print_integration_details(integrations, *show_secrets, file)
+            }
+            None => print_integration_details(integrations, *show_secrets, io::stdout().lock()),
+        };
     }
 
     match path {
@@ -39,3 +77,59 @@ pub fn get(client: &Client, args: &GetIntegrationsArgs, printer: &Printer) -> Re
         None => printer.print_resources(&integrations),
     }
 }
+
+fn print_integration_details(
+    integrations: Vec<Integration>,
+    show_secrets: bool,
+    mut writer: impl Write,
+) -> Result<()> {
+    for integration in integrations {
+        let mut value =
+            serde_json::to_value(integration).context("Could not serialise integration.")?;
+        if !show_secrets {
+            if let Some(connection) = value.pointer_mut("/configuration/connection") {
+                mask_strings(connection);
+            }
+        }
+        serde_json::to_writer_pretty(&mut writer, &value)
+            .context("Could not serialise integration.")?;
+        writeln!(writer).context("Failed to write integration to writer.")?;
+    }
+    Ok(())
+}
+
+/// Replace every string in the value with a mask, except for `kind` tags which only describe the
+/// type of connection.
+fn mask_strings(value: &mut Value) {
+    match value {
+        Value::String(string) => *string = MASKED.to_owned(),
+        Value::Object(map) => map
+            .iter_mut()
+            .filter(|(key, _)| key.as_str() != "kind")
+            .for_each(|(_, value)| mask_strings(value)),
+        Value::Array(values) => values.iter_mut().for_each(mask_strings),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask_strings() {
+        let mut connection = json!({
+            "access": {"kind": "user_access", "username": "robot@example.com"},
+            "application": {"kind": "custom", "client_id": "abc", "tenant_id": "def"},
+        });
+        mask_strings(&mut connection);
+        assert_eq!(
+            connection,
+            json!({
+                "access": {"kind": "user_access", "username": MASKED},
+                "application": {"kind": "custom", "client_id": MASKED, "tenant_id": MASKED},
+            })
+        );
+    }
+}
@@ -2,19 +2,27 @@ use anyhow::Result;
 use reinfer_client::{BucketIdentifier, Client};
 use structopt::StructOpt;
 
-use crate::printer::Printer;
+use crate::{
+    printer::Printer,
+    utils::{NameArg, Resolver},
+};
 
 #[derive(Debug, StructOpt)]
 pub struct GetKeyedSyncStatesArgs {
     #[structopt(name = "bucket")]
     /// The bucket to get keyed sync states for
-    bucket: BucketIdentifier,
+    bucket: NameArg<BucketIdentifier>,
 }
 
-pub fn get(client: &Client, args: &GetKeyedSyncStatesArgs, printer: &Printer) -> Result<()> {
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetKeyedSyncStatesArgs,
+    printer: &Printer,
+) -> Result<()> {
     let GetKeyedSyncStatesArgs { bucket } = args;
 
-    let bucket = client.get_bucket(bucket.clone())?;
+    let bucket = client.get_bucket(resolver.resolve(bucket)?)?;
 
     let keyed_sync_states = client.get_keyed_sync_states(&bucket.id)?;
 
@@ -2,15 +2,21 @@ mod audit_events;
 mod buckets;
 mod comments;
 mod custom_label_trend_report;
+mod dataset_diff;
+mod dataset_query;
+mod dataset_statistics;
 mod datasets;
 mod emails;
 mod integrations;
 mod keyed_sync_states;
+mod predictions;
 mod projects;
 mod quota;
+mod source_statistics;
 mod sources;
 mod streams;
 mod users;
+mod validation;
 
 use anyhow::Result;
 use custom_label_trend_report::GetCustomLabelTrendReportArgs;
@@ -22,16 +28,23 @@ use self::{
     audit_events::GetAuditEventsArgs,
     buckets::GetBucketsArgs,
     comments::{GetManyCommentsArgs, GetSingleCommentArgs},
+    dataset_diff::GetDatasetDiffArgs,
+    dataset_query::GetDatasetQueryArgs,
+    dataset_statistics::GetDatasetStatisticsArgs,
     datasets::GetDatasetsArgs,
-    emails::GetManyEmailsArgs,
+    emails::{GetManyEmailsArgs, GetSingleEmailArgs},
     integrations::GetIntegrationsArgs,
     keyed_sync_states::GetKeyedSyncStatesArgs,
+    predictions::GetPredictionsArgs,
     projects::GetProjectsArgs,
+    quota::GetQuotaArgs,
+    source_statistics::GetSourceStatisticsArgs,
     sources::GetSourcesArgs,
     streams::{GetStreamCommentsArgs, GetStreamStatsArgs, GetStreamsArgs},
     users::GetUsersArgs,
+    validation::{GetLabelValidationArgs, GetValidationArgs},
 };
-use crate::printer::Printer;
+use crate::{printer::Printer, utils::Resolver};
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, StructOpt)]
@@ -40,6 +53,10 @@ pub enum GetArgs {
     /// List the available buckets
     Buckets(GetBucketsArgs),
 
+    #[structopt(name = "email")]
+    /// Get a single email from a bucket
+    Email(GetSingleEmailArgs),
+
     #[structopt(name = "emails")]
     /// Download all emails from a source
     Emails(GetManyEmailsArgs),
@@ -56,6 +73,22 @@ pub enum GetArgs {
     /// List the available datasets
     Datasets(GetDatasetsArgs),
 
+    #[structopt(name = "dataset-diff")]
+    /// Compare the label, entity and general field definitions of two datasets
+    DatasetDiff(GetDatasetDiffArgs),
+
+    #[structopt(name = "dataset-query")]
+    /// Run a dataset query given as json and download the matching comments
+    DatasetQuery(GetDatasetQueryArgs),
+
+    #[structopt(name = "dataset-statistics")]
+    /// Get the number of comments and label counts of a dataset, optionally for a filtered slice
+    DatasetStatistics(GetDatasetStatisticsArgs),
+
+    #[structopt(name = "predictions")]
+    /// Get the predictions of a model version for a list of comments
+    Predictions(GetPredictionsArgs),
+
     #[structopt(name = "projects")]
     /// List the available projects
     Projects(GetProjectsArgs),
@@ -64,6 +97,10 @@ pub enum GetArgs {
     /// List the available sources
     Sources(GetSourcesArgs),
 
+    #[structopt(name = "source-statistics")]
+    /// Get the number of comments in a source, optionally for a filtered slice
+    SourceStatistics(GetSourceStatisticsArgs),
+
     #[structopt(name = "streams")]
     /// List the available streams for a dataset
     Streams(GetStreamsArgs),
@@ -80,13 +117,21 @@ pub enum GetArgs {
     /// List the available users
     Users(GetUsersArgs),
 
+    #[structopt(name = "validation")]
+    /// Get the validation summary of a model version of a dataset
+    Validation(GetValidationArgs),
+
+    #[structopt(name = "label-validation")]
+    /// Get the precision and recall of a label at each threshold, for a model version
+    LabelValidation(GetLabelValidationArgs),
+
     #[structopt(name = "current-user")]
     /// Get the user associated with the API token in use
     CurrentUser,
 
     #[structopt(name = "quotas")]
-    /// List all quotas for current tenant
-    Quotas,
+    /// List all quotas for current tenant, or for the given tenants
+    Quotas(GetQuotaArgs),
 
     #[structopt(name = "audit-events")]
     /// Get audit events for current tenant
@@ -105,24 +150,42 @@ pub enum GetArgs {
     CustomDatasetReport(GetCustomLabelTrendReportArgs),
 }
 
-pub fn run(args: &GetArgs, client: Client, printer: &Printer, pool: &mut Pool) -> Result<()> {
+pub fn run(
+    args: &GetArgs,
+    client: Client,
+    resolver: &Resolver,
+    printer: &Printer,
+    pool: &mut Pool,
+) -> Result<()> {
     match args {
-        GetArgs::Buckets(args) => buckets::get(&client, args, printer),
-        GetArgs::Emails(args) => emails::get_many(&client, args),
-        GetArgs::Comment(args) => comments::get_single(&client, args),
-        GetArgs::Comments(args) => comments::get_many(&client, args),
-        GetArgs::Datasets(args) => datasets::get(&client, args, printer, pool),
+        GetArgs::Buckets(args) => buckets::get(&client, resolver, args, printer, pool),
+        GetArgs::Email(args) => emails::get_single(&client, resolver, args),
+        GetArgs::Emails(args) => emails::get_many(&client, resolver, args),
+        GetArgs::Comment(args) => comments::get_single(&client, resolver, args),
+        GetArgs::Comments(args) => comments::get_many(&client, resolver, args, pool),
+        GetArgs::Datasets(args) => datasets::get(&client, resolver, args, printer, pool),
+        GetArgs::DatasetDiff(args) => dataset_diff::get(&client, resolver, args, printer),
+        GetArgs::DatasetQuery(args) => dataset_query::get(&client, resolver, args),
+        GetArgs::DatasetStatistics(args) => {
+            dataset_statistics::get(&client, resolver, args, printer)
+        }
+        GetArgs::Predictions(args) => predictions::get(&client, resolver, args),
         GetArgs::Projects(args) => projects::get(&client, args, printer),
-        GetArgs::Sources(args) => sources::get(&client, args, printer),
-        GetArgs::Streams(args) => streams::get(&client, args, printer),
+        GetArgs::Sources(args) => sources::get(&client, resolver, args, printer),
+        GetArgs::SourceStatistics(args) => source_statistics::get(&client, resolver, args, printer),
+        GetArgs::Streams(args) => streams::get(&client, resolver, args, printer),
         GetArgs::StreamComments(args) => streams::get_stream_comments(&client, args),
-        GetArgs::StreamStats(args) => streams::get_stream_stats(&client, args, printer, pool),
+        GetArgs::StreamStats(args) => {
+            streams::get_stream_stats(&client, resolver, args, printer, pool)
+        }
         GetArgs::Users(args) => users::get(&client, args, printer),
+        GetArgs::Validation(args) => validation::get(&client, resolver, args, printer),
+        GetArgs::LabelValidation(args) => validation::get_label(&client, resolver, args, printer),
         GetArgs::CurrentUser => users::get_current_user(&client, printer),
-        GetArgs::Quotas => quota::get(&client, printer),
+        GetArgs::Quotas(args) => quota::get(&client, args, printer, pool),
         GetArgs::AuditEvents(args) => audit_events::get(&client, args, printer),
         GetArgs::Integrations(args) => integrations::get(&client, args, printer),
-        GetArgs::KeyedSyncStates(args) => keyed_sync_states::get(&client, args, printer),
+        GetArgs::KeyedSyncStates(args) => keyed_sync_states::get(&client, resolver, args, printer),
         GetArgs::CustomDatasetReport(args) => {
             custom_label_trend_report::get(&client, args, printer)
         }
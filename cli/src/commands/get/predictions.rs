@@ -0,0 +1,138 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+use log::info;
+use ordered_float::NotNan;
+use reinfer_client::{
+    Client, CommentPredictionsThreshold, CommentUid, DatasetFullName, DatasetIdentifier,
+    ModelVersion,
+};
+use structopt::StructOpt;
+
+use crate::utils::{NameArg, Resolver};
+use crate::{
+    printer::print_resources_as_json,
+    utils::{self, ModelVersionArg},
+};
+
+/// Maximum number of comments to request predictions for at once.
+const PREDICTIONS_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, StructOpt)]
+pub struct GetPredictionsArgs {
+    #[structopt(name = "dataset")]
+    /// Dataset name or id
+    dataset: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "model-version")]
+    /// Model version to get the predictions of, or `latest`
+    model_version: ModelVersionArg,
+
+    #[structopt(long = "uids-file", parse(from_os_str))]
+    /// Path to a file with the uids of the comments to get predictions for, one per line (as
+    /// `<source id>.<comment id>`)
+    uids_path: PathBuf,
+
+    #[structopt(long = "threshold")]
+    /// Confidence threshold between 0 and 1 above which labels are returned. Defaults to the
+    /// automatic thresholds of the model.
+    threshold: Option<NotNan<f64>>,
+
+    #[structopt(short = "f", long = "file", parse(from_os_str))]
+    /// Path where to write the predictions as jsonl. If not specified, stdout will be used.
+    /// Paths ending in `.gz` are gzip compressed.
+    path: Option<PathBuf>,
+}
+
+pub fn get(client: &Client, resolver: &Resolver, args: &GetPredictionsArgs) -> Result<()> {
+    let GetPredictionsArgs {
+        dataset,
+        model_version,
+        uids_path,
+        threshold,
+        path,
+    } = args;
+
+    let threshold = match threshold {
+        Some(threshold) => {
+            ensure!(
+                (0.0..=1.0).contains(&threshold.into_inner()),
+                "The `threshold` option must be between 0 and 1."
+            );
+            CommentPredictionsThreshold::Fixed(*threshold)
+        }
+        None => CommentPredictionsThreshold::Auto,
+    };
+
+    let uids = read_uids(uids_path)?;
+
+    let dataset_name = resolver
+        .get_dataset(client, resolver.resolve(dataset)?)
+        .context("Operation to get dataset has failed.")?
+        .full_name();
+    let model_version = model_version.resolve(client, &dataset_name)?;
+
+    match path {
+        Some(path) => write_predictions(
+            client,
+            &dataset_name,
+            &model_version,
+            &uids,
+            threshold,
+            utils::create_output_file(path)?,
+        )?,
+        None => write_predictions(
+            client,
+            &dataset_name,
+            &model_version,
+            &uids,
+            threshold,
+            io::stdout().lock(),
+        )?,
+    };
+    info!("Successfully got predictions for {} comments", uids.len());
+    Ok(())
+}
+
+fn read_uids(path: &Path) -> Result<Vec<CommentUid>> {
+    let mut uids = Vec::new();
+    for line in utils::open_input_file(path)?.lines() {
+        let line = line.with_context(|| format!("Could not read `{}`", path.display()))?;
+        let uid = line.trim();
+        if !uid.is_empty() {
+            ensure!(
+                uid.contains('.'),
+                "Invalid comment uid `{}`, expected `<source id>.<comment id>`",
+                uid
+            );
+            uids.push(CommentUid(uid.to_owned()));
+        }
+    }
+    Ok(uids)
+}
+
+fn write_predictions(
+    client: &Client,
+    dataset_name: &DatasetFullName,
+    model_version: &ModelVersion,
+    uids: &[CommentUid],
+    threshold: CommentPredictionsThreshold,
+    mut writer: impl Write,
+) -> Result<()> {
+    for batch in uids.chunks(PREDICTIONS_BATCH_SIZE) {
+        let predictions = client
+            .get_comment_predictions(
+                dataset_name,
+                model_version,
+                batch.iter(),
+                Some(threshold),
+                None,
+            )
+            .context("Operation to get predictions has failed.")?;
+        print_resources_as_json(&predictions, &mut writer)?;
+    }
+    writer.flush().context("Could not write predictions")
+}
@@ -1,7 +1,110 @@
-use crate::printer::Printer;
-use anyhow::Result;
-use reinfer_client::Client;
+use crate::printer::{DisplayTable, Printer};
+use anyhow::{Context, Result};
+use log::warn;
+use prettytable::{cell, Row};
+use reinfer_client::{
+    resources::{
+        quota::Quota,
+        tenant_id::{ReinferTenantId, TenantId, UiPathTenantId},
+    },
+    Client, Error,
+};
+use reqwest::StatusCode;
+use scoped_threadpool::Pool;
+use serde::Serialize;
+use std::sync::mpsc::channel;
+use structopt::StructOpt;
 
-pub fn get(client: &Client, printer: &Printer) -> Result<()> {
-    printer.print_resources(&client.get_quotas()?)
+#[derive(Debug, StructOpt)]
+pub struct GetQuotaArgs {
+    #[structopt(long = "reinfer-tenant-id")]
+    /// Reinfer tenant IDs to get quotas for, instead of the current tenant
+    reinfer_tenant_ids: Vec<ReinferTenantId>,
+
+    #[structopt(long = "uipath-tenant-id")]
+    /// UiPath tenant IDs to get quotas for, instead of the current tenant
+    uipath_tenant_ids: Vec<UiPathTenantId>,
+}
+
+#[derive(Serialize)]
+pub struct TenantQuota {
+    tenant_id: String,
+    #[serde(flatten)]
+    quota: Quota,
+}
+
+impl DisplayTable for TenantQuota {
+    fn to_table_headers() -> Row {
+        let mut headers = Quota::to_table_headers();
+        headers.insert_cell(0, cell!(bFg -> "Tenant"));
+        headers
+    }
+
+    fn to_table_row(&self) -> Row {
+        let mut row = self.quota.to_table_row();
+        row.insert_cell(0, cell!(self.tenant_id));
+        row
+    }
+}
+
+pub fn get(client: &Client, args: &GetQuotaArgs, printer: &Printer, pool: &mut Pool) -> Result<()> {
+    let GetQuotaArgs {
+        reinfer_tenant_ids,
+        uipath_tenant_ids,
+    } = args;
+
+    let tenant_ids: Vec<TenantId> = reinfer_tenant_ids
+        .iter()
+        .cloned()
+        .map(TenantId::Reinfer)
+        .chain(uipath_tenant_ids.iter().cloned().map(TenantId::UiPath))
+        .collect();
+
+    if tenant_ids.is_empty() {
+        return printer.print_resources(
+            &client
+                .get_quotas()
+                .context("Operation to get quotas has failed.")?,
+        );
+    }
+
+    let (sender, receiver) = channel();
+    pool.scoped(|scope| {
+        for tenant_id in &tenant_ids {
+            let sender = sender.clone();
+            scope.execute(move || {
+                let result = match client.get_tenant_quotas(tenant_id) {
+                    Ok(quotas) => Ok(quotas
+                        .into_iter()
+                        .map(|quota| TenantQuota {
+                            tenant_id: tenant_id.to_string(),
+                            quota,
+                        })
+                        .collect()),
+                    Err(Error::Api { status_code, .. })
+                        if status_code == StatusCode::UNAUTHORIZED
+                            || status_code == StatusCode::FORBIDDEN =>
+                    {
+                        warn!("Skipping tenant `{tenant_id}`: not permitted to get its quotas");
+                        Ok(Vec::new())
+                    }
+                    Err(error) => Err(error).with_context(|| {
+                        format!("Operation to get quotas for tenant `{tenant_id}` has failed.")
+                    }),
+                };
+                sender.send(result).expect("Could not send result");
+            });
+        }
+    });
+    drop(sender);
+
+    let mut quotas: Vec<TenantQuota> = receiver
+        .iter()
+        .collect::<Result<Vec<Vec<_>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    quotas.sort_by(|left, right| left.tenant_id.cmp(&right.tenant_id));
+
+    printer.print_resources(&quotas)
 }
@@ -0,0 +1,59 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reinfer_client::{
+    resources::source::StatisticsRequestParams, Client, CommentFilter, SourceIdentifier,
+};
+use structopt::StructOpt;
+
+use super::{comments::StructExt, dataset_statistics::get_comment_filter};
+use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
+
+#[derive(Debug, StructOpt)]
+pub struct GetSourceStatisticsArgs {
+    #[structopt(name = "source")]
+    /// Source name or id
+    source: NameArg<SourceIdentifier>,
+
+    #[structopt(long = "filter")]
+    /// Comment filter to apply as a json string, e.g. `{"user_properties": {...}}`
+    filter: Option<StructExt<CommentFilter>>,
+
+    #[structopt(long = "from-timestamp")]
+    /// Only count comments at or after this timestamp. Overrides the timestamp in --filter.
+    from_timestamp: Option<DateTime<Utc>>,
+
+    #[structopt(long = "to-timestamp")]
+    /// Only count comments at or before this timestamp. Overrides the timestamp in --filter.
+    to_timestamp: Option<DateTime<Utc>>,
+}
+
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetSourceStatisticsArgs,
+    printer: &Printer,
+) -> Result<()> {
+    let GetSourceStatisticsArgs {
+        source,
+        filter,
+        from_timestamp,
+        to_timestamp,
+    } = args;
+
+    let source_name = resolver
+        .get_source(client, resolver.resolve(source)?)
+        .context("Operation to get source has failed.")?
+        .full_name();
+
+    let statistics = client
+        .get_source_statistics(
+            &source_name,
+            &StatisticsRequestParams {
+                comment_filter: get_comment_filter(filter.as_ref(), *from_timestamp, *to_timestamp),
+            },
+        )
+        .context("Operation to get source statistics has failed.")?;
+
+    printer.print_resources(&[statistics])
+}
@@ -5,27 +5,33 @@ use std::collections::HashMap;
 use structopt::StructOpt;
 
 use crate::printer::{PrintableSource, Printer};
+use crate::utils::{NameArg, Resolver};
 
 #[derive(Debug, StructOpt)]
 pub struct GetSourcesArgs {
     #[structopt(name = "source")]
     /// If specified, only list this source (name or id)
-    source: Option<SourceIdentifier>,
+    source: Option<NameArg<SourceIdentifier>>,
 
     #[structopt(long = "stats")]
     /// Whether to include source statistics in response
     include_stats: bool,
 }
 
-pub fn get(client: &Client, args: &GetSourcesArgs, printer: &Printer) -> Result<()> {
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetSourcesArgs,
+    printer: &Printer,
+) -> Result<()> {
     let GetSourcesArgs {
         source,
         include_stats,
     } = args;
 
     let sources = if let Some(source) = source {
-        vec![client
-            .get_source(source.clone())
+        vec![resolver
+            .get_source(client, resolver.resolve(source)?)
             .context("Operation to list sources has failed.")?]
     } else {
         let mut sources = client
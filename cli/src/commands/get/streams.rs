@@ -20,13 +20,17 @@ use std::{
 };
 use structopt::StructOpt;
 
-use crate::printer::{print_resources_as_json, DisplayTable, Printer};
+use crate::utils::{NameArg, Resolver};
+use crate::{
+    printer::{print_resources_as_json, DisplayTable, Printer},
+    utils::ModelVersionArg,
+};
 
 #[derive(Debug, StructOpt)]
 pub struct GetStreamsArgs {
     #[structopt(short = "d", long = "dataset")]
     /// The dataset name or id
-    dataset: DatasetIdentifier,
+    dataset: NameArg<DatasetIdentifier>,
 
     #[structopt(short = "f", long = "file", parse(from_os_str))]
     /// Path where to write streams as JSON.
@@ -50,6 +54,11 @@ pub struct GetStreamCommentsArgs {
     #[structopt(long = "individual-advance")]
     /// If set, the command will acknowledge each comment in turn, rather than full batches.
     individual_advance: bool,
+
+    #[structopt(long = "max-items", requires = "listen")]
+    /// Stop listening after writing this many comments. The stream is only advanced up to the
+    /// last comment written.
+    max_items: Option<usize>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -59,15 +68,20 @@ pub struct GetStreamStatsArgs {
     stream_full_name: StreamFullName,
 
     #[structopt(long = "compare-version", short = "v")]
-    /// The model version to compare stats with
-    compare_to_model_version: Option<ModelVersion>,
+    /// The model version to compare stats with, or `latest`
+    compare_to_model_version: Option<ModelVersionArg>,
 
     #[structopt(long = "compare-dataset", short = "d")]
     /// The dataset to compare stats with
-    compare_to_dataset: Option<DatasetFullName>,
+    compare_to_dataset: Option<NameArg<DatasetFullName>>,
 }
 
-pub fn get(client: &Client, args: &GetStreamsArgs, printer: &Printer) -> Result<()> {
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetStreamsArgs,
+    printer: &Printer,
+) -> Result<()> {
     let GetStreamsArgs { dataset, path } = args;
 
     let file: Option<Box<dyn Write>> = match path {
@@ -79,8 +93,8 @@ pub fn get(client: &Client, args: &GetStreamsArgs, printer: &Printer) -> Result<
         None => None,
     };
 
-    let dataset_name = client
-        .get_dataset(dataset.clone())
+    let dataset_name = resolver
+        .get_dataset(client, resolver.resolve(dataset)?)
         .context("Operation to get dataset has failed.")?
         .full_name();
     let mut streams = client
@@ -295,7 +309,7 @@ impl CompareConfig {
 
 fn get_compare_config(
     client: &Client,
-    model_version: &Option<ModelVersion>,
+    model_version: &Option<ModelVersionArg>,
     dataset_name: &Option<DatasetFullName>,
     stream_name: &StreamFullName,
 ) -> Result<Option<CompareConfig>> {
@@ -310,8 +324,8 @@ fn get_compare_config(
     };
 
     let model_version = model_version
-        .clone()
-        .context("No compare to model version provided")?;
+        .context("No compare to model version provided")?
+        .resolve(client, dataset_name)?;
 
     info!("Getting validation for {}", dataset_name.0);
     let validation = client.get_validation(dataset_name, &model_version)?;
@@ -406,6 +420,7 @@ fn get_stream_stat(
 
 pub fn get_stream_stats(
     client: &Client,
+    resolver: &Resolver,
     args: &GetStreamStatsArgs,
     printer: &Printer,
     pool: &mut Pool,
@@ -429,7 +444,10 @@ pub fn get_stream_stats(
     let compare_config = get_compare_config(
         client,
         compare_to_model_version,
-        compare_to_dataset,
+        &compare_to_dataset
+            .as_ref()
+            .map(|dataset| resolver.resolve(dataset))
+            .transpose()?,
         stream_full_name,
     )?;
 
@@ -480,8 +498,10 @@ pub fn get_stream_comments(client: &Client, args: &GetStreamCommentsArgs) -> Res
         size,
         listen,
         individual_advance,
+        max_items,
     } = args;
 
+    let mut num_written = 0;
     match listen {
         Some(delay) => loop {
             let batch = client
@@ -497,10 +517,25 @@ pub fn get_stream_comments(client: &Client, args: &GetStreamCommentsArgs) -> Res
                 }
                 continue;
             }
-            let needs_final_advance = !individual_advance
-                || batch.sequence_id != batch.results.last().unwrap().sequence_id;
-            for result in batch.results {
+            let mut results = batch.results;
+            let limit_exceeded = max_items.is_some_and(|max_items| {
+                let remaining = max_items.saturating_sub(num_written);
+                let limit_exceeded = results.len() > remaining;
+                results.truncate(remaining);
+                limit_exceeded
+            });
+            let last_sequence_id = results.last().map(|result| result.sequence_id.clone());
+            // If the batch was truncated, only advance up to the last comment written.
+            let final_sequence_id = if limit_exceeded {
+                last_sequence_id.clone()
+            } else {
+                Some(batch.sequence_id)
+            };
+
+            let needs_final_advance = !individual_advance || final_sequence_id != last_sequence_id;
+            for result in results {
                 print_resources_as_json(Some(&result), io::stdout().lock())?;
+                num_written += 1;
 
                 if *individual_advance {
                     client
@@ -508,11 +543,19 @@ pub fn get_stream_comments(client: &Client, args: &GetStreamCommentsArgs) -> Res
                         .context("Operation to advance stream for comment failed.")?;
                 }
             }
-            if needs_final_advance {
+            if let Some(final_sequence_id) = final_sequence_id.filter(|_| needs_final_advance) {
                 client
-                    .advance_stream(stream, batch.sequence_id)
+                    .advance_stream(stream, final_sequence_id)
                     .context("Operation to advance stream for batch failed.")?;
             }
+
+            if limit_exceeded {
+                info!(
+                    "Stopped after {} comments as the `--max-items` limit was reached.",
+                    num_written
+                );
+                return Ok(());
+            }
         },
         None => {
             let batch = client
@@ -1,5 +1,8 @@
-use anyhow::{bail, Context, Result};
-use reinfer_client::{Client, ProjectName, ProjectPermission, UserIdentifier};
+use anyhow::{Context, Result};
+use reinfer_client::{
+    Client, GlobalPermission, ProjectName, ProjectPermission, User, UserIdentifier,
+};
+use std::collections::HashSet;
 use structopt::StructOpt;
 
 use crate::printer::Printer;
@@ -15,8 +18,13 @@ pub struct GetUsersArgs {
     project_name_filter: Option<ProjectName>,
 
     #[structopt(short = "p", long = "permission")]
-    /// Filter users by a given project permission
+    /// Filter users by a given project permission. Without --project, users with the permission
+    /// in any project are listed.
     project_permission_filter: Option<ProjectPermission>,
+
+    #[structopt(long = "global-permission")]
+    /// Filter users by a given global permission
+    global_permission_filter: Option<GlobalPermission>,
 }
 
 pub fn get(client: &Client, args: &GetUsersArgs, printer: &Printer) -> Result<()> {
@@ -24,12 +32,9 @@ pub fn get(client: &Client, args: &GetUsersArgs, printer: &Printer) -> Result<()
         user,
         project_name_filter,
         project_permission_filter,
+        global_permission_filter,
     } = args;
 
-    if project_name_filter.is_none() && project_permission_filter.is_some() {
-        bail!("You cannot filter on `permission` without a `project`")
-    }
-
     let mut users = match user {
         Some(user_id) => {
             let user = client
@@ -42,23 +47,39 @@ pub fn get(client: &Client, args: &GetUsersArgs, printer: &Printer) -> Result<()
             .context("Operation to list users has failed.")?,
     };
 
-    if let Some(project_name) = project_name_filter {
-        users.retain(|user| {
-            user.project_permissions
-                .get(project_name)
-                .is_some_and(|user_permissions| {
-                    if let Some(project_permission) = project_permission_filter {
-                        user_permissions.contains(project_permission)
-                    } else {
-                        true
-                    }
-                })
-        })
-    }
+    users.retain(|user| {
+        matches_project_filters(
+            user,
+            project_name_filter.as_ref(),
+            project_permission_filter.as_ref(),
+        ) && global_permission_filter
+            .as_ref()
+            .is_none_or(|permission| user.global_permissions.contains(permission))
+    });
 
     printer.print_resources(&users)
 }
 
+fn matches_project_filters(
+    user: &User,
+    project_name: Option<&ProjectName>,
+    project_permission: Option<&ProjectPermission>,
+) -> bool {
+    let has_permission = |user_permissions: &HashSet<ProjectPermission>| {
+        project_permission.is_none_or(|permission| user_permissions.contains(permission))
+    };
+
+    match project_name {
+        Some(project_name) => user
+            .project_permissions
+            .get(project_name)
+            .is_some_and(has_permission),
+        None => {
+            project_permission.is_none() || user.project_permissions.values().any(has_permission)
+        }
+    }
+}
+
 pub fn get_current_user(client: &Client, printer: &Printer) -> Result<()> {
     let user = client
         .get_current_user()
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use log::info;
+use reinfer_client::{Client, DatasetFullName, DatasetIdentifier, LabelName};
+use structopt::StructOpt;
+
+use crate::utils::{NameArg, Resolver};
+use crate::{printer::Printer, utils::ModelVersionArg};
+
+#[derive(Debug, StructOpt)]
+pub struct GetValidationArgs {
+    #[structopt(name = "dataset")]
+    /// Dataset name or id
+    dataset: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "model-version")]
+    /// Model version to get the validation of, or `latest` for the newest pinned one. Defaults
+    /// to the latest validated model version.
+    model_version: Option<ModelVersionArg>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct GetLabelValidationArgs {
+    #[structopt(name = "dataset")]
+    /// Dataset name or id
+    dataset: NameArg<DatasetIdentifier>,
+
+    #[structopt(long = "label")]
+    /// Name of the label to get the validation of, e.g. `Parent > Child`
+    label: String,
+
+    #[structopt(long = "model-version")]
+    /// Model version to get the validation of, or `latest` for the newest pinned one. Defaults
+    /// to the latest validated model version.
+    model_version: Option<ModelVersionArg>,
+}
+
+pub fn get(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetValidationArgs,
+    printer: &Printer,
+) -> Result<()> {
+    let GetValidationArgs {
+        dataset,
+        model_version,
+    } = args;
+
+    let dataset_name = get_dataset_name(client, resolver, &resolver.resolve(dataset)?)?;
+    let validation = match model_version {
+        Some(model_version) => client.get_validation(
+            &dataset_name,
+            &model_version.resolve(client, &dataset_name)?,
+        ),
+        None => client.get_latest_validation(&dataset_name),
+    }
+    .context("Operation to get validation has failed.")?;
+
+    printer.print_resources(&[validation])
+}
+
+pub fn get_label(
+    client: &Client,
+    resolver: &Resolver,
+    args: &GetLabelValidationArgs,
+    printer: &Printer,
+) -> Result<()> {
+    let GetLabelValidationArgs {
+        dataset,
+        label,
+        model_version,
+    } = args;
+
+    let dataset_name = get_dataset_name(client, resolver, &resolver.resolve(dataset)?)?;
+    let model_version = match model_version {
+        Some(model_version) => model_version.resolve(client, &dataset_name)?,
+        None => {
+            let version = client
+                .get_latest_validation(&dataset_name)
+                .context("Operation to get validation has failed.")?
+                .validation
+                .version;
+            info!("Using latest model version {}", version);
+            version
+        }
+    };
+
+    let label_validation = client
+        .get_label_validation(&LabelName(label.clone()), &dataset_name, &model_version)
+        .with_context(|| format!("Operation to get validation for label `{label}` has failed."))?;
+
+    printer.print_resources(&[label_validation])
+}
+
+fn get_dataset_name(
+    client: &Client,
+    resolver: &Resolver,
+    dataset: &DatasetIdentifier,
+) -> Result<DatasetFullName> {
+    Ok(resolver
+        .get_dataset(client, dataset.clone())
+        .context("Operation to get dataset has failed.")?
+        .full_name())
+}
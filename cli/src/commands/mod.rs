@@ -1,13 +1,16 @@
 use std::{
-    fs::{create_dir, File},
-    io::{BufWriter, Write},
+    collections::{hash_map::Entry, HashMap},
+    fs::{self, create_dir_all, File},
+    io,
     path::PathBuf,
+    sync::Mutex,
 };
 
 use anyhow::{anyhow, Context, Result};
 use dialoguer::Confirm;
-use once_cell::sync::Lazy;
-use reinfer_client::TransformTag;
+use log::debug;
+use reinfer_client::{resources::comment::AttachmentReference, Client};
+use sha2::{Digest, Sha256};
 use url::Url;
 
 pub mod config;
@@ -15,6 +18,7 @@ pub mod create;
 pub mod delete;
 pub mod get;
 pub mod parse;
+pub mod schema;
 pub mod update;
 
 pub fn ensure_uip_user_consents_to_ai_unit_charge(base_url: &Url) -> Result<()> {
@@ -43,8 +47,7 @@ Do you want to continue?"#,
     }
 }
 
-static DEFAULT_TRANSFORM_TAG: Lazy<TransformTag> =
-    Lazy::new(|| TransformTag("generic.0.CONVKER5".to_string()));
+const DEFAULT_TRANSFORM_TAG: &str = "generic.0.CONVKER5";
 
 pub struct LocalAttachmentPath {
     index: usize,
@@ -65,7 +68,7 @@ fn clean_file_name(mut name: String) -> String {
 impl LocalAttachmentPath {
     fn ensure_parent_dir_exists(&self) -> Result<()> {
         if !self.parent_dir.exists() {
-            create_dir(&self.parent_dir)?;
+            create_dir_all(&self.parent_dir)?;
         }
         Ok(())
     }
@@ -82,25 +85,83 @@ impl LocalAttachmentPath {
         self.path().is_file()
     }
 
-    pub fn write(&self, buf_to_write: Vec<u8>) -> Result<bool> {
+    /// Stream an attachment to disk without buffering it in memory. Returns whether the file was
+    /// written, i.e. `false` if it already existed.
+    pub fn download(
+        &self,
+        client: &Client,
+        reference: &AttachmentReference,
+        deduplicator: Option<&AttachmentDeduplicator>,
+    ) -> Result<bool> {
         self.ensure_parent_dir_exists()?;
 
-        if !self.exists() {
-            let f = File::create(self.path()).context("Could not create attachment output file")?;
+        if self.exists() {
+            return Ok(false);
+        }
+
+        client
+            .get_attachment_to_path(reference, &self.path())
+            .context("Operation to get attachment has failed.")?;
+        if let Some(deduplicator) = deduplicator {
+            self.deduplicate(deduplicator)?;
+        }
+        Ok(true)
+    }
 
-            let mut buf_writer = BufWriter::new(f);
-            buf_writer.write_all(&buf_to_write)?;
-            Ok(true)
-        } else {
-            Ok(false)
+    /// If an attachment with identical content was already written during this run, replace this
+    /// one with a hard link to it. The copy is kept where links are not supported (e.g. across
+    /// file systems or on restricted Windows setups).
+    fn deduplicate(&self, deduplicator: &AttachmentDeduplicator) -> Result<()> {
+        let mut hasher = Sha256::new();
+        io::copy(
+            &mut File::open(self.path()).context("Could not open attachment")?,
+            &mut hasher,
+        )
+        .context("Could not read attachment")?;
+        let checksum: [u8; 32] = hasher.finalize().into();
+
+        let mut written = deduplicator
+            .written
+            .lock()
+            .expect("Attachment deduplicator lock poisoned");
+        match written.entry(checksum) {
+            Entry::Occupied(entry) => {
+                let original = entry.get();
+                // Link under a temporary name first, so the copy is only replaced once the link
+                // exists.
+                let link_path = self.parent_dir.join(format!("{}.link", self.name()));
+                if let Err(error) = fs::hard_link(original, &link_path)
+                    .and_then(|()| fs::rename(&link_path, self.path()))
+                {
+                    debug!(
+                        "Could not link `{}` to `{}`, keeping a copy instead: {}",
+                        self.path().display(),
+                        original.display(),
+                        error
+                    );
+                    let _ = fs::remove_file(&link_path);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(self.path());
+            }
         }
+        Ok(())
     }
 }
 
+/// Tracks the sha256 checksums of attachments written during a run, so that identical
+/// attachments are only written to disk once.
+#[derive(Debug, Default)]
+pub struct AttachmentDeduplicator {
+    written: Mutex<HashMap<[u8; 32], PathBuf>>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::clean_file_name;
+    use super::{clean_file_name, AttachmentDeduplicator, LocalAttachmentPath};
     use pretty_assertions::assert_eq;
+    use std::fs;
 
     #[test]
     fn test_clean_file_name() {
@@ -109,4 +170,39 @@ mod tests {
 
         assert_eq!("this is a file 2024□08□07□", cleaned)
     }
+
+    #[test]
+    fn test_deduplicate() {
+        let dir = std::env::temp_dir().join(format!("re-dedup-test-{}", uuid::Uuid::new_v4()));
+        let deduplicator = AttachmentDeduplicator::default();
+        let attachment = |comment_id: &str, name: &str| LocalAttachmentPath {
+            index: 0,
+            name: name.to_string(),
+            parent_dir: dir.join(comment_id),
+        };
+
+        for (comment_id, name, content) in [
+            ("a", "logo.png", b"logo".as_slice()),
+            ("b", "logo.png", b"logo"),
+            ("c", "other.png", b"other"),
+        ] {
+            let attachment = attachment(comment_id, name);
+            attachment.ensure_parent_dir_exists().unwrap();
+            fs::write(attachment.path(), content).unwrap();
+            attachment.deduplicate(&deduplicator).unwrap();
+        }
+        assert!(attachment("a", "logo.png").exists());
+
+        assert_eq!(
+            fs::read(attachment("b", "logo.png").path()).unwrap(),
+            b"logo"
+        );
+        assert_eq!(
+            fs::read(attachment("c", "other.png").path()).unwrap(),
+            b"other"
+        );
+        assert_eq!(deduplicator.written.lock().unwrap().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
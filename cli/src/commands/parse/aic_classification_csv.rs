@@ -1,15 +1,20 @@
+use crate::utils::{NameArg, Resolver};
 use crate::{
     commands::{
         create::annotations::{upload_batch_of_annotations, CommentIdComment, NewAnnotation},
-        parse::{get_progress_bar, upload_batch_of_comments},
+        parse::{get_or_create_source, get_progress_bar, upload_batch_of_comments},
     },
     parse::Statistics,
+    utils,
 };
 use anyhow::Result;
 use log::{error, info};
 use scoped_threadpool::Pool;
 use serde::Deserialize;
-use std::sync::{mpsc::channel, Arc};
+use std::{
+    sync::{mpsc::channel, Arc},
+    time::Instant,
+};
 
 use reinfer_client::{
     Client, CommentId, DatasetFullName, DatasetIdentifier, EitherLabelling, Label, Message,
@@ -28,11 +33,23 @@ pub struct ParseAicClassificationCsvArgs {
 
     #[structopt(short = "s", long = "source")]
     /// The source to upload the data to
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
+
+    #[structopt(long = "create-source")]
+    /// Create the source if it doesn't exist. The source must be given by name.
+    create_source: bool,
+
+    #[structopt(long = "language", requires = "create-source")]
+    /// Language of the source, if it's created
+    language: Option<String>,
+
+    #[structopt(long = "should-translate", requires = "create-source")]
+    /// Enable translation for the source, if it's created
+    should_translate: Option<bool>,
 
     #[structopt(short = "d", long = "dataset")]
     /// The dataset to upload annotations to
-    dataset: DatasetIdentifier,
+    dataset: NameArg<DatasetIdentifier>,
 
     #[structopt(short = "n", long = "no-charge")]
     /// Whether to attempt to bypass billing (internal only)
@@ -98,16 +115,32 @@ fn send_comments_if_needed(
     }
 }
 
-pub fn parse(client: &Client, args: &ParseAicClassificationCsvArgs, pool: &mut Pool) -> Result<()> {
+pub fn parse(
+    client: &Client,
+    resolver: &Resolver,
+    args: &ParseAicClassificationCsvArgs,
+    pool: &mut Pool,
+) -> Result<()> {
     let ParseAicClassificationCsvArgs {
         file_path,
         source,
+        create_source,
+        language,
+        should_translate,
         dataset,
         no_charge,
     } = args;
 
-    let source = client.get_source(source.clone())?;
-    let dataset = client.get_dataset(dataset.clone())?;
+    let start = Instant::now();
+    let source = get_or_create_source(
+        client,
+        resolver,
+        &resolver.resolve(source)?,
+        *create_source,
+        language.as_deref(),
+        *should_translate,
+    )?;
+    let dataset = resolver.get_dataset(client, resolver.resolve(dataset)?)?;
     let record_count = csv::Reader::from_path(file_path)?.records().count();
 
     let statistics = Arc::new(Statistics::new());
@@ -189,5 +222,15 @@ pub fn parse(client: &Client, args: &ParseAicClassificationCsvArgs, pool: &mut P
         statistics.num_uploaded(),
         statistics.num_failed()
     );
+    info!(
+        "{}",
+        utils::throughput_summary(
+            start.elapsed(),
+            &[
+                (statistics.num_uploaded(), "comments"),
+                (statistics.num_annotations(), "annotations"),
+            ]
+        )
+    );
     Ok(())
 }
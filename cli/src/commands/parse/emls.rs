@@ -1,20 +1,27 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
-use log::error;
+use log::{error, info};
 use mailparse::{DispositionType, MailHeader, MailHeaderMap};
 use scoped_threadpool::Pool;
+use sha2::{Digest, Sha256};
 use std::{
     fs,
     path::{Path, PathBuf},
     sync::{mpsc::channel, Arc},
+    time::Instant,
 };
 
-use crate::commands::{
-    ensure_uip_user_consents_to_ai_unit_charge,
-    parse::{get_files_in_directory, get_progress_bar, Statistics},
+use crate::{
+    commands::{
+        ensure_uip_user_consents_to_ai_unit_charge,
+        parse::{
+            get_files_in_directory, get_progress_bar, IdFrom, MessageDeduplicator, Statistics,
+        },
+    },
+    utils::{self, NameArg, Resolver},
 };
 use reinfer_client::{
-    resources::attachments::AttachmentMetadata, BucketIdentifier, Client, NewEmail,
+    resources::attachments::AttachmentMetadata, BucketIdentifier, Client, MimeContent, NewEmail,
 };
 use structopt::StructOpt;
 
@@ -29,7 +36,18 @@ pub struct ParseEmlArgs {
 
     #[structopt(short = "b", long = "bucket")]
     /// Name of the bucket where the emails will be uploaded.
-    bucket: BucketIdentifier,
+    bucket: NameArg<BucketIdentifier>,
+
+    #[structopt(long = "id-from", default_value = "message-id")]
+    /// How to derive email ids, one of: message-id, content-hash. Message-Id headers are stable
+    /// across exports of the same email. The content hash is the sha256 of the raw email, so it's
+    /// only stable for byte-identical emls.
+    id_from: IdFrom,
+
+    #[structopt(long = "dedup")]
+    /// Skip emails with a Message-Id which was already seen in this run, e.g. when parsing
+    /// overlapping exports.
+    dedup: bool,
 
     #[structopt(short = "n", long = "no-charge")]
     /// Whether to attempt to bypass billing (internal only)
@@ -40,10 +58,17 @@ pub struct ParseEmlArgs {
     yes: bool,
 }
 
-pub fn parse(client: &Client, args: &ParseEmlArgs, pool: &mut Pool) -> Result<()> {
+pub fn parse(
+    client: &Client,
+    resolver: &Resolver,
+    args: &ParseEmlArgs,
+    pool: &mut Pool,
+) -> Result<()> {
     let ParseEmlArgs {
         directory,
         bucket,
+        id_from,
+        dedup,
         no_charge,
         yes,
     } = args;
@@ -52,16 +77,18 @@ pub fn parse(client: &Client, args: &ParseEmlArgs, pool: &mut Pool) -> Result<()
         ensure_uip_user_consents_to_ai_unit_charge(client.base_url())?;
     }
 
+    let start = Instant::now();
     let eml_paths = get_files_in_directory(directory, "eml", true)?;
     let statistics = Arc::new(Statistics::new());
     let _progress = get_progress_bar(eml_paths.len() as u64, &statistics);
 
     let bucket = client
-        .get_bucket(bucket.clone())
+        .get_bucket(resolver.resolve(bucket)?)
         .with_context(|| format!("Unable to get bucket {}", args.bucket))?;
 
     let mut emails = Vec::new();
     let mut errors = Vec::new();
+    let deduplicator = dedup.then(MessageDeduplicator::default);
 
     let mut send_if_needed = |emails: &mut Vec<NewEmail>, force_send: bool| -> Result<()> {
         let thread_count = pool.thread_count();
@@ -101,8 +128,22 @@ pub fn parse(client: &Client, args: &ParseEmlArgs, pool: &mut Pool) -> Result<()
     };
 
     for path in eml_paths {
-        match read_eml_to_new_email(&path.path()) {
-            Ok(new_email) => {
+        match read_eml_to_new_email(&path.path(), false) {
+            Ok(mut new_email) => {
+                if *id_from == IdFrom::ContentHash {
+                    new_email.id = reinfer_client::EmailId(format!(
+                        "{:x}",
+                        Sha256::digest(new_email.mime_content.0.as_bytes())
+                    ));
+                }
+                if deduplicator
+                    .as_ref()
+                    .is_some_and(|deduplicator| deduplicator.is_duplicate(new_email.id.0.clone()))
+                {
+                    statistics.increment_skipped_duplicates();
+                    statistics.increment_processed();
+                    continue;
+                }
                 emails.push(new_email);
 
                 send_if_needed(&mut emails, false)?;
@@ -125,10 +166,22 @@ pub fn parse(client: &Client, args: &ParseEmlArgs, pool: &mut Pool) -> Result<()
     for error in errors {
         error!("{}", error);
     }
+    if *dedup {
+        info!(
+            "Skipped {} duplicate emails",
+            statistics.num_skipped_duplicates()
+        );
+    }
+    info!(
+        "{}",
+        utils::throughput_summary(start.elapsed(), &[(statistics.num_uploaded(), "emails")])
+    );
     Ok(())
 }
 
-fn read_eml_to_new_email(path: &PathBuf) -> Result<NewEmail> {
+/// Converts an `.eml` file to a `NewEmail`, with its original bytes as the MIME content. Files
+/// which are not valid UTF-8 are rejected, unless `replace_invalid_utf8` is set.
+pub fn read_eml_to_new_email(path: &PathBuf, replace_invalid_utf8: bool) -> Result<NewEmail> {
     if !path.is_file() {
         return Err(anyhow!("No such file : {:?}", path));
     }
@@ -189,8 +242,7 @@ fn read_eml_to_new_email(path: &PathBuf) -> Result<NewEmail> {
         .to_string_lossy()
         .to_string();
 
-    // Get mime content
-    let eml_str = std::str::from_utf8(&eml_bytes)?;
+    let mime_content = mime_content_from_bytes(&eml_bytes, replace_invalid_utf8)?;
 
     Ok(NewEmail {
         id: reinfer_client::EmailId(message_id),
@@ -198,9 +250,21 @@ fn read_eml_to_new_email(path: &PathBuf) -> Result<NewEmail> {
         timestamp,
         metadata: None,
         attachments,
-        mime_content: reinfer_client::MimeContent(eml_str.to_string()),
+        mime_content,
     })
 }
+
+fn mime_content_from_bytes(eml_bytes: &[u8], replace_invalid_utf8: bool) -> Result<MimeContent> {
+    let eml_str = if replace_invalid_utf8 {
+        String::from_utf8_lossy(eml_bytes).into_owned()
+    } else {
+        std::str::from_utf8(eml_bytes)
+            .context("Email is not valid UTF-8, so its raw MIME content cannot be kept")?
+            .to_string()
+    };
+    Ok(MimeContent(eml_str))
+}
+
 pub fn parse_header(headers: &[MailHeader], header: &str) -> Option<String> {
     headers
         .get_first_value(header)
@@ -248,9 +312,24 @@ mod tests {
             mime_content: reinfer_client::MimeContent(expected_mime_content.to_string()),
         };
 
-        let actual_email = read_eml_to_new_email(&PathBuf::from("tests/samples/test.eml"))
+        let actual_email = read_eml_to_new_email(&PathBuf::from("tests/samples/test.eml"), false)
             .expect("Failed to read eml");
 
         assert_eq!(expected_email, actual_email);
     }
+
+    #[test]
+    fn test_mime_content_from_bytes() {
+        let eml_bytes = b"Subject: caf\xe9\r\n\r\nHello";
+
+        assert!(mime_content_from_bytes(eml_bytes, false).is_err());
+        assert_eq!(
+            mime_content_from_bytes(eml_bytes, true).unwrap(),
+            MimeContent("Subject: caf\u{fffd}\r\n\r\nHello".to_string())
+        );
+        assert_eq!(
+            mime_content_from_bytes(b"Subject: cafe", false).unwrap(),
+            MimeContent("Subject: cafe".to_string())
+        );
+    }
 }
@@ -1,21 +1,27 @@
 mod aic_classification_csv;
-mod emls;
+pub mod emls;
 mod msgs;
 
 use aic_classification_csv::ParseAicClassificationCsvArgs;
-use anyhow::Result;
+use anyhow::{anyhow, Context, Error, Result};
 use colored::Colorize;
+use log::info;
 use reinfer_client::resources::bucket::FullName as BucketFullName;
 use reinfer_client::resources::documents::Document;
-use reinfer_client::{Client, NewComment, NewEmail, Source, TransformTag};
+use reinfer_client::{
+    Client, NewComment, NewEmail, NewSource, Source, SourceIdentifier, TransformTag,
+};
 use scoped_threadpool::Pool;
+use std::collections::HashSet;
 use std::fs::DirEntry;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use structopt::StructOpt;
 
 use crate::progress::{Options as ProgressOptions, Progress};
+use crate::utils::Resolver;
 
 use self::emls::ParseEmlArgs;
 use self::msgs::ParseMsgArgs;
@@ -39,11 +45,13 @@ pub enum ParseArgs {
     AicClassificationCsv(ParseAicClassificationCsvArgs),
 }
 
-pub fn run(args: &ParseArgs, client: Client, pool: &mut Pool) -> Result<()> {
+pub fn run(args: &ParseArgs, client: Client, resolver: &Resolver, pool: &mut Pool) -> Result<()> {
     match args {
-        ParseArgs::Msgs(args) => msgs::parse(&client, args),
-        ParseArgs::Emls(args) => emls::parse(&client, args, pool),
-        ParseArgs::AicClassificationCsv(args) => aic_classification_csv::parse(&client, args, pool),
+        ParseArgs::Msgs(args) => msgs::parse(&client, resolver, args, pool),
+        ParseArgs::Emls(args) => emls::parse(&client, resolver, args, pool),
+        ParseArgs::AicClassificationCsv(args) => {
+            aic_classification_csv::parse(&client, resolver, args, pool)
+        }
     }
 }
 
@@ -54,6 +62,7 @@ pub struct Statistics {
     uploaded: AtomicUsize,
     annotations: AtomicUsize,
     failed_annotations: AtomicUsize,
+    skipped_duplicates: AtomicUsize,
 }
 
 impl AnnotationStatistic for Statistics {
@@ -83,6 +92,11 @@ impl Statistics {
         self.failed.fetch_add(1, Ordering::SeqCst);
     }
 
+    #[inline]
+    fn increment_skipped_duplicates(&self) {
+        self.skipped_duplicates.fetch_add(1, Ordering::SeqCst);
+    }
+
     #[inline]
     fn increment_processed(&self) {
         self.processed.fetch_add(1, Ordering::SeqCst);
@@ -107,6 +121,86 @@ impl Statistics {
     fn num_annotations(&self) -> usize {
         self.annotations.load(Ordering::SeqCst)
     }
+
+    #[inline]
+    fn num_skipped_duplicates(&self) -> usize {
+        self.skipped_duplicates.load(Ordering::SeqCst)
+    }
+}
+
+/// How to derive the ids of parsed messages, so that parsing the same messages again updates them
+/// rather than creating duplicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdFrom {
+    MessageId,
+    ContentHash,
+}
+
+impl FromStr for IdFrom {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "message-id" => Ok(IdFrom::MessageId),
+            "content-hash" => Ok(IdFrom::ContentHash),
+            _ => Err(anyhow!(
+                "unknown id source: '{}', expected `message-id` or `content-hash`",
+                string
+            )),
+        }
+    }
+}
+
+/// Tracks the messages seen during a parse run, so that messages which appear in several
+/// overlapping exports are only uploaded once.
+#[derive(Debug, Default)]
+pub struct MessageDeduplicator {
+    seen: Mutex<HashSet<String>>,
+}
+
+impl MessageDeduplicator {
+    /// Whether a message with this key was already seen, recording it otherwise.
+    fn is_duplicate(&self, key: String) -> bool {
+        !self
+            .seen
+            .lock()
+            .expect("Message deduplicator lock poisoned")
+            .insert(key)
+    }
+}
+
+/// Get the source to parse into. With `create`, a source given by name which doesn't exist yet is
+/// created first.
+fn get_or_create_source(
+    client: &Client,
+    resolver: &Resolver,
+    source: &SourceIdentifier,
+    create: bool,
+    language: Option<&str>,
+    should_translate: Option<bool>,
+) -> Result<Source> {
+    match source {
+        SourceIdentifier::FullName(full_name) if create => {
+            match client.get_source(full_name.clone()) {
+                Ok(source) => Ok(source),
+                Err(error) if error.is_not_found() => {
+                    info!("Creating source `{}`", full_name.0);
+                    client
+                        .create_source(
+                            full_name,
+                            NewSource {
+                                language,
+                                should_translate,
+                                ..Default::default()
+                            },
+                        )
+                        .context("Operation to create source has failed.")
+                }
+                Err(error) => Err(error).context("Operation to get source has failed."),
+            }
+        }
+        _ => resolver.get_source(client, source.clone()),
+    }
 }
 
 pub fn get_files_in_directory(
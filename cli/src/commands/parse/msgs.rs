@@ -1,21 +1,31 @@
 use crate::{
     commands::DEFAULT_TRANSFORM_TAG,
-    parse::{get_files_in_directory, Statistics},
+    parse::{
+        get_files_in_directory, get_or_create_source, IdFrom, MessageDeduplicator, Statistics,
+    },
+    utils::{NameArg, Resolver},
 };
 use anyhow::{anyhow, Context, Result};
 use cfb::CompoundFile;
 use colored::Colorize;
-use log::error;
+use log::{error, info};
+use mailparse::MailHeaderMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::{io::Read, sync::Arc};
+use scoped_threadpool::Pool;
+use sha2::{Digest, Sha256};
+use std::{
+    io::Read,
+    sync::{mpsc::channel, Arc},
+    time::Instant,
+};
 
 use reinfer_client::{
     resources::{
         attachments::AttachmentMetadata,
         documents::{Document, RawEmail, RawEmailBody, RawEmailHeaders},
     },
-    Client, PropertyMap, SourceIdentifier, TransformTag,
+    Client, CommentId, PropertyMap, SourceIdentifier, TransformTag,
 };
 use std::{
     fs::File,
@@ -26,6 +36,7 @@ use structopt::StructOpt;
 use crate::{
     commands::ensure_uip_user_consents_to_ai_unit_charge,
     progress::{Options as ProgressOptions, Progress},
+    utils,
 };
 
 use super::upload_batch_of_documents;
@@ -57,11 +68,35 @@ pub struct ParseMsgArgs {
 
     #[structopt(short = "s", long = "source")]
     /// Source name or id
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
+
+    #[structopt(long = "create-source")]
+    /// Create the source if it doesn't exist. The source must be given by name.
+    create_source: bool,
+
+    #[structopt(long = "language", requires = "create-source")]
+    /// Language of the source, if it's created
+    language: Option<String>,
 
-    #[structopt(long = "transform-tag")]
-    /// Transform tag to use.
-    transform_tag: Option<TransformTag>,
+    #[structopt(long = "should-translate", requires = "create-source")]
+    /// Enable translation for the source, if it's created
+    should_translate: Option<bool>,
+
+    #[structopt(long = "id-from")]
+    /// How to derive comment ids, one of: message-id, content-hash. By default ids are assigned
+    /// by the API, so parsing the same msgs again creates new comments. Message-ID headers are
+    /// stable across exports of the same email. The content hash is the sha256 of the parsed
+    /// headers and body, so it may change if a new version of the CLI parses msgs differently.
+    id_from: Option<IdFrom>,
+
+    #[structopt(long = "dedup")]
+    /// Skip msgs with a Message-ID (or, without one, content) which was already seen in this
+    /// run, e.g. when parsing overlapping exports.
+    dedup: bool,
+
+    #[structopt(long = "transform-tag", default_value = DEFAULT_TRANSFORM_TAG)]
+    /// Transform tag to use, of the form <name>.<version>.<model>
+    transform_tag: TransformTag,
 
     #[structopt(short = "n", long = "no-charge")]
     /// Whether to attempt to bypass billing (internal only)
@@ -219,10 +254,20 @@ fn read_msg_to_document(path: &PathBuf) -> Result<Document> {
     })
 }
 
-pub fn parse(client: &Client, args: &ParseMsgArgs) -> Result<()> {
+pub fn parse(
+    client: &Client,
+    resolver: &Resolver,
+    args: &ParseMsgArgs,
+    pool: &mut Pool,
+) -> Result<()> {
     let ParseMsgArgs {
         directory,
         source,
+        create_source,
+        language,
+        should_translate,
+        id_from,
+        dedup,
         transform_tag,
         no_charge,
         yes,
@@ -232,61 +277,139 @@ pub fn parse(client: &Client, args: &ParseMsgArgs) -> Result<()> {
         ensure_uip_user_consents_to_ai_unit_charge(client.base_url())?;
     }
 
+    let start = Instant::now();
     let msg_paths = get_files_in_directory(directory, "msg", true)?;
     let statistics = Arc::new(Statistics::new());
     let _progress = get_progress_bar(msg_paths.len() as u64, &statistics);
-    let source = client.get_source(source.clone())?;
-    let transform_tag = transform_tag
-        .clone()
-        .unwrap_or(DEFAULT_TRANSFORM_TAG.clone());
-
-    let mut documents = Vec::new();
-    let mut errors = Vec::new();
-
-    let send = |documents: &mut Vec<Document>| -> Result<()> {
-        upload_batch_of_documents(
-            client,
-            &source,
-            documents,
-            &transform_tag,
-            *no_charge,
-            &statistics,
-        )?;
-        documents.clear();
-        Ok(())
-    };
-
-    for path in msg_paths {
-        match read_msg_to_document(&path.path()) {
-            Ok(document) => {
-                documents.push(document);
-
-                if documents.len() >= UPLOAD_BATCH_SIZE {
-                    send(&mut documents)?;
-                }
-                statistics.increment_processed();
-            }
-            Err(error) => {
-                errors.push(format!(
-                    "Failed to process file {}: {}",
-                    path.file_name().to_string_lossy(),
-                    error
-                ));
-                statistics.increment_failed();
-                statistics.increment_processed();
+    let source = get_or_create_source(
+        client,
+        resolver,
+        &resolver.resolve(source)?,
+        *create_source,
+        language.as_deref(),
+        *should_translate,
+    )?;
+
+    let deduplicator = dedup.then(MessageDeduplicator::default);
+    let (error_sender, error_receiver) = channel();
+    let (upload_error_sender, upload_error_receiver) = channel();
+    // Each thread reads and uploads one batch of msgs at a time.
+    let chunk_size = pool.thread_count() as usize * UPLOAD_BATCH_SIZE;
+    let (source, statistics, deduplicator) = (&source, &statistics, &deduplicator);
+    let (error_sender, upload_error_sender) = (&error_sender, &upload_error_sender);
+    for paths in msg_paths.chunks(chunk_size) {
+        pool.scoped(|scope| {
+            for batch in paths.chunks(UPLOAD_BATCH_SIZE) {
+                scope.execute(move || {
+                    let mut documents = Vec::with_capacity(batch.len());
+                    for path in batch {
+                        let document =
+                            read_msg_to_document(&path.path()).and_then(|mut document| {
+                                if let Some(id_from) = id_from {
+                                    document.comment_id = Some(comment_id(&document, *id_from)?);
+                                }
+                                Ok(document)
+                            });
+                        match document {
+                            Ok(document)
+                                if deduplicator.as_ref().is_some_and(|deduplicator| {
+                                    deduplicator.is_duplicate(deduplication_key(&document))
+                                }) =>
+                            {
+                                statistics.increment_skipped_duplicates();
+                            }
+                            Ok(document) => documents.push(document),
+                            Err(error) => {
+                                error_sender
+                                    .send(format!(
+                                        "Failed to process file {}: {}",
+                                        path.file_name().to_string_lossy(),
+                                        error
+                                    ))
+                                    .expect("Could not send error");
+                                statistics.increment_failed();
+                            }
+                        }
+                        statistics.increment_processed();
+                    }
+
+                    if documents.is_empty() {
+                        return;
+                    }
+                    if let Err(error) = upload_batch_of_documents(
+                        client,
+                        source,
+                        &documents,
+                        transform_tag,
+                        *no_charge,
+                        statistics,
+                    ) {
+                        upload_error_sender
+                            .send(error)
+                            .expect("Could not send error");
+                    }
+                });
             }
+        });
+
+        if let Ok(error) = upload_error_receiver.try_recv() {
+            return Err(error);
         }
     }
 
-    send(&mut documents)?;
-
-    for error in errors {
+    for error in error_receiver.try_iter() {
         error!("{}", error);
     }
+    if *dedup {
+        info!(
+            "Skipped {} duplicate msgs",
+            statistics.num_skipped_duplicates()
+        );
+    }
+    info!(
+        "{}",
+        utils::throughput_summary(start.elapsed(), &[(statistics.num_uploaded(), "emails")])
+    );
 
     Ok(())
 }
 
+/// The Message-ID of a parsed msg, falling back to a hash of its content for msgs without one.
+fn deduplication_key(document: &Document) -> String {
+    message_id(document).unwrap_or_else(|| content_hash(document))
+}
+
+fn comment_id(document: &Document, id_from: IdFrom) -> Result<CommentId> {
+    Ok(CommentId(match id_from {
+        IdFrom::MessageId => message_id(document).context("Msg has no Message-ID header")?,
+        IdFrom::ContentHash => content_hash(document),
+    }))
+}
+
+fn message_id(document: &Document) -> Option<String> {
+    match &document.raw_email.headers {
+        RawEmailHeaders::Raw(headers) => mailparse::parse_headers(headers.as_bytes())
+            .ok()
+            .and_then(|(headers, _)| headers.get_first_value("Message-ID")),
+        RawEmailHeaders::Parsed(_) => None,
+    }
+    .map(|message_id| message_id.trim().to_owned())
+    .filter(|message_id| !message_id.is_empty())
+}
+
+/// The sha256 of the headers and body of a parsed msg. The file name, which is kept as a user
+/// property, is left out so that the same msg exported twice has the same hash.
+fn content_hash(document: &Document) -> String {
+    let RawEmail { body, headers, .. } = &document.raw_email;
+    let body = match body {
+        RawEmailBody::Plain(body) | RawEmailBody::Html(body) => body,
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(headers).expect("Could not serialize headers"));
+    hasher.update(body.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
 fn get_progress_bar(total_bytes: u64, statistics: &Arc<Statistics>) -> Progress {
     Progress::new(
         move |statistic| {
@@ -366,4 +489,33 @@ mod tests {
 
         assert_eq!(expected_document, actual_document);
     }
+
+    #[test]
+    fn test_deduplication_key() {
+        let document = read_msg_to_document(&PathBuf::from("tests/samples/unicode.msg"))
+            .expect("Failed to read msg");
+        assert_eq!(
+            deduplication_key(&document),
+            "<AM9PR02MB66424EB36E9581626499575190DEA@AM9PR02MB6642.eurprd02.prod.outlook.com>"
+        );
+
+        let without_message_id = Document {
+            raw_email: RawEmail {
+                body: RawEmailBody::Plain("Hello".to_owned()),
+                headers: RawEmailHeaders::Raw("Subject: Hi\r\n".to_owned()),
+                attachments: Vec::new(),
+            },
+            user_properties: PropertyMap::new(),
+            comment_id: None,
+        };
+        let key = deduplication_key(&without_message_id);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, deduplication_key(&without_message_id.clone()));
+
+        assert_eq!(
+            comment_id(&without_message_id, IdFrom::ContentHash).unwrap(),
+            CommentId(key)
+        );
+        assert!(comment_id(&without_message_id, IdFrom::MessageId).is_err());
+    }
 }
@@ -0,0 +1,400 @@
+use anyhow::{anyhow, Error, Result};
+use serde_json::{json, Value as JsonValue};
+use std::{io, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    Comments,
+}
+
+impl FromStr for SchemaKind {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "comments" => Ok(SchemaKind::Comments),
+            _ => Err(anyhow!("unknown schema: '{}'", string)),
+        }
+    }
+}
+
+pub fn run(kind: SchemaKind) -> Result<()> {
+    let schema = match kind {
+        SchemaKind::Comments => comments_schema(),
+    };
+    serde_json::to_writer_pretty(io::stdout().lock(), &schema)?;
+    println!();
+    Ok(())
+}
+
+/// JSON schema of one line of the input to `re create comments`, following `NewAnnotatedComment`.
+fn comments_schema() -> JsonValue {
+    let translatable_text = json!({
+        "type": "object",
+        "required": ["text"],
+        "properties": {
+            "text": {"type": "string"},
+            "translated_from": {"type": "string"}
+        }
+    });
+
+    let rich_text = json!({
+        "type": "object",
+        "required": ["text"],
+        "properties": {
+            "text": {"type": "string"},
+            "translated_from": {"type": "string"},
+            "text_markup": {},
+            "translated_from_markup": {}
+        }
+    });
+
+    let label = json!({
+        "type": "object",
+        "required": ["name", "sentiment"],
+        "properties": {
+            "name": {"type": "string", "description": "Label name, with parts separated by ` > `"},
+            "sentiment": {"enum": ["positive", "negative"]},
+            "metadata": {"type": "object"}
+        }
+    });
+
+    let labels = json!({"type": "array", "items": {"$ref": "#/definitions/label"}});
+
+    let entity_span = json!({
+        "type": "object",
+        "required": ["content_part", "message_index", "utf16_byte_start", "utf16_byte_end"],
+        "properties": {
+            "content_part": {"type": "string"},
+            "message_index": {"type": "integer", "minimum": 0},
+            "utf16_byte_start": {"type": "integer", "minimum": 0},
+            "utf16_byte_end": {"type": "integer", "minimum": 0}
+        }
+    });
+
+    let entity = json!({
+        "type": "object",
+        "required": ["name", "formatted_value"],
+        "properties": {
+            "name": {"type": "string"},
+            "formatted_value": {"type": "string"},
+            "field_id": {"type": "string"},
+            "span": {"$ref": "#/definitions/entity_span"},
+            "spans": {"type": "array", "items": {"$ref": "#/definitions/entity_span"}}
+        },
+        "oneOf": [{"required": ["span"]}, {"required": ["spans"]}]
+    });
+
+    let entities = json!({"type": "array", "items": {"$ref": "#/definitions/entity"}});
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Comment",
+        "description": "One line of the jsonl input to `re create comments`",
+        "type": "object",
+        "required": ["comment"],
+        "properties": {
+            "comment": {
+                "type": "object",
+                "required": ["id", "timestamp", "messages"],
+                "properties": {
+                    "id": {"type": "string"},
+                    "thread_id": {"type": "string"},
+                    "timestamp": {"type": "string", "format": "date-time"},
+                    "messages": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["body"],
+                            "properties": {
+                                "body": rich_text,
+                                "subject": translatable_text,
+                                "signature": rich_text,
+                                "language": {"type": "string"},
+                                "from": {"type": "string"},
+                                "to": {"type": "array", "items": {"type": "string"}},
+                                "cc": {"type": "array", "items": {"type": "string"}},
+                                "bcc": {"type": "array", "items": {"type": "string"}},
+                                "sent_at": {"type": "string", "format": "date-time"}
+                            }
+                        }
+                    },
+                    "user_properties": {
+                        "type": "object",
+                        "description": "Property names prefixed with `string:` or `number:`",
+                        "patternProperties": {
+                            "^string:": {"type": "string"},
+                            "^number:": {"type": "number"}
+                        },
+                        "additionalProperties": false
+                    },
+                    "attachments": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["name", "size", "content_type"],
+                            "properties": {
+                                "name": {"type": "string"},
+                                "size": {"type": "integer", "minimum": 0},
+                                "content_type": {"type": "string"},
+                                "attachment_reference": {"type": "string"},
+                                "content_hash": {"type": "string"}
+                            }
+                        }
+                    }
+                }
+            },
+            "labelling": {
+                "oneOf": [
+                    {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["group"],
+                            "properties": {
+                                "group": {"type": "string"},
+                                "assigned": labels,
+                                "dismissed": labels
+                            }
+                        }
+                    },
+                    {
+                        "type": "object",
+                        "description": "Legacy labelling, for the default label group",
+                        "properties": {
+                            "assigned": labels,
+                            "dismissed": labels
+                        }
+                    }
+                ]
+            },
+            "entities": {
+                "type": "object",
+                "properties": {
+                    "assigned": entities,
+                    "dismissed": entities
+                }
+            },
+            "audio_path": {
+                "type": "string",
+                "description": "Path to an audio file to upload with the comment"
+            },
+            "moon_forms": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["group"],
+                    "properties": {
+                        "group": {"type": "string"},
+                        "assigned": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "required": ["label"],
+                                "properties": {
+                                    "label": {"$ref": "#/definitions/label"},
+                                    "captures": {
+                                        "type": "array",
+                                        "items": {
+                                            "type": "object",
+                                            "properties": {"fields": entities}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "definitions": {
+            "label": label,
+            "entity": entity,
+            "entity_span": entity_span
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonschema::JSONSchema;
+    use reinfer_client::NewAnnotatedComment;
+
+    const SAMPLES: &[(&str, &str)] = &[
+        ("audio", include_str!("../../tests/samples/audio.jsonl")),
+        ("basic", include_str!("../../tests/samples/basic.jsonl")),
+        (
+            "duplicates",
+            include_str!("../../tests/samples/duplicates.jsonl"),
+        ),
+        (
+            "labelling",
+            include_str!("../../tests/samples/labelling.jsonl"),
+        ),
+        (
+            "legacy_labelling",
+            include_str!("../../tests/samples/legacy_labelling.jsonl"),
+        ),
+        ("many", include_str!("../../tests/samples/many.jsonl")),
+        (
+            "moon_forms",
+            include_str!("../../tests/samples/moon_forms.jsonl"),
+        ),
+    ];
+
+    fn json_type(value: &JsonValue) -> &'static str {
+        match value {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "boolean",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+
+    /// The schema and its `oneOf` alternatives which could describe `value`, with `$ref`s
+    /// resolved.
+    fn candidate_schemas<'a>(
+        value: &JsonValue,
+        schema: &'a JsonValue,
+        root: &'a JsonValue,
+    ) -> Vec<&'a JsonValue> {
+        let resolve = |schema: &'a JsonValue| match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/definitions/");
+                &root["definitions"][name]
+            }
+            None => schema,
+        };
+        let schema = resolve(schema);
+        std::iter::once(schema)
+            .chain(
+                schema["oneOf"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(resolve),
+            )
+            .filter(|schema| {
+                schema["type"]
+                    .as_str()
+                    .is_none_or(|schema_type| schema_type == json_type(value))
+            })
+            .collect()
+    }
+
+    /// Check that every object key in `value`, at any depth, is described by `schema`.
+    fn assert_keys_in_schema(value: &JsonValue, schema: &JsonValue, root: &JsonValue, path: &str) {
+        let candidates = candidate_schemas(value, schema, root);
+        match value {
+            JsonValue::Object(object) => {
+                // Schemas without properties, such as for markup or label metadata, accept any
+                // object.
+                if candidates.iter().all(|schema| {
+                    schema.get("properties").is_none() && schema.get("patternProperties").is_none()
+                }) {
+                    return;
+                }
+                for (key, value) in object {
+                    let property_schema = candidates.iter().find_map(|schema| {
+                        schema["properties"].get(key).or_else(|| {
+                            schema["patternProperties"]
+                                .as_object()?
+                                .iter()
+                                .find(|(pattern, _)| {
+                                    key.starts_with(pattern.trim_start_matches('^'))
+                                })
+                                .map(|(_, schema)| schema)
+                        })
+                    });
+                    let property_schema = property_schema
+                        .unwrap_or_else(|| panic!("`{path}.{key}` is missing from the schema"));
+                    assert_keys_in_schema(value, property_schema, root, &format!("{path}.{key}"));
+                }
+            }
+            JsonValue::Array(items) => {
+                if let Some(items_schema) = candidates.iter().find_map(|schema| schema.get("items"))
+                {
+                    for (index, item) in items.iter().enumerate() {
+                        assert_keys_in_schema(
+                            item,
+                            items_schema,
+                            root,
+                            &format!("{path}[{index}]"),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn assert_valid(schema: &JSONSchema, value: &JsonValue, description: &str) {
+        if let Err(errors) = schema.validate(value) {
+            let errors: Vec<String> = errors.map(|error| error.to_string()).collect();
+            panic!("{description} does not match the schema: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn test_comments_schema_covers_new_annotated_comment() {
+        let comment: NewAnnotatedComment = serde_json::from_value(json!({
+            "comment": {
+                "id": "0123",
+                "thread_id": "thread",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "messages": [{
+                    "body": {"text": "Hello", "translated_from": "Bonjour"},
+                    "subject": {"text": "Hi"},
+                    "signature": {"text": "Bye"},
+                    "language": "en",
+                    "from": "a@x.com",
+                    "to": ["b@x.com"],
+                    "cc": ["c@x.com"],
+                    "bcc": ["d@x.com"],
+                    "sent_at": "2024-01-01T00:00:00Z"
+                }],
+                "user_properties": {"string:Channel": "Email"},
+                "attachments": [{"name": "a.pdf", "size": 1, "content_type": "application/pdf"}]
+            },
+            "labelling": [{
+                "group": "default",
+                "assigned": [{"name": "Billing", "sentiment": "positive"}]
+            }],
+            "entities": {"assigned": []},
+            "audio_path": "call.wav",
+            "moon_forms": [{"group": "default", "assigned": []}]
+        }))
+        .unwrap();
+        let comment = serde_json::to_value(comment).unwrap();
+
+        let schema = comments_schema();
+        assert_keys_in_schema(&comment, &schema, &schema, "");
+        assert_valid(
+            &JSONSchema::compile(&schema).unwrap(),
+            &comment,
+            "Round tripped comment",
+        );
+    }
+
+    #[test]
+    fn test_comments_schema_validates_samples() {
+        let schema = comments_schema();
+        let compiled_schema = JSONSchema::compile(&schema).unwrap();
+
+        for (name, sample) in SAMPLES {
+            for (index, line) in sample.lines().enumerate() {
+                let description = format!("Line {} of `{name}.jsonl`", index + 1);
+                let value: JsonValue = serde_json::from_str(line).unwrap();
+                assert_valid(&compiled_schema, &value, &description);
+
+                let comment: NewAnnotatedComment = serde_json::from_value(value).unwrap();
+                let comment = serde_json::to_value(comment).unwrap();
+                assert_keys_in_schema(&comment, &schema, &schema, "");
+                assert_valid(&compiled_schema, &comment, &description);
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
 use anyhow::{Context, Result};
 use log::info;
 use reinfer_client::{Client, DatasetIdentifier, SourceId, SourceIdentifier, UpdateDataset};
@@ -9,7 +10,7 @@ use structopt::StructOpt;
 pub struct UpdateDatasetArgs {
     #[structopt(name = "dataset")]
     /// Name or id of the dataset to delete
-    dataset: DatasetIdentifier,
+    dataset: NameArg<DatasetIdentifier>,
 
     #[structopt(long = "title")]
     /// Set the title of the dataset
@@ -21,10 +22,15 @@ pub struct UpdateDatasetArgs {
 
     #[structopt(short = "s", long = "source")]
     /// Names or ids of the sources in the dataset
-    sources: Option<Vec<SourceIdentifier>>,
+    sources: Option<Vec<NameArg<SourceIdentifier>>>,
 }
 
-pub fn update(client: &Client, args: &UpdateDatasetArgs, printer: &Printer) -> Result<()> {
+pub fn update(
+    client: &Client,
+    resolver: &Resolver,
+    args: &UpdateDatasetArgs,
+    printer: &Printer,
+) -> Result<()> {
     let UpdateDatasetArgs {
         dataset,
         title,
@@ -37,16 +43,16 @@ pub fn update(client: &Client, args: &UpdateDatasetArgs, printer: &Printer) -> R
         .map::<Result<Vec<SourceId>>, _>(|sources| {
             sources
                 .iter()
-                .map(|source| Ok(client.get_source(source.clone())?.id))
+                .map(|source| Ok(resolver.get_source(client, resolver.resolve(source)?)?.id))
                 .collect()
         })
         .transpose()
         .context("Operation to get sources failed")?;
 
-    let dataset_full_name = match dataset {
-        DatasetIdentifier::FullName(name) => name.to_owned(),
-        dataset @ DatasetIdentifier::Id(_) => client
-            .get_dataset(dataset.to_owned())
+    let dataset_full_name = match resolver.resolve(dataset)? {
+        DatasetIdentifier::FullName(name) => name,
+        dataset @ DatasetIdentifier::Id(_) => resolver
+            .get_dataset(client, dataset)
             .context("Fetching dataset id.")?
             .full_name(),
     };
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use dialoguer::Confirm;
+use log::info;
+use reinfer_client::{resources::integration::NewIntegration, Client, IntegrationFullName};
+use serde_json::Value;
+use structopt::StructOpt;
+
+use crate::{commands::create::integrations::print_integration_diff, printer::Printer};
+
+#[derive(Debug, StructOpt)]
+pub struct UpdateIntegrationArgs {
+    #[structopt(name = "name")]
+    /// Full name of the integration
+    name: IntegrationFullName,
+
+    #[structopt(long = "from-json", parse(from_os_str))]
+    /// Path to a JSON file with the parts of the integration to change. It is applied to the
+    /// existing integration as a JSON merge patch, i.e. objects are merged recursively, `null`
+    /// removes a field and any other value replaces it
+    from_json: Option<PathBuf>,
+
+    #[structopt(long = "title")]
+    /// Set the title of the integration
+    title: Option<String>,
+
+    #[structopt(long = "enabled", parse(try_from_str))]
+    /// Enable (--enabled=true) or disable (--enabled=false) the integration
+    enabled: Option<bool>,
+
+    #[structopt(long)]
+    /// Don't ask for confirmation before applying the changes
+    yes: bool,
+}
+
+pub fn update(client: &Client, args: &UpdateIntegrationArgs, printer: &Printer) -> Result<()> {
+    let UpdateIntegrationArgs {
+        name,
+        from_json,
+        title,
+        enabled,
+        yes,
+    } = args;
+
+    let existing = client
+        .get_integration(name)
+        .context("Operation to get integration has failed.")?;
+    let mut integration =
+        serde_json::to_value(existing).context("Could not serialise integration.")?;
+    let old_integration: NewIntegration = serde_json::from_value(integration.clone())
+        .context("Could not convert existing integration")?;
+
+    if let Some(path) = from_json {
+        let patch_str = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not open file `{}`", path.display()))?;
+        let patch: Value = serde_json::from_str(&patch_str)
+            .with_context(|| format!("Could not parse JSON in `{}`", path.display()))?;
+        merge_patch(&mut integration, patch);
+    }
+    if let Some(title) = title {
+        integration["title"] = Value::String(title.clone());
+    }
+    if let Some(enabled) = enabled {
+        integration["enabled"] = Value::Bool(*enabled);
+    }
+
+    let new_integration: NewIntegration = serde_json::from_value(integration)
+        .context("The integration is not valid after applying the changes")?;
+
+    if new_integration == old_integration {
+        bail!("No changes to apply to integration `{}`", name.0)
+    }
+
+    print_integration_diff(&old_integration, &new_integration)?;
+
+    if !yes
+        && !Confirm::new()
+            .with_prompt(
+                "Above are the changes that are about to be made, do you want to continue?",
+            )
+            .interact()?
+    {
+        bail!("Operation aborted by user")
+    }
+
+    let integration = client
+        .post_integration(name, &new_integration)
+        .context("Operation to update integration has failed.")?
+        .integration;
+    info!("Integration `{}` updated successfully", name.0);
+    printer.print_resources(&[integration])
+}
+
+/// Apply a JSON merge patch (RFC 7396) to a value.
+fn merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Default::default());
+            }
+            let target = target.as_object_mut().expect("target is an object");
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        patch => *target = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch() {
+        let mut integration = json!({
+            "title": "Old",
+            "enabled": true,
+            "configuration": {"mailboxes": [{"email": "a@b.com"}], "connection": {"x": 1}},
+        });
+        merge_patch(
+            &mut integration,
+            json!({
+                "title": "New",
+                "configuration": {"mailboxes": [], "connection": null},
+            }),
+        );
+        assert_eq!(
+            integration,
+            json!({
+                "title": "New",
+                "enabled": true,
+                "configuration": {"mailboxes": []},
+            })
+        );
+    }
+}
@@ -1,15 +1,17 @@
 mod dataset;
+mod integration;
 mod project;
 mod source;
 mod users;
 
 use self::{
-    dataset::UpdateDatasetArgs, project::UpdateProjectArgs, source::UpdateSourceArgs,
-    users::UpdateUsersArgs,
+    dataset::UpdateDatasetArgs, integration::UpdateIntegrationArgs, project::UpdateProjectArgs,
+    source::UpdateSourceArgs, users::UpdateUsersArgs,
 };
-use crate::printer::Printer;
+use crate::{printer::Printer, utils::Resolver};
 use anyhow::Result;
 use reinfer_client::Client;
+use scoped_threadpool::Pool;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -29,13 +31,28 @@ pub enum UpdateArgs {
     #[structopt(name = "users")]
     /// Update existing users
     Users(UpdateUsersArgs),
+
+    #[structopt(name = "integration")]
+    /// Update an existing integration
+    Integration(UpdateIntegrationArgs),
 }
 
-pub fn run(update_args: &UpdateArgs, client: Client, printer: &Printer) -> Result<()> {
+pub fn run(
+    update_args: &UpdateArgs,
+    client: Client,
+    resolver: &Resolver,
+    printer: &Printer,
+    pool: &mut Pool,
+) -> Result<()> {
     match update_args {
-        UpdateArgs::Source(source_args) => source::update(&client, source_args, printer),
-        UpdateArgs::Dataset(dataset_args) => dataset::update(&client, dataset_args, printer),
+        UpdateArgs::Source(source_args) => source::update(&client, resolver, source_args, printer),
+        UpdateArgs::Dataset(dataset_args) => {
+            dataset::update(&client, resolver, dataset_args, printer)
+        }
         UpdateArgs::Project(project_args) => project::update(&client, project_args, printer),
-        UpdateArgs::Users(users_args) => users::update(&client, users_args),
+        UpdateArgs::Users(users_args) => users::update(&client, users_args, pool),
+        UpdateArgs::Integration(integration_args) => {
+            integration::update(&client, integration_args, printer)
+        }
     }
 }
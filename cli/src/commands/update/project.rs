@@ -1,7 +1,10 @@
 use crate::printer::Printer;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use log::info;
-use reinfer_client::{Client, ProjectName, UpdateProject};
+use reinfer_client::{
+    Client, ProjectName, ProjectPermission, UpdateProject, UpdateUser, User, UserId,
+};
+use std::collections::HashMap;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -17,6 +20,18 @@ pub struct UpdateProjectArgs {
     #[structopt(long = "description")]
     /// Set the description of the project
     description: Option<String>,
+
+    #[structopt(long = "add-user", requires = "permissions")]
+    /// Id or email of a user to add to the project, with the permissions given by --permission
+    add_users: Vec<String>,
+
+    #[structopt(long = "permission")]
+    /// Project permission to give to the users in --add-user
+    permissions: Vec<ProjectPermission>,
+
+    #[structopt(long = "remove-user")]
+    /// Id or email of a user to remove from the project
+    remove_users: Vec<String>,
 }
 
 pub fn update(client: &Client, args: &UpdateProjectArgs, printer: &Printer) -> Result<()> {
@@ -24,18 +39,196 @@ pub fn update(client: &Client, args: &UpdateProjectArgs, printer: &Printer) -> R
         name,
         title,
         description,
+        add_users,
+        permissions,
+        remove_users,
     } = args;
 
-    let project = client
-        .update_project(
-            name,
-            UpdateProject {
-                title: title.as_deref(),
-                description: description.as_deref(),
-            },
-        )
-        .context("Operation to update a project has failed")?;
-    info!("Project `{}` updated successfully", project.name.0,);
+    let project = if title.is_some()
+        || description.is_some()
+        || (add_users.is_empty() && remove_users.is_empty())
+    {
+        let project = client
+            .update_project(
+                name,
+                UpdateProject {
+                    title: title.as_deref(),
+                    description: description.as_deref(),
+                },
+            )
+            .context("Operation to update a project has failed")?;
+        info!("Project `{}` updated successfully", project.name.0,);
+        project
+    } else {
+        client
+            .get_project(name)
+            .context("Operation to get a project has failed")?
+    };
+
+    if !add_users.is_empty() || !remove_users.is_empty() {
+        let users = client
+            .get_users()
+            .context("Operation to list users has failed.")?;
+        let updates = membership_updates(&users, name, add_users, permissions, remove_users)?;
+
+        for (user_id, update) in updates {
+            client
+                .post_user(&user_id, update)
+                .with_context(|| format!("Operation to update user `{}` has failed", user_id.0))?;
+        }
+        info!(
+            "Project `{}` members updated successfully [{} added | {} removed]",
+            name.0,
+            add_users.len(),
+            remove_users.len()
+        );
+    }
+
     printer.print_resources(&[project])?;
     Ok(())
 }
+
+fn find_user<'a>(users: &'a [User], identifier: &str) -> Result<&'a User> {
+    users
+        .iter()
+        .find(|user| user.id.0 == identifier || user.email.0.eq_ignore_ascii_case(identifier))
+        .ok_or_else(|| anyhow!("Could not find a user with id or email `{}`", identifier))
+}
+
+/// Computes the user updates needed to add and remove members of `project`. Each update contains
+/// the full set of project permissions of the user, with only `project` changed.
+fn membership_updates(
+    users: &[User],
+    project: &ProjectName,
+    add_users: &[String],
+    permissions: &[ProjectPermission],
+    remove_users: &[String],
+) -> Result<Vec<(UserId, UpdateUser)>> {
+    let mut project_permissions: HashMap<UserId, HashMap<ProjectName, Vec<ProjectPermission>>> =
+        HashMap::new();
+    let mut current_permissions = |user: &User| {
+        project_permissions
+            .entry(user.id.clone())
+            .or_insert_with(|| {
+                user.project_permissions
+                    .iter()
+                    .map(|(project, permissions)| {
+                        (project.clone(), permissions.iter().cloned().collect())
+                    })
+                    .collect()
+            })
+            .clone()
+    };
+
+    let mut updated = HashMap::new();
+    for identifier in remove_users {
+        let user = find_user(users, identifier)?;
+        let mut user_permissions = current_permissions(user);
+        if user_permissions.remove(project).is_none() {
+            bail!(
+                "User `{}` is not a member of project `{}`",
+                identifier,
+                project.0
+            );
+        }
+        updated.insert(user.id.clone(), user_permissions);
+    }
+    for identifier in add_users {
+        let user = find_user(users, identifier)?;
+        ensure!(
+            !updated.contains_key(&user.id),
+            "User `{}` cannot be both added to and removed from the project",
+            identifier
+        );
+        let mut user_permissions = current_permissions(user);
+        user_permissions.insert(project.clone(), permissions.to_vec());
+        updated.insert(user.id.clone(), user_permissions);
+    }
+
+    // Projects must keep at least one user who can manage their members
+    let has_owner = users.iter().any(|user| {
+        let permissions = match updated.get(&user.id) {
+            Some(user_permissions) => user_permissions.get(project).cloned(),
+            None => user
+                .project_permissions
+                .get(project)
+                .map(|permissions| permissions.iter().cloned().collect()),
+        };
+        permissions.is_some_and(|permissions| permissions.contains(&ProjectPermission::UsersWrite))
+    });
+    ensure!(
+        has_owner,
+        "Project `{}` would be left without a user with the `{}` permission",
+        project.0,
+        ProjectPermission::UsersWrite
+    );
+
+    Ok(updated
+        .into_iter()
+        .map(|(user_id, organisation_permissions)| {
+            (
+                user_id,
+                UpdateUser {
+                    organisation_permissions: Some(organisation_permissions),
+                    global_permissions: None,
+                },
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user(id: &str, email: &str, permissions: &[&str]) -> User {
+        serde_json::from_value(json!({
+            "id": id,
+            "username": id,
+            "email": email,
+            "created": "2024-01-01T00:00:00Z",
+            "global_permissions": [],
+            "organisation_permissions": {"project": permissions},
+            "sso_global_permissions": [],
+            "verified": true
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_membership_updates() {
+        let project = ProjectName("project".to_owned());
+        let users = vec![
+            user("01", "owner@x.com", &["users-write", "voc"]),
+            user("02", "member@x.com", &["voc"]),
+            user("03", "new@x.com", &[]),
+        ];
+        let read = vec![ProjectPermission::DatasetsRead];
+
+        let updates = membership_updates(
+            &users,
+            &project,
+            &["NEW@x.com".to_owned()],
+            &read,
+            &["02".to_owned()],
+        )
+        .unwrap();
+        let updates: HashMap<_, _> = updates.into_iter().collect();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(
+            updates[&UserId("03".to_owned())].organisation_permissions,
+            Some(HashMap::from([(project.clone(), read.clone())]))
+        );
+        assert_eq!(
+            updates[&UserId("02".to_owned())].organisation_permissions,
+            Some(HashMap::new())
+        );
+
+        let error = membership_updates(&users, &project, &[], &[], &["owner@x.com".to_owned()])
+            .unwrap_err();
+        assert!(error.to_string().contains("without a user"));
+
+        assert!(membership_updates(&users, &project, &[], &[], &["04".to_owned()]).is_err());
+    }
+}
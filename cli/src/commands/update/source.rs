@@ -1,4 +1,5 @@
 use crate::printer::Printer;
+use crate::utils::{NameArg, Resolver};
 use anyhow::{Context, Result};
 use log::info;
 use reinfer_client::{BucketIdentifier, Client, SourceIdentifier, TransformTag, UpdateSource};
@@ -8,7 +9,7 @@ use structopt::StructOpt;
 pub struct UpdateSourceArgs {
     #[structopt(name = "source")]
     /// Id or full name of the source to update
-    source: SourceIdentifier,
+    source: NameArg<SourceIdentifier>,
 
     #[structopt(long = "title")]
     /// Set the title of the source
@@ -24,14 +25,19 @@ pub struct UpdateSourceArgs {
 
     #[structopt(long = "bucket")]
     /// Bucket to pull emails from.
-    bucket: Option<BucketIdentifier>,
+    bucket: Option<NameArg<BucketIdentifier>>,
 
     #[structopt(long = "transform-tag")]
     /// Set the transform tag of the source
     transform_tag: Option<TransformTag>,
 }
 
-pub fn update(client: &Client, args: &UpdateSourceArgs, printer: &Printer) -> Result<()> {
+pub fn update(
+    client: &Client,
+    resolver: &Resolver,
+    args: &UpdateSourceArgs,
+    printer: &Printer,
+) -> Result<()> {
     let UpdateSourceArgs {
         source,
         title,
@@ -41,7 +47,11 @@ pub fn update(client: &Client, args: &UpdateSourceArgs, printer: &Printer) -> Re
         transform_tag,
     } = args;
 
-    let bucket_id = match bucket.to_owned() {
+    let bucket_id = match bucket
+        .as_ref()
+        .map(|bucket| resolver.resolve(bucket))
+        .transpose()?
+    {
         Some(BucketIdentifier::Id(bucket_id)) => Some(bucket_id),
         Some(full_name @ BucketIdentifier::FullName(_)) => Some(
             client
@@ -52,10 +62,10 @@ pub fn update(client: &Client, args: &UpdateSourceArgs, printer: &Printer) -> Re
         None => None,
     };
 
-    let source_full_name = match source.to_owned() {
+    let source_full_name = match resolver.resolve(source)? {
         SourceIdentifier::FullName(name) => name,
-        source @ SourceIdentifier::Id(_) => client
-            .get_source(source)
+        source @ SourceIdentifier::Id(_) => resolver
+            .get_source(client, source)
             .context("Fetching source id.")?
             .full_name(),
     };
@@ -68,8 +78,8 @@ pub fn update(client: &Client, args: &UpdateSourceArgs, printer: &Printer) -> Re
                 description: description.as_deref(),
                 should_translate: *should_translate,
                 bucket_id,
-                sensitive_properties: None,
                 transform_tag: transform_tag.as_ref(),
+                ..Default::default()
             },
         )
         .context("Operation to update a source has failed")?;
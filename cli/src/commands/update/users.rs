@@ -1,13 +1,19 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use colored::Colorize;
-use log::info;
-use reinfer_client::{Client, UpdateUser, UserId};
+use log::{error, info};
+use reinfer_client::{
+    Client, GlobalPermission, ProjectName, ProjectPermission, UpdateUser, UserEmail, UserId,
+};
+use scoped_threadpool::Pool;
 use std::{
+    collections::HashMap,
     fs::{self, File},
     io::{self, BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    str::FromStr,
     sync::{
         atomic::{AtomicUsize, Ordering},
+        mpsc::channel,
         Arc,
     },
 };
@@ -21,12 +27,22 @@ pub struct UpdateUsersArgs {
     /// Path to JSON file with users. If not specified, stdin will be used.
     input_file: Option<PathBuf>,
 
+    #[structopt(long = "from-csv", parse(from_os_str), conflicts_with = "input-file")]
+    /// Path to a CSV file with rows of `email,permission,...`, instead of JSON. Each row replaces
+    /// the permissions of the user with that email by the listed ones, given as
+    /// `<project>:<permission>` for project permissions and `<permission>` for global ones.
+    csv_file: Option<PathBuf>,
+
     #[structopt(long)]
     /// Don't display a progress bar (only applicable when --file is used).
     no_progress: bool,
 }
 
-pub fn update(client: &Client, args: &UpdateUsersArgs) -> Result<()> {
+pub fn update(client: &Client, args: &UpdateUsersArgs, pool: &mut Pool) -> Result<()> {
+    if let Some(csv_file) = &args.csv_file {
+        return update_users_from_csv(client, csv_file, pool);
+    }
+
     let statistics = match &args.input_file {
         Some(input_file) => {
             info!("Processing users from file `{}`", input_file.display(),);
@@ -107,6 +123,133 @@ fn update_users_from_reader(
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+struct CsvUserRow {
+    email: UserEmail,
+    update: UpdateUser,
+}
+
+fn parse_csv_row(record: &csv::StringRecord) -> Result<CsvUserRow> {
+    let mut fields = record
+        .iter()
+        .map(str::trim)
+        .filter(|field| !field.is_empty());
+    let email = fields.next().context("Row is missing an email")?;
+
+    let mut organisation_permissions: HashMap<ProjectName, Vec<ProjectPermission>> = HashMap::new();
+    let mut global_permissions = Vec::new();
+    for permission in fields {
+        match permission.split_once(':') {
+            Some((project, permission)) => {
+                ensure!(
+                    ProjectPermission::NAMES.contains(&permission),
+                    "Unknown project permission `{}`, expected one of: {}",
+                    permission,
+                    ProjectPermission::NAMES.join(", ")
+                );
+                organisation_permissions
+                    .entry(ProjectName::from_str(project)?)
+                    .or_default()
+                    .push(ProjectPermission::from_str(permission)?);
+            }
+            None => {
+                let global_permission = GlobalPermission::from_str(permission)?;
+                ensure!(
+                    !matches!(global_permission, GlobalPermission::Unknown(_)),
+                    "Unknown global permission `{}`",
+                    permission
+                );
+                global_permissions.push(global_permission);
+            }
+        }
+    }
+
+    Ok(CsvUserRow {
+        email: UserEmail(email.to_owned()),
+        update: UpdateUser {
+            organisation_permissions: Some(organisation_permissions),
+            global_permissions: Some(global_permissions),
+        },
+    })
+}
+
+fn read_csv_rows(path: &Path) -> Result<Vec<CsvUserRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Could not open file `{}`", path.display()))?;
+
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let row_number = index + 1;
+        let record =
+            record.with_context(|| format!("Could not read row {row_number} of the CSV"))?;
+        if row_number == 1 && record.get(0).is_some_and(|field| field.trim() == "email") {
+            continue;
+        }
+        rows.push(
+            parse_csv_row(&record)
+                .with_context(|| format!("Could not parse row {row_number} of the CSV"))?,
+        );
+    }
+    Ok(rows)
+}
+
+fn update_users_from_csv(client: &Client, path: &Path, pool: &mut Pool) -> Result<()> {
+    info!("Processing users from CSV file `{}`", path.display());
+    let rows = read_csv_rows(path)?;
+
+    let user_ids: HashMap<String, UserId> = client
+        .get_users()
+        .context("Operation to list users has failed.")?
+        .into_iter()
+        .map(|user| (user.email.0.to_lowercase(), user.id))
+        .collect();
+
+    let (sender, receiver) = channel();
+    pool.scoped(|scope| {
+        for row in &rows {
+            let sender = sender.clone();
+            let user_ids = &user_ids;
+            scope.execute(move || {
+                let result = match user_ids.get(&row.email.0.to_lowercase()) {
+                    Some(user_id) => client
+                        .post_user(user_id, row.update.clone())
+                        .map(|_| ())
+                        .context("Could not update user"),
+                    None => Err(anyhow!("No user with this email")),
+                };
+                sender
+                    .send((&row.email, result))
+                    .expect("Could not send result");
+            });
+        }
+    });
+    drop(sender);
+
+    let mut num_updated = 0;
+    let mut num_failed = 0;
+    for (email, result) in receiver.iter() {
+        match result {
+            Ok(()) => {
+                info!("Updated user `{}`", email.0);
+                num_updated += 1;
+            }
+            Err(error) => {
+                error!("Failed to update user `{}`: {:#}", email.0, error);
+                num_failed += 1;
+            }
+        }
+    }
+
+    info!("Successfully updated {num_updated} users. {num_failed} failed");
+    if num_failed > 0 {
+        bail!("Failed to update {num_failed} users");
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct Statistics {
     bytes_read: AtomicUsize,
@@ -157,3 +300,40 @@ fn progress_bar(total_bytes: u64, statistics: &Arc<Statistics>) -> Progress {
         ProgressOptions { bytes_units: true },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_row() {
+        let record = csv::StringRecord::from(vec![
+            "user@x.com",
+            "project:voc-readonly",
+            " project:users-write",
+            "root",
+            "",
+        ]);
+        let row = parse_csv_row(&record).unwrap();
+        assert_eq!(row.email, UserEmail("user@x.com".to_owned()));
+        assert_eq!(
+            row.update.organisation_permissions,
+            Some(HashMap::from([(
+                ProjectName("project".to_owned()),
+                vec![
+                    ProjectPermission::from_str("voc-readonly").unwrap(),
+                    ProjectPermission::from_str("users-write").unwrap()
+                ]
+            )]))
+        );
+        assert_eq!(
+            row.update.global_permissions,
+            Some(vec![GlobalPermission::Root])
+        );
+
+        let record = csv::StringRecord::from(vec!["user@x.com", "project:not-a-permission"]);
+        assert!(parse_csv_row(&record).is_err());
+        let record = csv::StringRecord::from(vec!["user@x.com", "not-a-permission"]);
+        assert!(parse_csv_row(&record).is_err());
+    }
+}
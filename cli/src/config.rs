@@ -44,7 +44,7 @@ impl ReinferConfig {
             if self
                 .current_context
                 .as_ref()
-                .map_or(false, |current_context| current_context == name)
+                .is_some_and(|current_context| current_context == name)
             {
                 self.current_context = None
             }
@@ -87,6 +87,8 @@ pub struct ContextConfig {
     pub token: Option<String>,
     pub accept_invalid_certificates: bool,
     pub proxy: Option<Url>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proxy_exclusions: Vec<String>,
 }
 
 pub fn read_reinfer_config(path: impl AsRef<Path>) -> Result<ReinferConfig> {
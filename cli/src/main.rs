@@ -8,28 +8,32 @@ mod thousands;
 mod utils;
 
 use anyhow::{anyhow, Context, Result};
-use log::{error, warn};
+use log::{debug, error, warn};
 use reinfer_client::{
     retry::{RetryConfig, RetryStrategy},
-    Client, Config as ClientConfig, Token, DEFAULT_ENDPOINT,
+    Client, Config as ClientConfig, PrintCurl, SplitConfig, Token, DEFAULT_ENDPOINT,
+    DEFAULT_MAX_RESPONSE_BYTES,
 };
 use scoped_threadpool::Pool;
 use std::{env, fs, io, path::PathBuf, process};
 use structopt::{clap::Shell as ClapShell, StructOpt};
 
 use crate::{
-    args::{Args, Command, Shell},
-    commands::{config as config_command, create, delete, get, parse, update},
+    args::{Args, Command, ErrorOutput, LogFormat, Shell},
+    commands::{config as config_command, create, delete, get, parse, schema, update},
     config::ReinferConfig,
     printer::Printer,
+    utils::Resolver,
 };
 
 const NUM_THREADS_ENV_VARIABLE_NAME: &str = "REINFER_CLI_NUM_THREADS";
+const LOG_FORMAT_ENV_VARIABLE_NAME: &str = "REINFER_CLI_LOG_FORMAT";
+const USER_AGENT_ENV_VARIABLE_NAME: &str = "REINFER_CLI_USER_AGENT";
 
 fn run(args: Args) -> Result<()> {
     let config_path = find_configuration(&args)?;
     let config = config::read_reinfer_config(&config_path)?;
-    let printer = Printer::new(args.output);
+    let printer = Printer::new(args.output, args.columns.clone());
 
     let number_of_threads = if let Ok(num_threads_env_var_str) =
         env::var(NUM_THREADS_ENV_VARIABLE_NAME)
@@ -42,6 +46,7 @@ fn run(args: Args) -> Result<()> {
     };
 
     let mut pool = Pool::new(number_of_threads);
+    let resolver = Resolver::new(args.owner.clone());
 
     match &args.command {
         Command::Config { config_args } => {
@@ -59,24 +64,37 @@ fn run(args: Args) -> Result<()> {
         Command::Get { get_args } => get::run(
             get_args,
             client_from_args(&args, &config)?,
+            &resolver,
             &printer,
             &mut pool,
         ),
-        Command::Delete { delete_args } => {
-            delete::run(delete_args, client_from_args(&args, &config)?)
-        }
+        Command::Delete { delete_args } => delete::run(
+            delete_args,
+            client_from_args(&args, &config)?,
+            &resolver,
+            &mut pool,
+        ),
         Command::Create { create_args } => create::run(
             create_args,
             client_from_args(&args, &config)?,
+            &resolver,
             &printer,
             &mut pool,
         ),
-        Command::Update { update_args } => {
-            update::run(update_args, client_from_args(&args, &config)?, &printer)
-        }
-        Command::Parse { parse_args } => {
-            parse::run(parse_args, client_from_args(&args, &config)?, &mut pool)
-        }
+        Command::Update { update_args } => update::run(
+            update_args,
+            client_from_args(&args, &config)?,
+            &resolver,
+            &printer,
+            &mut pool,
+        ),
+        Command::Parse { parse_args } => parse::run(
+            parse_args,
+            client_from_args(&args, &config)?,
+            &resolver,
+            &mut pool,
+        ),
+        Command::Schema { kind } => schema::run(*kind),
     }
 }
 
@@ -125,6 +143,10 @@ fn client_from_args(args: &Args, config: &ReinferConfig) -> Result<Client> {
         .clone()
         .or_else(|| current_context.and_then(|context| context.proxy.clone()));
 
+    let proxy_exclusions = current_context
+        .map(|context| context.proxy_exclusions.clone())
+        .unwrap_or_default();
+
     // Retry everything but the very first request.
     // Retry wait schedule is [5s, 10s, 20s, fail]. (Plus the time for each attempt to timeout.)
     let retry_config = RetryConfig {
@@ -139,7 +161,37 @@ fn client_from_args(args: &Args, config: &ReinferConfig) -> Result<Client> {
         token,
         accept_invalid_certificates,
         proxy,
+        proxy_exclusions,
         retry_config: Some(retry_config),
+        print_curl: if args.print_curl_only {
+            PrintCurl::Only
+        } else if args.print_curl {
+            PrintCurl::Yes
+        } else {
+            PrintCurl::No
+        },
+        cache_dir: args.cache_dir.clone(),
+        split_config: SplitConfig {
+            base_wait: std::time::Duration::from_millis(args.split_wait_ms),
+            max_split_size: args.max_split_size,
+        },
+        user_agent: Some(
+            args.user_agent
+                .clone()
+                .or_else(|| env::var(USER_AGENT_ENV_VARIABLE_NAME).ok())
+                .unwrap_or_else(|| concat!("reinfer-cli/", env!("CARGO_PKG_VERSION")).to_owned()),
+        ),
+        pool_max_idle_per_host: args.pool_max_idle_per_host,
+        pool_idle_timeout: args
+            .pool_idle_timeout_secs
+            .map(std::time::Duration::from_secs),
+        http1_only: args.http1_only,
+        attachment_timeout: args
+            .attachment_timeout_seconds
+            .map(std::time::Duration::from_secs),
+        max_response_bytes: args
+            .max_response_bytes
+            .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES),
     })
     .context("Failed to initialise the HTTP client.")?;
 
@@ -163,7 +215,30 @@ fn check_if_context_is_a_required_field(
         ));
     }
 
-    let current_user = client.get_current_user()?;
+    // The current user can't be fetched without sending a request.
+    if args.print_curl_only || args.no_preflight {
+        return Ok(());
+    }
+
+    // This is the first request of each command, so it also checks that the endpoint is reachable
+    let current_user = client.get_current_user().map_err(|error| {
+        let error = anyhow::Error::new(error);
+        let unreachable = error.chain().any(|cause| {
+            cause
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(|error| error.is_connect() || error.is_timeout())
+        });
+        if unreachable {
+            error
+                .context(format!("Cannot reach endpoint `{}`.", client.base_url()))
+                .context(
+                    "Pass --endpoint or -c <context> to use a different endpoint, or \
+                     --no-preflight to skip this check.",
+                )
+        } else {
+            error
+        }
+    })?;
 
     if DOMAINS_THAT_REQUIRE_CONTEXT
         .iter()
@@ -205,19 +280,43 @@ fn find_configuration(args: &Args) -> Result<PathBuf> {
 
 fn main() {
     let args = Args::from_args();
-    utils::init_env_logger(args.verbose);
+    let (log_format, invalid_log_format) = match args.log_format {
+        Some(log_format) => (log_format, None),
+        None => match env::var(LOG_FORMAT_ENV_VARIABLE_NAME).map(|value| value.parse()) {
+            Ok(Ok(log_format)) => (log_format, None),
+            Ok(Err(error)) => (LogFormat::default(), Some(error)),
+            Err(_) => (LogFormat::default(), None),
+        },
+    };
+    utils::init_env_logger(args.verbose, args.quiet, log_format);
+    if let Some(error) = invalid_log_format {
+        warn!("Ignoring environment variable {LOG_FORMAT_ENV_VARIABLE_NAME}: {error}");
+    }
+    if args.quiet || log_format == LogFormat::Json {
+        progress::disable_progress_bars();
+    }
+    if let Some(owner) = &args.owner {
+        debug!("Names without an owner are assumed to be in project `{owner}`");
+    }
 
+    let error_output = args.error_output;
     if let Err(error) = run(args) {
-        error!("An error occurred:");
-        for cause in error.chain() {
-            error!(" |- {}", cause);
-        }
+        let exit_code = utils::ExitCode::for_error(&error) as i32;
+        match error_output {
+            ErrorOutput::Human => {
+                error!("An error occurred:");
+                for cause in error.chain() {
+                    error!(" |- {}", cause);
+                }
 
-        #[cfg(feature = "backtrace")]
-        {
-            error!("{}", error.backtrace());
+                #[cfg(feature = "backtrace")]
+                {
+                    error!("{}", error.backtrace());
+                }
+            }
+            ErrorOutput::Json => println!("{}", utils::error_to_json(&error, exit_code)),
         }
 
-        process::exit(1);
+        process::exit(exit_code);
     }
 }
@@ -1,6 +1,7 @@
 use super::thousands::Thousands;
 use colored::Colorize;
-use prettytable::{format, row, Row, Table};
+use ordered_float::NotNan;
+use prettytable::{format, row, Cell, Row, Table};
 use reinfer_client::{
     resources::{
         audit::PrintableAuditEvent,
@@ -9,8 +10,10 @@ use reinfer_client::{
         dataset::DatasetAndStats,
         integration::Integration,
         quota::Quota,
+        statistics::LabelCount,
+        validation::{LabelValidation, ValidationResponse},
     },
-    Bucket, CommentStatistics, Dataset, Project, Source, Stream, User,
+    Bucket, CommentStatistics, Dataset, LabelName, Project, Source, Stream, User,
 };
 use serde::{Serialize, Serializer};
 
@@ -35,9 +38,26 @@ where
     Ok(())
 }
 
+/// Writes each resource as a separate YAML document, i.e. as a stream of `---`-separated
+/// documents.
+pub fn print_resources_as_yaml<Resource>(
+    resources: impl IntoIterator<Item = Resource>,
+    mut writer: impl Write,
+) -> Result<()>
+where
+    Resource: Serialize,
+{
+    for resource in resources {
+        writeln!(writer, "---").context("Failed to write YAML document separator to writer.")?;
+        serde_yaml::to_writer(&mut writer, &resource).context("Could not serialise resource.")?;
+    }
+    Ok(())
+}
+
 #[derive(Copy, Clone, Default, Debug)]
 pub enum OutputFormat {
     Json,
+    Yaml,
     #[default]
     Table,
 }
@@ -50,6 +70,8 @@ impl FromStr for OutputFormat {
             Ok(OutputFormat::Table)
         } else if string == "json" {
             Ok(OutputFormat::Json)
+        } else if string == "yaml" {
+            Ok(OutputFormat::Yaml)
         } else {
             Err(anyhow!("{}", string))
         }
@@ -175,6 +197,70 @@ impl DisplayTable for DatasetAndStats {
     }
 }
 
+impl DisplayTable for CommentStatistics {
+    fn to_table_headers() -> Row {
+        row![bFg => "Comments", "Label", "Positive", "Negative"]
+    }
+
+    fn to_table_row(&self) -> Row {
+        let mut label_counts: Vec<_> = self.label_counts.iter().collect();
+        label_counts.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.0.cmp(&rhs.0));
+        let column = |value: fn(&(&LabelName, &LabelCount)) -> String| {
+            label_counts
+                .iter()
+                .map(value)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        row![
+            Thousands(self.num_comments.into_inner() as u64),
+            column(|(label, _)| label.0.clone()),
+            column(|(_, count)| count.positive.to_string()),
+            column(|(_, count)| count.negative.to_string()),
+        ]
+    }
+}
+
+impl DisplayTable for ValidationResponse {
+    fn to_table_headers() -> Row {
+        row![bFg => "Model Version", "Score", "Quality", "Reviewed", "Labels"]
+    }
+
+    fn to_table_row(&self) -> Row {
+        row![
+            self.validation.version,
+            self.validation.model_rating.score,
+            self.validation.model_rating.quality,
+            self.validation.reviewed_size,
+            self.label_groups
+                .iter()
+                .map(|group| group.label_defs.len())
+                .sum::<usize>(),
+        ]
+    }
+}
+
+impl DisplayTable for LabelValidation {
+    fn to_table_headers() -> Row {
+        row![bFg => "Threshold", "Precision", "Recall"]
+    }
+
+    fn to_table_row(&self) -> Row {
+        let column = |values: &[NotNan<f64>]| {
+            values
+                .iter()
+                .map(|value| format!("{value:.3}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        row![
+            column(&self.thresholds),
+            column(&self.precisions),
+            column(&self.recalls),
+        ]
+    }
+}
+
 impl DisplayTable for Project {
     fn to_table_headers() -> Row {
         row![bFg => "Name", "ID", "Title"]
@@ -413,7 +499,9 @@ impl DisplayTable for PrintableAuditEvent {
 
 /// Helper trait to allow collection of resources to be converted into a table.
 pub trait IntoTable {
-    fn into_table(self) -> Table;
+    /// Convert the resources into a table. If `columns` is provided, only those columns will be
+    /// rendered (in the given order), otherwise the default set of columns is used.
+    fn into_table(self, columns: Option<&[String]>) -> Result<Table>;
 }
 
 /// All iterators of resources can be converted into a table.
@@ -422,16 +510,65 @@ where
     Iterable: IntoIterator<Item = &'a Item>,
     Item: DisplayTable,
 {
-    fn into_table(self) -> Table {
+    fn into_table(self, columns: Option<&[String]>) -> Result<Table> {
+        let headers = Item::to_table_headers();
+        let indices = match columns {
+            Some(columns) => Some(select_column_indices(&headers, columns)?),
+            None => None,
+        };
+        let select = |row: Row| match &indices {
+            Some(indices) => Row::new(
+                indices
+                    .iter()
+                    .map(|index| row.get_cell(*index).cloned().unwrap_or_else(Cell::default))
+                    .collect(),
+            ),
+            None => row,
+        };
+
         let mut table = new_table();
-        table.set_titles(Item::to_table_headers());
+        table.set_titles(select(headers));
         for source in self.into_iter() {
-            table.add_row(source.to_table_row());
+            table.add_row(select(source.to_table_row()));
         }
-        table
+        Ok(table)
     }
 }
 
+/// Normalise a column name, such that e.g. "Updated (UTC)" and "Transform Tag" can be selected
+/// using `updated` and `transform-tag` respectively.
+fn column_key(name: &str) -> String {
+    let name = name.split('(').next().unwrap_or_default();
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+fn select_column_indices(headers: &Row, columns: &[String]) -> Result<Vec<usize>> {
+    let available: Vec<String> = headers
+        .iter()
+        .map(|cell| column_key(&cell.get_content()))
+        .collect();
+
+    columns
+        .iter()
+        .map(|column| {
+            let key = column_key(column);
+            available
+                .iter()
+                .position(|available| *available == key)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Unknown column `{}`. Available columns are: {}",
+                        column,
+                        available.join(", ")
+                    )
+                })
+        })
+        .collect()
+}
+
 fn new_table() -> Table {
     let mut table = Table::new();
     let format = format::FormatBuilder::new()
@@ -444,9 +581,10 @@ fn new_table() -> Table {
     table
 }
 
-fn print_table<T: IntoTable>(resources: T) {
-    let table = resources.into_table();
+fn print_table<T: IntoTable>(resources: T, columns: Option<&[String]>) -> Result<()> {
+    let table = resources.into_table(columns)?;
     table.printstd();
+    Ok(())
 }
 
 /// Print resources using the selected output format.
@@ -456,11 +594,12 @@ fn print_table<T: IntoTable>(resources: T) {
 #[derive(Default, Debug)]
 pub struct Printer {
     output: OutputFormat,
+    columns: Option<Vec<String>>,
 }
 
 impl Printer {
-    pub fn new(output: OutputFormat) -> Self {
-        Self { output }
+    pub fn new(output: OutputFormat, columns: Option<Vec<String>>) -> Self {
+        Self { output, columns }
     }
 
     pub fn print_resources<T, Resource>(&self, resources: T) -> Result<()>
@@ -469,9 +608,36 @@ impl Printer {
         Resource: Serialize,
     {
         match self.output {
-            OutputFormat::Table => print_table(resources),
+            OutputFormat::Table => print_table(resources, self.columns.as_deref())?,
             OutputFormat::Json => print_resources_as_json(resources, io::stdout().lock())?,
+            OutputFormat::Yaml => print_resources_as_yaml(resources, io::stdout().lock())?,
         };
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_key() {
+        assert_eq!(column_key("Updated (UTC)"), "updated");
+        assert_eq!(column_key("Transform Tag"), "transform-tag");
+        assert_eq!(column_key("transform-tag"), "transform-tag");
+        assert_eq!(column_key("ID"), "id");
+    }
+
+    #[test]
+    fn test_select_column_indices() {
+        let headers = row!["Name", "ID", "Updated (UTC)", "Transform Tag", "Title"];
+        assert_eq!(
+            select_column_indices(&headers, &["title".to_owned(), "Name".to_owned()]).unwrap(),
+            vec![4, 0]
+        );
+        assert!(select_column_indices(&headers, &["bucket".to_owned()])
+            .unwrap_err()
+            .to_string()
+            .contains("Available columns are: name, id, updated, transform-tag, title"));
+    }
+}
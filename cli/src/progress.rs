@@ -13,6 +13,14 @@ use crate::utils::LOG_PREFIX_INFO;
 
 pub type ProgressMessage = (u64, String);
 
+static PROGRESS_BARS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Globally disable progress bars, e.g. when running with `--quiet`. Any `Progress` created
+/// afterwards will not report anything.
+pub fn disable_progress_bars() {
+    PROGRESS_BARS_DISABLED.store(true, Ordering::SeqCst);
+}
+
 pub struct Options {
     pub bytes_units: bool,
 }
@@ -40,6 +48,13 @@ impl Progress {
         StatisticsT: Sync + Send + 'static,
     {
         let report_progress_flag = Arc::new(AtomicBool::new(true));
+        if PROGRESS_BARS_DISABLED.load(Ordering::SeqCst) {
+            return Progress {
+                report_progress_flag,
+                progress_thread: None,
+            };
+        }
+
         let progress_thread = spawn_progress_thread(
             Arc::clone(statistics),
             progress_fn,
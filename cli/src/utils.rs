@@ -1,16 +1,31 @@
-use anyhow::{Context, Result};
+use crate::args::LogFormat;
+use anyhow::{anyhow, Context, Error, Result};
+use chrono::Utc;
 use colored::{ColoredString, Colorize};
 use env_logger::{fmt::Formatter as LogFormatter, Builder as LogBuilder};
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
 use log::{Level as LogLevel, LevelFilter as LogLevelFilter, Record as LogRecord};
 use once_cell::sync::Lazy;
+use reinfer_client::{
+    Client, Dataset, DatasetFullName, DatasetIdentifier, ModelVersion, Source, SourceIdentifier,
+};
+use serde_json::json;
 use std::{
+    collections::HashMap,
     env,
-    io::{self, Write},
+    fmt::{self, Display},
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
     ops::Deref,
+    path::Path,
+    str::FromStr,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
 };
 
-pub fn init_env_logger(verbose: bool) {
-    let format = |formatter: &mut LogFormatter, record: &LogRecord<'_>| {
+pub fn init_env_logger(verbose: u8, quiet: bool, log_format: LogFormat) {
+    let human_format = |formatter: &mut LogFormatter, record: &LogRecord<'_>| {
         let level = match record.level() {
             LogLevel::Debug => LOG_PREFIX_DEBUG.deref(),
             LogLevel::Info => LOG_PREFIX_INFO.deref(),
@@ -21,15 +36,35 @@ pub fn init_env_logger(verbose: bool) {
         writeln!(formatter, "{} {}", level, record.args())
     };
 
+    let json_format = |formatter: &mut LogFormatter, record: &LogRecord<'_>| {
+        let line = json!({
+            "level": record.level().as_str(),
+            "timestamp": Utc::now().to_rfc3339(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        });
+        writeln!(formatter, "{line}")
+    };
+
     let mut builder = LogBuilder::new();
-    builder.format(format).filter(
+    match log_format {
+        LogFormat::Human => builder.format(human_format),
+        LogFormat::Json => builder.format(json_format),
+    };
+    builder.filter(
         None,
-        if verbose {
+        if quiet {
+            LogLevelFilter::Error
+        } else if verbose > 0 {
             LogLevelFilter::Debug
         } else {
             LogLevelFilter::Info
         },
     );
+    if !quiet && verbose > 1 {
+        // Only trace our own API client, the HTTP stack is far too noisy at trace level.
+        builder.filter(Some("reinfer_client"), LogLevelFilter::Trace);
+    }
 
     if env::var("RUST_LOG").is_ok() {
         builder.parse_filters(&env::var("RUST_LOG").unwrap());
@@ -75,9 +110,649 @@ pub fn read_token_from_stdin() -> Result<Option<String>> {
     Ok(if !input.is_empty() { Some(input) } else { None })
 }
 
+/// A file to write output to, gzip compressed if its path ends in `.gz`.
+pub enum OutputFile {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutputFile {
+    /// Flush the file, writing the gzip trailer if compressed. Dropping an `OutputFile` does this
+    /// too, but ignores any error.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut writer) => writer.flush(),
+            Self::Gzip(writer) => writer.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Exit code of a failed command, by the kind of error which caused it. Documented in the README.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Other = 1,
+    Auth = 3,
+    NotFound = 4,
+    Validation = 5,
+    Server = 6,
+    Network = 7,
+    Io = 8,
+    Transient = 9,
+}
+
+impl ExitCode {
+    /// The exit code for the first error in the chain which can be classified.
+    pub fn for_error(error: &anyhow::Error) -> Self {
+        // Retries running out takes priority over the error of the last attempt, as it is what
+        // tells callers that the command may succeed if re-run.
+        if error.chain().any(|cause| {
+            matches!(
+                cause.downcast_ref(),
+                Some(reinfer_client::Error::RetriesExhausted { .. })
+            )
+        }) {
+            return ExitCode::Transient;
+        }
+
+        error
+            .chain()
+            .find_map(|cause| {
+                if let Some(reinfer_client::Error::Api { status_code, .. }) = cause.downcast_ref() {
+                    Some(match status_code.as_u16() {
+                        401 | 403 => ExitCode::Auth,
+                        404 => ExitCode::NotFound,
+                        400..=499 => ExitCode::Validation,
+                        _ => ExitCode::Server,
+                    })
+                } else if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+                    (error.is_timeout() || error.is_connect()).then_some(ExitCode::Network)
+                } else if cause.is::<io::Error>() {
+                    Some(ExitCode::Io)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(ExitCode::Other)
+    }
+}
+
+/// Describe a failed command as JSON, with the chain of errors and the details of the first API
+/// error in it, if any.
+pub fn error_to_json(error: &anyhow::Error, exit_code: i32) -> serde_json::Value {
+    let api_error = error.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(reinfer_client::Error::Api {
+            status_code,
+            message,
+            request_id,
+        }) => Some(json!({
+            "status_code": status_code.as_u16(),
+            "message": message,
+            "request_id": request_id,
+        })),
+        _ => None,
+    });
+
+    json!({
+        "error": error.to_string(),
+        "causes": error.chain().map(ToString::to_string).collect::<Vec<_>>(),
+        "api_error": api_error,
+        "exit_code": exit_code,
+    })
+}
+
+/// Clamp a requested page size to `1..=max_page_size`, warning when it is out of range.
+pub fn clamp_page_size(page_size: Option<usize>, max_page_size: usize) -> Option<usize> {
+    page_size.map(|page_size| {
+        let clamped = page_size.clamp(1, max_page_size);
+        if clamped != page_size {
+            log::warn!(
+                "Page size {page_size} is out of range, using {clamped} (the maximum is \
+                 {max_page_size})"
+            );
+        }
+        clamped
+    })
+}
+
+/// A model version given on the command line, either as a number or as `latest` for the newest
+/// pinned model version of the dataset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelVersionArg {
+    Latest,
+    Version(u32),
+}
+
+impl FromStr for ModelVersionArg {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        if string == "latest" {
+            Ok(ModelVersionArg::Latest)
+        } else {
+            string.parse().map(ModelVersionArg::Version).map_err(|_| {
+                anyhow!(
+                    "invalid model version: '{}', expected a number or `latest`",
+                    string
+                )
+            })
+        }
+    }
+}
+
+impl ModelVersionArg {
+    pub fn resolve(self, client: &Client, dataset_name: &DatasetFullName) -> Result<ModelVersion> {
+        match self {
+            ModelVersionArg::Version(version) => Ok(ModelVersion(version)),
+            ModelVersionArg::Latest => {
+                let latest = client
+                    .get_labellers(dataset_name)
+                    .context("Operation to get the model versions of a dataset has failed.")?
+                    .into_iter()
+                    .map(|labeller| labeller.version)
+                    .max_by_key(|version| version.0)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Dataset `{}` does not have any pinned model versions",
+                            dataset_name.0
+                        )
+                    })?;
+                log::info!("Using latest model version {}", latest);
+                Ok(latest)
+            }
+        }
+    }
+}
+
+/// A source, dataset or bucket given on the command line, either in any form `T` accepts, such as
+/// an id or `owner/name`, or as a bare name in the project given by `--owner`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NameArg<T> {
+    Parsed(T),
+    Bare(String),
+}
+
+impl<T> FromStr for NameArg<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match T::from_str(string) {
+            Ok(parsed) => Ok(NameArg::Parsed(parsed)),
+            // Still check that the name is valid, as it will be once it has an owner
+            Err(_) if !string.contains('/') && T::from_str(&format!("owner/{string}")).is_ok() => {
+                Ok(NameArg::Bare(string.to_owned()))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+impl<T: Display> Display for NameArg<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameArg::Parsed(parsed) => parsed.fmt(formatter),
+            NameArg::Bare(name) => name.fmt(formatter),
+        }
+    }
+}
+
+/// Resolves the sources, datasets and buckets used by a command. Sources and datasets are cached
+/// by both id and name for the rest of the invocation, so that resolving the same identifier
+/// repeatedly only costs one round trip.
+pub struct Resolver {
+    owner: Option<String>,
+    sources: Mutex<HashMap<SourceIdentifier, Source>>,
+    datasets: Mutex<HashMap<DatasetIdentifier, Dataset>>,
+}
+
+impl Resolver {
+    /// `owner` is the project of resources given by bare name.
+    pub fn new(owner: Option<String>) -> Self {
+        Self {
+            owner,
+            sources: Mutex::default(),
+            datasets: Mutex::default(),
+        }
+    }
+
+    /// The identifier or name given by `arg`, with a bare name read as `<owner>/<name>`.
+    pub fn resolve<T>(&self, arg: &NameArg<T>) -> Result<T>
+    where
+        T: Clone + FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        match arg {
+            NameArg::Parsed(parsed) => Ok(parsed.clone()),
+            NameArg::Bare(name) => {
+                let owner = self.owner.as_ref().ok_or_else(|| {
+                    anyhow!("`{name}` has no owner, use `<owner>/{name}` or pass --owner")
+                })?;
+                Ok(T::from_str(&format!("{owner}/{name}"))?)
+            }
+        }
+    }
+
+    /// Get a source, suggesting similarly named sources in the error if it doesn't exist.
+    pub fn get_source(
+        &self,
+        client: &Client,
+        source: impl Into<SourceIdentifier>,
+    ) -> Result<Source> {
+        let source = source.into();
+        if let Some(cached) = self
+            .sources
+            .lock()
+            .expect("Source cache lock poisoned")
+            .get(&source)
+        {
+            return Ok(cached.clone());
+        }
+        let fetched = client
+            .get_source(source.clone())
+            .map_err(|error| match &source {
+                SourceIdentifier::FullName(full_name) if error.is_not_found() => {
+                    let suggestions = client
+                        .get_sources()
+                        .map(|sources| {
+                            let names: Vec<String> =
+                                sources.iter().map(|source| source.full_name().0).collect();
+                            closest_names(&full_name.0, &names)
+                        })
+                        .unwrap_or_default();
+                    not_found_error(error, "Source", &full_name.0, &suggestions)
+                }
+                _ => Error::new(error),
+            })?;
+        let mut cache = self.sources.lock().expect("Source cache lock poisoned");
+        cache.insert(SourceIdentifier::Id(fetched.id.clone()), fetched.clone());
+        cache.insert(fetched.full_name().into(), fetched.clone());
+        Ok(fetched)
+    }
+
+    /// Get a dataset, suggesting similarly named datasets in the error if it doesn't exist.
+    pub fn get_dataset(
+        &self,
+        client: &Client,
+        dataset: impl Into<DatasetIdentifier>,
+    ) -> Result<Dataset> {
+        let dataset = dataset.into();
+        if let Some(cached) = self
+            .datasets
+            .lock()
+            .expect("Dataset cache lock poisoned")
+            .get(&dataset)
+        {
+            return Ok(cached.clone());
+        }
+        let fetched = client
+            .get_dataset(dataset.clone())
+            .map_err(|error| match &dataset {
+                DatasetIdentifier::FullName(full_name) if error.is_not_found() => {
+                    let suggestions = client
+                        .get_datasets()
+                        .map(|datasets| {
+                            let names: Vec<String> = datasets
+                                .iter()
+                                .map(|dataset| dataset.full_name().0)
+                                .collect();
+                            closest_names(&full_name.0, &names)
+                        })
+                        .unwrap_or_default();
+                    not_found_error(error, "Dataset", &full_name.0, &suggestions)
+                }
+                _ => Error::new(error),
+            })?;
+        let mut cache = self.datasets.lock().expect("Dataset cache lock poisoned");
+        cache.insert(DatasetIdentifier::Id(fetched.id.clone()), fetched.clone());
+        cache.insert(fetched.full_name().into(), fetched.clone());
+        Ok(fetched)
+    }
+}
+
+fn not_found_error(
+    error: reinfer_client::Error,
+    kind: &str,
+    name: &str,
+    suggestions: &[String],
+) -> Error {
+    let message = if suggestions.is_empty() {
+        format!("{} `{}` does not exist", kind, name)
+    } else {
+        format!(
+            "{} `{}` does not exist, did you mean {}?",
+            kind,
+            name,
+            suggestions
+                .iter()
+                .map(|suggestion| format!("`{}`", suggestion))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    Error::new(error).context(message)
+}
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The names closest to `name` by edit distance, ignoring any which are too different to be a
+/// plausible typo.
+pub fn closest_names(name: &str, candidates: &[String]) -> Vec<String> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &String)> = candidates
+        .iter()
+        .map(|candidate| (levenshtein(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+fn levenshtein(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+/// How long a command took and the throughput of each kind of item it processed, e.g. `Finished
+/// in 12.5s: 1000 comments (80.0/s), 10 annotations (0.8/s)`.
+pub fn throughput_summary(elapsed: Duration, counts: &[(usize, &str)]) -> String {
+    let seconds = elapsed.as_secs_f64();
+    let rates = counts
+        .iter()
+        .map(|(count, noun)| {
+            let rate = if seconds > 0.0 {
+                *count as f64 / seconds
+            } else {
+                0.0
+            };
+            format!("{count} {noun} ({rate:.1}/s)")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Finished in {seconds:.1}s: {rates}")
+}
+
+/// Calls `is_done` every couple of seconds until it returns `true`, for at most `timeout`.
+/// Returns whether it completed before the timeout.
+pub fn poll_until(timeout: Duration, mut is_done: impl FnMut() -> Result<bool>) -> Result<bool> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    let start = Instant::now();
+    loop {
+        if is_done()? {
+            return Ok(true);
+        }
+        if start.elapsed() >= timeout {
+            return Ok(false);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+pub fn is_gzip_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+/// Create (or truncate) an output file, compressing on the fly if the path ends in `.gz`.
+pub fn create_output_file(path: &Path) -> Result<OutputFile> {
+    open_output_file(path, false)
+}
+
+/// Open an output file, compressing on the fly if the path ends in `.gz`. When appending to a
+/// gzip file a new gzip member is started, which decompressors read as a continuation.
+pub fn open_output_file(path: &Path, append: bool) -> Result<OutputFile> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .with_context(|| format!("Could not open file for writing `{}`", path.display()))
+        .map(BufWriter::new)?;
+
+    Ok(if is_gzip_path(path) {
+        OutputFile::Gzip(GzEncoder::new(file, Compression::default()))
+    } else {
+        OutputFile::Plain(file)
+    })
+}
+
+/// A file to read input from, decompressed on the fly if its path ends in `.gz`.
+pub enum InputFile {
+    Plain(File),
+    Gzip(MultiGzDecoder<File>),
+}
+
+impl Read for InputFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(reader) => reader.read(buf),
+            Self::Gzip(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Open an input file, decompressing on the fly if the path ends in `.gz`. Compressed files are
+/// not seekable, so to read such a file twice it must be opened again.
+pub fn open_input_file(path: &Path) -> Result<BufReader<InputFile>> {
+    let file =
+        File::open(path).with_context(|| format!("Could not open file `{}`", path.display()))?;
+
+    Ok(BufReader::new(if is_gzip_path(path) {
+        InputFile::Gzip(MultiGzDecoder::new(file))
+    } else {
+        InputFile::Plain(file)
+    }))
+}
+
 pub static LOG_PREFIX_DEBUG: Lazy<ColoredString> = Lazy::new(|| "D".normal());
 pub static LOG_PREFIX_INFO: Lazy<ColoredString> = Lazy::new(|| "I".green());
 pub static LOG_PREFIX_WARN: Lazy<ColoredString> = Lazy::new(|| "W".yellow().bold());
 pub static LOG_PREFIX_ERROR: Lazy<ColoredString> = Lazy::new(|| "E".red().bold());
 pub static LOG_PREFIX_TRACE: Lazy<ColoredString> = Lazy::new(|| "T".normal());
 pub static LOG_PREFIX_INPUT: Lazy<ColoredString> = Lazy::new(|| "*".blue().bold());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_for_error() {
+        let api_error = |status_code| {
+            anyhow::Error::new(reinfer_client::Error::Api {
+                status_code,
+                message: String::new(),
+                request_id: None,
+            })
+            .context("Operation has failed.")
+        };
+        assert_eq!(
+            ExitCode::for_error(&api_error(reqwest::StatusCode::FORBIDDEN)),
+            ExitCode::Auth
+        );
+        assert_eq!(
+            ExitCode::for_error(&api_error(reqwest::StatusCode::NOT_FOUND)),
+            ExitCode::NotFound
+        );
+        assert_eq!(
+            ExitCode::for_error(&api_error(reqwest::StatusCode::UNPROCESSABLE_ENTITY)),
+            ExitCode::Validation
+        );
+        assert_eq!(
+            ExitCode::for_error(&api_error(reqwest::StatusCode::BAD_GATEWAY)),
+            ExitCode::Server
+        );
+        assert_eq!(
+            ExitCode::for_error(
+                &anyhow::Error::new(reinfer_client::Error::RetriesExhausted {
+                    retries: 3,
+                    source: Box::new(reinfer_client::Error::Api {
+                        status_code: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                        message: String::new(),
+                        request_id: None,
+                    }),
+                })
+                .context("Operation has failed.")
+            ),
+            ExitCode::Transient
+        );
+        assert_eq!(
+            ExitCode::for_error(
+                &anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound)).context("Open")
+            ),
+            ExitCode::Io
+        );
+        assert_eq!(
+            ExitCode::for_error(&anyhow::anyhow!("Invalid argument")),
+            ExitCode::Other
+        );
+    }
+
+    #[test]
+    fn test_error_to_json() {
+        let error = anyhow::Error::new(reinfer_client::Error::Api {
+            status_code: reqwest::StatusCode::NOT_FOUND,
+            message: "Not found".to_owned(),
+            request_id: Some("abc123".to_owned()),
+        })
+        .context("Operation to get source has failed.");
+
+        let value = error_to_json(&error, 1);
+        assert_eq!(value["error"], "Operation to get source has failed.");
+        assert_eq!(value["causes"].as_array().unwrap().len(), 2);
+        assert_eq!(value["api_error"]["status_code"], 404);
+        assert_eq!(value["api_error"]["request_id"], "abc123");
+        assert_eq!(value["exit_code"], 1);
+    }
+
+    #[test]
+    fn test_parse_model_version_arg() {
+        assert_eq!(
+            ModelVersionArg::from_str("latest").unwrap(),
+            ModelVersionArg::Latest
+        );
+        assert_eq!(
+            ModelVersionArg::from_str("12").unwrap(),
+            ModelVersionArg::Version(12)
+        );
+        assert!(ModelVersionArg::from_str("-1").is_err());
+    }
+
+    #[test]
+    fn test_resolve_name_arg() {
+        let bare = NameArg::<SourceIdentifier>::from_str("emails").unwrap();
+        assert_eq!(bare, NameArg::Bare("emails".to_owned()));
+        assert!(Resolver::new(None).resolve(&bare).is_err());
+        assert_eq!(
+            Resolver::new(Some("acme".to_owned()))
+                .resolve(&bare)
+                .unwrap(),
+            SourceIdentifier::FullName(reinfer_client::SourceFullName("acme/emails".to_owned()))
+        );
+
+        let full_name = NameArg::<SourceIdentifier>::from_str("other/emails").unwrap();
+        assert_eq!(
+            Resolver::new(Some("acme".to_owned()))
+                .resolve(&full_name)
+                .unwrap(),
+            SourceIdentifier::FullName(reinfer_client::SourceFullName("other/emails".to_owned()))
+        );
+        assert!(NameArg::<SourceIdentifier>::from_str("a/b/c").is_err());
+    }
+
+    #[test]
+    fn test_clamp_page_size() {
+        assert_eq!(clamp_page_size(None, 256), None);
+        assert_eq!(clamp_page_size(Some(100), 256), Some(100));
+        assert_eq!(clamp_page_size(Some(1000), 256), Some(256));
+        assert_eq!(clamp_page_size(Some(0), 256), Some(1));
+    }
+
+    #[test]
+    fn test_throughput_summary() {
+        assert_eq!(
+            throughput_summary(
+                Duration::from_millis(12_500),
+                &[(1000, "comments"), (10, "annotations")]
+            ),
+            "Finished in 12.5s: 1000 comments (80.0/s), 10 annotations (0.8/s)"
+        );
+        assert_eq!(
+            throughput_summary(Duration::ZERO, &[(0, "emails")]),
+            "Finished in 0.0s: 0 emails (0.0/s)"
+        );
+    }
+
+    #[test]
+    fn test_closest_names() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+
+        let candidates: Vec<String> = ["acme/emails", "acme/email-archive", "other/tickets"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(
+            closest_names("acme/emials", &candidates),
+            vec!["acme/emails".to_owned()]
+        );
+        assert_eq!(
+            closest_names("Acme/Emails", &candidates),
+            vec!["acme/emails".to_owned()]
+        );
+        assert!(closest_names("nothing/similar", &candidates).is_empty());
+    }
+
+    #[test]
+    fn test_gzip_round_trip_with_append() {
+        let path = env::temp_dir().join(format!("re-gzip-test-{}.jsonl.gz", uuid::Uuid::new_v4()));
+
+        let mut file = create_output_file(&path).unwrap();
+        file.write_all(b"first\n").unwrap();
+        file.finish().unwrap();
+        let mut file = open_output_file(&path, true).unwrap();
+        file.write_all(b"second\n").unwrap();
+        file.finish().unwrap();
+
+        let mut contents = String::new();
+        open_input_file(&path)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "first\nsecond\n");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}